@@ -22,6 +22,10 @@ pub struct AddPallet {
     #[interactive_clap(skip_default_input_arg)]
     /// What is the source of the pallet you'd like to install?
     source: PalletSource,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Index of the pallet in `construct_runtime!`, the next free one by default
+    index: String,
 }
 
 #[derive(Debug, Clone)]
@@ -95,6 +99,10 @@ impl AddPallet {
             PalletSourceDiscriminants::CustomRegistry => Ok(Some(PalletSource::CustomRegistry)),
         }
     }
+
+    fn input_index(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        Ok(Some(String::new()))
+    }
 }
 
 impl AddPalletContext {
@@ -102,6 +110,16 @@ impl AddPalletContext {
         previous_context: GlobalContext,
         scope: &<AddPallet as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
     ) -> color_eyre::eyre::Result<Self> {
+        let index = if scope.index.is_empty() {
+            None
+        } else {
+            match scope.index.parse::<u8>() {
+                Ok(index) => Some(index),
+                Err(_) => {
+                    color_eyre::eyre::bail!("`--index` must be a number from 0 to 255, got `{}`", scope.index);
+                }
+            }
+        };
         let features = parse_features(&scope.features)
             .map(|f| f.to_string())
             .collect::<Vec<String>>();
@@ -154,6 +172,8 @@ impl AddPalletContext {
                 features,
                 default_features: Some(false),
                 source,
+                config_items: Vec::new(),
+                index,
             };
 
             if let Err(e) = ops::add_pallet(&opts, &previous_context.config) {
@@ -162,7 +182,7 @@ impl AddPalletContext {
 
             Ok(Self)
         } else {
-            color_eyre::eyre::bail!("Incorrect project type")
+            color_eyre::eyre::bail!("Incorrect project type");
         }
     }
 }
@@ -0,0 +1,145 @@
+use inquire::{list_option::ListOption, validator::Validation, MultiSelect};
+use substrate_manager::{
+    ops::substrate_bench::{
+        self, build_benchmark_node, list_benchmarks, local_pallets, BenchOptions, DEFAULT_REPEAT,
+        DEFAULT_STEPS,
+    },
+    util::config::ProjectType,
+};
+
+use super::GlobalContext;
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = BenchContext)]
+pub struct Bench {
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Pallets to benchmark, separated by commas, e.g. pallet_template
+    pallet: String,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Number of steps of the benchmarks' components, 50 by default
+    steps: String,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Number of times each benchmark is repeated, 20 by default
+    repeat: String,
+}
+
+impl Bench {
+    fn input_pallet(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        // Prompted once the node is built and its benchmarks are known
+        Ok(Some(String::new()))
+    }
+
+    fn input_steps(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        Ok(Some(String::new()))
+    }
+
+    fn input_repeat(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        Ok(Some(String::new()))
+    }
+}
+
+fn parse_count(value: &str, name: &str, default: u64) -> color_eyre::eyre::Result<u64> {
+    if value.is_empty() {
+        return Ok(default);
+    }
+    match value.parse::<u64>() {
+        Ok(count) if count > 0 => Ok(count),
+        _ => {
+            color_eyre::eyre::bail!("`--{}` must be a positive number, got `{}`", name, value);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchContext;
+
+impl BenchContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<Bench as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        if let ProjectType::Chain(chain_info) = &previous_context.config.project_type.clone().unwrap()
+        {
+            let config = &previous_context.config;
+            let steps = parse_count(&scope.steps, "steps", DEFAULT_STEPS)?;
+            let repeat = parse_count(&scope.repeat, "repeat", DEFAULT_REPEAT)?;
+
+            let binary = build_benchmark_node(config, chain_info).map_err(|e| color_eyre::eyre::eyre!(e))?;
+            let benchmarks = list_benchmarks(&binary).map_err(|e| color_eyre::eyre::eyre!(e))?;
+            if benchmarks.is_empty() {
+                color_eyre::eyre::bail!("the runtime has no benchmarks, check its `define_benchmarks!`");
+            }
+
+            let pallets = if scope.pallet.is_empty() {
+                let local_pallets = local_pallets(config, chain_info).map_err(|e| color_eyre::eyre::eyre!(e))?;
+                let variants = benchmarks
+                    .iter()
+                    .map(|benchmark| {
+                        format!("{} ({} benchmarks)", benchmark.pallet, benchmark.extrinsics.len())
+                    })
+                    .collect::<Vec<_>>();
+                // The pallets of the workspace are the ones whose weights usually need updating
+                let defaults = benchmarks
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, benchmark)| local_pallets.iter().any(|(name, _)| *name == benchmark.pallet))
+                    .map(|(index, _)| index)
+                    .collect::<Vec<_>>();
+                let selected = MultiSelect::new("Which pallets do you want to benchmark?", variants)
+                    .with_default(&defaults)
+                    .with_validator(|selected: &[ListOption<&String>]| {
+                        if selected.is_empty() {
+                            Ok(Validation::Invalid("Select at least one pallet".into()))
+                        } else {
+                            Ok(Validation::Valid)
+                        }
+                    })
+                    .raw_prompt()?;
+                selected
+                    .iter()
+                    .map(|selected| benchmarks[selected.index].pallet.clone())
+                    .collect()
+            } else {
+                let pallets = scope
+                    .pallet
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|pallet| !pallet.is_empty())
+                    .map(str::to_string)
+                    .collect::<Vec<_>>();
+                if let Some(unknown) = pallets
+                    .iter()
+                    .find(|pallet| !benchmarks.iter().any(|benchmark| &benchmark.pallet == *pallet))
+                {
+                    let names = benchmarks
+                        .iter()
+                        .map(|benchmark| benchmark.pallet.as_str())
+                        .collect::<Vec<_>>();
+                    color_eyre::eyre::bail!(
+                        "`{}` has no benchmarks, expected one of: {}",
+                        unknown,
+                        names.join(", ")
+                    );
+                }
+                pallets
+            };
+
+            let opts = BenchOptions {
+                pallets,
+                steps,
+                repeat,
+            };
+            if let Err(e) = substrate_bench::bench(&opts, config, chain_info, &binary) {
+                return Err(color_eyre::eyre::eyre!(e));
+            }
+
+            Ok(Self)
+        } else {
+            color_eyre::eyre::bail!("Incorrect project type");
+        }
+    }
+}
@@ -1,23 +1,111 @@
-use substrate_manager::{ops, util::Config};
+use std::path::PathBuf;
+
+use inquire::MultiSelect;
+use substrate_manager::{
+    ops::substrate_build::{build_contracts, ContractBuildOptions},
+    util::config::ProjectType,
+};
 
 use super::GlobalContext;
 
+/// Prompts for some contracts of a workspace, returning their comma separated names.
+///
+/// Nothing is prompted outside of contract workspaces, and an empty selection means all contracts.
+pub fn input_contracts(
+    context: &GlobalContext,
+    message: &str,
+) -> color_eyre::eyre::Result<Option<String>> {
+    let Some(ProjectType::Contract(info)) = &context.config.project_type else {
+        return Ok(Some(String::new()));
+    };
+    if !info.workspace {
+        return Ok(Some(String::new()));
+    }
+
+    let names = info
+        .contracts
+        .iter()
+        .map(|contract| contract.name.clone())
+        .collect::<Vec<_>>();
+    let all = (0..names.len()).collect::<Vec<_>>();
+    let selected = MultiSelect::new(message, names.clone())
+        .with_default(&all)
+        .prompt()?;
+    if selected.is_empty() || selected.len() == names.len() {
+        return Ok(Some(String::new()));
+    }
+    Ok(Some(selected.join(",")))
+}
+
 #[derive(Debug, Clone, interactive_clap::InteractiveClap)]
 #[interactive_clap(input_context = GlobalContext)]
 #[interactive_clap(output_context = BuildContext)]
-pub struct Build;
+pub struct Build {
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Contracts of the workspace to build, separated by commas
+    package: String,
+    #[interactive_clap(long)]
+    /// Build with optimizations
+    release: bool,
+    #[interactive_clap(long)]
+    /// Build in a docker container so that the contract's code can be verified
+    verifiable: bool,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Path to the contract's Cargo.toml
+    manifest_path: String,
+    #[interactive_clap(long)]
+    /// Print cargo-contract's build result as JSON
+    output_json: bool,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Directory for all generated artifacts
+    target_dir: String,
+}
+
+impl Build {
+    fn input_package(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_contracts(context, "Which contracts do you want to build?")
+    }
+
+    fn input_manifest_path(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        // Defaults to the contract in the current directory, only set through `--manifest-path`
+        Ok(Some(String::new()))
+    }
+
+    fn input_target_dir(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        Ok(Some(String::new()))
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct BuildContext;
 
 impl BuildContext {
     pub fn from_previous_context(
-        _previous_context: GlobalContext,
-        _scope: &<Build as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+        previous_context: GlobalContext,
+        scope: &<Build as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
     ) -> color_eyre::eyre::Result<Self> {
-        if let Err(e) = ops::build() {
-            return Err(color_eyre::eyre::eyre!(e));
+        let opts = ContractBuildOptions {
+            release: scope.release,
+            verifiable: scope.verifiable,
+            manifest_path: (!scope.manifest_path.is_empty())
+                .then(|| PathBuf::from(&scope.manifest_path)),
+            output_json: scope.output_json,
+            target_dir: (!scope.target_dir.is_empty()).then(|| PathBuf::from(&scope.target_dir)),
+        };
+        if let Some(ProjectType::Contract(info)) = &previous_context.config.project_type {
+            let contracts = info
+                .select(&scope.package)
+                .map_err(|e| color_eyre::eyre::eyre!(e))?;
+            if let Err(e) = build_contracts(&opts, &contracts, &previous_context.config) {
+                return Err(color_eyre::eyre::eyre!(e));
+            }
+
+            Ok(Self)
+        } else {
+            color_eyre::eyre::bail!("Incorrect project type");
         }
-        Ok(Self)
     }
 }
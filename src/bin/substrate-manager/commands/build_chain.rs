@@ -0,0 +1,121 @@
+use inquire::Select;
+use strum::{EnumDiscriminants, EnumIter, EnumMessage, IntoEnumIterator};
+use substrate_manager::{
+    ops::substrate_build::{build_chain, build_profiles, BuildTarget, ChainBuildOptions},
+    util::config::ProjectType,
+};
+
+use super::GlobalContext;
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = BuildChainContext)]
+pub struct BuildChain {
+    #[interactive_clap(value_enum)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// What do you want to build?
+    target: ChainBuildTarget,
+    #[interactive_clap(skip_default_input_arg)]
+    /// Which profile do you want to build with?
+    profile: String,
+    #[interactive_clap(long)]
+    /// Copy the runtime wasm to the artifacts/ directory
+    artifacts: bool,
+    #[interactive_clap(long)]
+    /// Build the runtime reproducibly in an srtool container
+    deterministic: bool,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Blake2-256 hash, or srtool JSON report, the runtime wasm must match
+    verify: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct BuildChainContext;
+
+#[derive(Debug, Clone, EnumDiscriminants, clap::ValueEnum)]
+#[strum_discriminants(derive(EnumMessage, EnumIter))]
+pub enum ChainBuildTarget {
+    #[strum_discriminants(strum(message = "node      - Build the node, including its runtime"))]
+    Node,
+    #[strum_discriminants(strum(message = "runtime   - Build the runtime wasm only"))]
+    Runtime,
+}
+
+impl interactive_clap::ToCli for ChainBuildTarget {
+    type CliVariant = ChainBuildTarget;
+}
+impl std::str::FromStr for ChainBuildTarget {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "node" => Ok(Self::Node),
+            "runtime" => Ok(Self::Runtime),
+            _ => Err("ChainBuildTarget: incorrect value entered".to_string()),
+        }
+    }
+}
+impl std::fmt::Display for ChainBuildTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Node => write!(f, "node"),
+            Self::Runtime => write!(f, "runtime"),
+        }
+    }
+}
+impl std::fmt::Display for ChainBuildTargetDiscriminants {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let msg = self.get_message().unwrap();
+        write!(f, "{msg}")
+    }
+}
+
+impl BuildChain {
+    fn input_target(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<ChainBuildTarget>> {
+        let variants = ChainBuildTargetDiscriminants::iter().collect::<Vec<_>>();
+        let selected = Select::new("What do you want to build?", variants).prompt()?;
+        match selected {
+            ChainBuildTargetDiscriminants::Node => Ok(Some(ChainBuildTarget::Node)),
+            ChainBuildTargetDiscriminants::Runtime => Ok(Some(ChainBuildTarget::Runtime)),
+        }
+    }
+
+    fn input_profile(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        let profiles = build_profiles(&context.config);
+        let profile = Select::new("Which profile do you want to build with?", profiles).prompt()?;
+        Ok(Some(profile))
+    }
+
+    fn input_verify(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        // Verification is opt-in through `--verify`, don't prompt for it
+        Ok(Some(String::new()))
+    }
+}
+
+impl BuildChainContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<BuildChain as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        if let ProjectType::Chain(chain_info) = &previous_context.config.project_type.clone().unwrap()
+        {
+            let opts = ChainBuildOptions {
+                target: match scope.target {
+                    ChainBuildTarget::Node => BuildTarget::Node,
+                    ChainBuildTarget::Runtime => BuildTarget::Runtime,
+                },
+                profile: scope.profile.clone(),
+                copy_artifacts: scope.artifacts,
+                deterministic: scope.deterministic,
+                verify: (!scope.verify.is_empty()).then(|| scope.verify.clone()),
+            };
+            if let Err(e) = build_chain(&opts, &previous_context.config, chain_info) {
+                return Err(color_eyre::eyre::eyre!(e));
+            }
+
+            Ok(Self)
+        } else {
+            color_eyre::eyre::bail!("Incorrect project type");
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use inquire::Select;
+use substrate_manager::ops::{
+    substrate_call::{self, CallOptions},
+    substrate_deploy::contract_bundle,
+};
+
+use super::{
+    deploy::{input_contract, input_suri, input_url, prompt_args, secret_uri, ContractContext},
+    GlobalContext,
+};
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = CallContext)]
+pub struct Call {
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Contract of the workspace to call
+    package: String,
+    #[interactive_clap(named_arg)]
+    /// Which message do you want to call?
+    message: CallMessage,
+}
+
+impl Call {
+    fn input_package(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_contract(context, "Which contract do you want to call?")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CallContext(ContractContext);
+
+impl CallContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<Call as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        Ok(Self(ContractContext::new(previous_context, &scope.package)?))
+    }
+}
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = CallContext)]
+#[interactive_clap(output_context = CallMessageContext)]
+pub struct CallMessage {
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// What's the RPC endpoint of the node?
+    url: String,
+    #[interactive_clap(skip_default_input_arg)]
+    /// Which message do you want to call?
+    message: String,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Arguments of the message, separated by spaces
+    args: String,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Which account should sign the transaction?
+    suri: String,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Address of the contract, defaults to its last deployment on the network
+    contract: String,
+}
+
+impl CallMessage {
+    fn input_url(context: &CallContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_url(&context.0.config)
+    }
+
+    fn input_message(context: &CallContext) -> color_eyre::eyre::Result<Option<String>> {
+        let bundle = contract_bundle(&context.0.config, &context.0.contract)
+            .map_err(|e| color_eyre::eyre::eyre!(e))?;
+        let messages = bundle.messages();
+        let variants = messages
+            .iter()
+            .map(|message| {
+                let kind = if message.mutates { "transaction" } else { "query" };
+                format!("{} ({})", message.signature(), kind)
+            })
+            .collect::<Vec<_>>();
+        let selected = Select::new("Which message do you want to call?", variants).raw_prompt()?;
+        Ok(Some(messages[selected.index].label.clone()))
+    }
+
+    fn input_args(_context: &CallContext) -> color_eyre::eyre::Result<Option<String>> {
+        // Prompted for each argument of the selected message, unless passed with `--args`
+        Ok(Some(String::new()))
+    }
+
+    fn input_suri(_context: &CallContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_suri()
+    }
+
+    fn input_contract(_context: &CallContext) -> color_eyre::eyre::Result<Option<String>> {
+        Ok(Some(String::new()))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CallMessageContext;
+
+impl CallMessageContext {
+    pub fn from_previous_context(
+        previous_context: CallContext,
+        scope: &<CallMessage as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        let ContractContext { config, contract } = previous_context.0;
+        let mut args = shell_words::split(&scope.args)?;
+        if args.is_empty() {
+            let bundle = contract_bundle(&config, &contract).map_err(|e| color_eyre::eyre::eyre!(e))?;
+            if let Some(message) = bundle
+                .messages()
+                .into_iter()
+                .find(|message| message.label == scope.message)
+            {
+                args = prompt_args(&message.args)?;
+            }
+        }
+
+        let opts = CallOptions {
+            contract,
+            url: scope.url.clone(),
+            address: (!scope.contract.is_empty()).then(|| scope.contract.clone()),
+            message: scope.message.clone(),
+            args,
+            suri: secret_uri(&scope.suri)?,
+        };
+        if let Err(e) = substrate_call::call(&opts, &config) {
+            return Err(color_eyre::eyre::eyre!(e));
+        }
+        Ok(Self)
+    }
+}
@@ -1,21 +1,328 @@
-use substrate_manager::{ops, util::Config};
+use inquire::{Password, PasswordDisplayMode, Select, Text};
+use strum::{EnumDiscriminants, EnumIter, EnumMessage};
+use substrate_manager::{
+    core::contract_bundle::ContractArg,
+    ops::{
+        self,
+        substrate_contracts_node::running_node_url,
+        substrate_deploy::{
+            self, contract_bundle, open_contracts_ui, DeployOptions, UploadOptions, DEFAULT_RPC_URL,
+            DEV_ACCOUNTS,
+        },
+    },
+    util::{
+        config::{ContractMember, ProjectType},
+        Config,
+    },
+};
 
 use super::GlobalContext;
 
+const CUSTOM_SURI: &str = "custom secret URI…";
+
+/// `--suri` value standing for a custom secret URI, which is never part of the command line.
+const CUSTOM_SURI_ARG: &str = "custom";
+
+/// Environment variable holding the secret URI of `--suri custom`, for scripts.
+const SURI_ENV: &str = "SUBSTRATE_MANAGER_SURI";
+
+/// Prompts for the node's RPC endpoint, defaulting to the project's running contracts node.
+pub fn input_url(config: &Config) -> color_eyre::eyre::Result<Option<String>> {
+    let mut prompt = Text::new("What's the RPC endpoint of the node?");
+    let running_node = running_node_url(config);
+    if let Some(url) = &running_node {
+        prompt = prompt
+            .with_default(url)
+            .with_help_message("A local contracts node is running on this endpoint");
+    } else {
+        prompt = prompt.with_default(DEFAULT_RPC_URL);
+    }
+    Ok(Some(prompt.prompt()?))
+}
+
+/// Prompts for the signer, one of the dev accounts or a custom secret URI.
+pub fn input_suri() -> color_eyre::eyre::Result<Option<String>> {
+    let mut variants = DEV_ACCOUNTS.to_vec();
+    variants.push(CUSTOM_SURI);
+    let selected = Select::new("Which account should sign the transaction?", variants).prompt()?;
+    if selected == CUSTOM_SURI {
+        // Only the placeholder is kept, the secret is asked for once the command runs
+        return Ok(Some(CUSTOM_SURI_ARG.to_string()));
+    }
+    Ok(Some(selected.to_string()))
+}
+
+/// The secret URI of the signer given by `--suri`. For `custom`, it is read from
+/// `$SUBSTRATE_MANAGER_SURI` or else prompted for, so that it doesn't show in the console command.
+pub fn secret_uri(suri: &str) -> color_eyre::eyre::Result<String> {
+    if suri != CUSTOM_SURI_ARG {
+        return Ok(suri.to_string());
+    }
+    if let Ok(suri) = std::env::var(SURI_ENV) {
+        return Ok(suri);
+    }
+
+    let suri = Password::new("Secret URI of the account:")
+        .with_display_mode(PasswordDisplayMode::Masked)
+        .without_confirmation()
+        .prompt()?;
+    Ok(suri)
+}
+
+/// Prompts for each argument of a constructor or message.
+pub fn prompt_args(args: &[ContractArg]) -> color_eyre::eyre::Result<Vec<String>> {
+    args.iter()
+        .map(|arg| Ok(Text::new(&format!("{}: {}", arg.label, arg.type_name)).prompt()?))
+        .collect()
+}
+
+/// Prompts for one contract of a workspace, nothing is prompted for single contract projects.
+pub fn input_contract(
+    context: &GlobalContext,
+    message: &str,
+) -> color_eyre::eyre::Result<Option<String>> {
+    match &context.config.project_type {
+        Some(ProjectType::Contract(info)) if info.workspace => {
+            let names = info
+                .contracts
+                .iter()
+                .map(|contract| contract.name.clone())
+                .collect::<Vec<_>>();
+            Ok(Some(Select::new(message, names).prompt()?))
+        }
+        _ => Ok(Some(String::new())),
+    }
+}
+
+/// The context of commands that target a single contract of the project.
+#[derive(Debug, Clone)]
+pub struct ContractContext {
+    pub config: Config,
+    pub contract: ContractMember,
+}
+
+impl ContractContext {
+    pub fn new(previous_context: GlobalContext, package: &str) -> color_eyre::eyre::Result<Self> {
+        let Some(ProjectType::Contract(info)) = &previous_context.config.project_type else {
+            color_eyre::eyre::bail!("Incorrect project type");
+        };
+        let contract = info
+            .contract(package)
+            .map_err(|e| color_eyre::eyre::eyre!(e))?
+            .clone();
+
+        Ok(Self {
+            config: previous_context.config,
+            contract,
+        })
+    }
+}
+
 #[derive(Debug, Clone, interactive_clap::InteractiveClap)]
 #[interactive_clap(input_context = GlobalContext)]
 #[interactive_clap(output_context = DeployContext)]
-pub struct Deploy;
+pub struct Deploy {
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Contract of the workspace to deploy
+    package: String,
+    #[interactive_clap(subcommand)]
+    command: DeployCommand,
+}
+
+impl Deploy {
+    fn input_package(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_contract(context, "Which contract do you want to deploy?")
+    }
+}
 
 #[derive(Debug, Clone)]
-pub struct DeployContext;
+pub struct DeployContext(ContractContext);
 
 impl DeployContext {
     pub fn from_previous_context(
-        _previous_context: GlobalContext,
-        _scope: &<Deploy as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+        previous_context: GlobalContext,
+        scope: &<Deploy as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        Ok(Self(ContractContext::new(previous_context, &scope.package)?))
+    }
+}
+
+#[derive(Debug, EnumDiscriminants, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(context = DeployContext)]
+#[strum_discriminants(derive(EnumMessage, EnumIter))]
+#[non_exhaustive]
+#[expect(dead_code, reason = "interactive_clap only builds the `Cli` mirror of the menu")]
+/// Where do you want to deploy your contract?
+pub enum DeployCommand {
+    /// Upload and instantiate the contract on a node through cargo-contract
+    #[strum_discriminants(strum(message = "node    - 🚀 Instantiate the contract on a node"))]
+    Node(DeployNode),
+    /// Only upload the contract's code, to instantiate it later or from another contract
+    #[strum_discriminants(strum(message = "upload  - 📤 Only upload the contract's code to a node"))]
+    Upload(UploadCode),
+    /// Open the Substrate Contracts UI to deploy the contract from your browser
+    #[strum_discriminants(strum(message = "ui      - 🌐 Deploy using Substrate Contracts UI"))]
+    Ui(DeployUi),
+}
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = DeployContext)]
+#[interactive_clap(output_context = DeployNodeContext)]
+pub struct DeployNode {
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// What's the RPC endpoint of the node?
+    url: String,
+    #[interactive_clap(skip_default_input_arg)]
+    /// Which constructor do you want to instantiate the contract with?
+    constructor: String,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Arguments of the constructor, separated by spaces
+    args: String,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Which account should sign the transaction?
+    suri: String,
+}
+
+impl DeployNode {
+    fn input_url(context: &DeployContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_url(&context.0.config)
+    }
+
+    fn input_constructor(context: &DeployContext) -> color_eyre::eyre::Result<Option<String>> {
+        let bundle = contract_bundle(&context.0.config, &context.0.contract)
+            .map_err(|e| color_eyre::eyre::eyre!(e))?;
+        let constructors = bundle.constructors();
+        let signatures = constructors
+            .iter()
+            .map(|constructor| constructor.signature())
+            .collect::<Vec<_>>();
+        let selected = Select::new(
+            "Which constructor do you want to instantiate the contract with?",
+            signatures,
+        )
+        .raw_prompt()?;
+        Ok(Some(constructors[selected.index].label.clone()))
+    }
+
+    fn input_args(_context: &DeployContext) -> color_eyre::eyre::Result<Option<String>> {
+        // Prompted for each argument of the selected constructor, unless passed with `--args`
+        Ok(Some(String::new()))
+    }
+
+    fn input_suri(_context: &DeployContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_suri()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeployNodeContext;
+
+impl DeployNodeContext {
+    pub fn from_previous_context(
+        previous_context: DeployContext,
+        scope: &<DeployNode as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        let ContractContext { config, contract } = previous_context.0;
+        let mut args = shell_words::split(&scope.args)?;
+        if args.is_empty() {
+            let bundle = contract_bundle(&config, &contract).map_err(|e| color_eyre::eyre::eyre!(e))?;
+            if let Some(constructor) = bundle
+                .constructors()
+                .into_iter()
+                .find(|constructor| constructor.label == scope.constructor)
+            {
+                args = prompt_args(&constructor.args)?;
+            }
+        }
+
+        let opts = DeployOptions {
+            contract,
+            url: scope.url.clone(),
+            constructor: scope.constructor.clone(),
+            args,
+            suri: secret_uri(&scope.suri)?,
+        };
+        if let Err(e) = ops::deploy(&opts, &config) {
+            return Err(color_eyre::eyre::eyre!(e));
+        }
+        Ok(Self)
+    }
+}
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = DeployContext)]
+#[interactive_clap(output_context = UploadCodeContext)]
+pub struct UploadCode {
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// What's the RPC endpoint of the node?
+    url: String,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Which account should sign the transaction?
+    suri: String,
+}
+
+impl UploadCode {
+    fn input_url(context: &DeployContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_url(&context.0.config)
+    }
+
+    fn input_suri(_context: &DeployContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_suri()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UploadCodeContext;
+
+impl UploadCodeContext {
+    pub fn from_previous_context(
+        previous_context: DeployContext,
+        scope: &<UploadCode as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        let ContractContext { config, contract } = previous_context.0;
+        let opts = UploadOptions {
+            contract,
+            url: scope.url.clone(),
+            suri: secret_uri(&scope.suri)?,
+        };
+        if let Err(e) = substrate_deploy::upload(&opts, &config) {
+            return Err(color_eyre::eyre::eyre!(e));
+        }
+        Ok(Self)
+    }
+}
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = DeployContext)]
+#[interactive_clap(output_context = DeployUiContext)]
+pub struct DeployUi {
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// What's the RPC endpoint of the node?
+    url: String,
+}
+
+impl DeployUi {
+    fn input_url(context: &DeployContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_url(&context.0.config)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeployUiContext;
+
+impl DeployUiContext {
+    pub fn from_previous_context(
+        _previous_context: DeployContext,
+        scope: &<DeployUi as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
     ) -> color_eyre::eyre::Result<Self> {
-        if let Err(e) = ops::deploy() {
+        if let Err(e) = open_contracts_ui(&scope.url) {
             return Err(color_eyre::eyre::eyre!(e));
         }
         Ok(Self)
@@ -1,29 +1,69 @@
-use clap::{ArgMatches, Command};
-use inquire::Confirm;
+use inquire::{Confirm, Select, Text};
 use substrate_manager::ops;
-use substrate_manager::ops::substrate_frontend::clone_substrate_frontend_template;
-use substrate_manager::util::config::{ChainInfo, ProjectType};
-use substrate_manager::util::{command_prelude::*, CliResult, Config};
+use substrate_manager::ops::substrate_frontend::{
+    default_rpc_url, generate_frontend, is_node_listening, FrontendOptions, FrontendTemplate,
+};
+use substrate_manager::util::config::ProjectType;
 
 use super::GlobalContext;
 
-pub fn cli() -> Command {
-    subcommand("frontend")
-        .about("Run the frontend application that connects to the node")
-        .arg_quiet()
-        .after_help("Run `substrate help frontend` for more detailed information.\n")
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = FrontendContext)]
+pub struct Frontend {
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Template to generate the frontend from when it's missing: one of the built-in templates, a template config or a git remote
+    template: String,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// RPC endpoint of the node the frontend connects to, defaults to the first node of the network or the dev node
+    rpc: String,
 }
 
-pub fn exec(_config: &mut Config, _args: &ArgMatches) -> CliResult {
-    ops::frontend()?;
+const FRONTEND_TEMPLATES: [(&str, &str); 2] = [
+    ("substrate-front-end", "React app of the Substrate Developer Hub, using polkadot.js"),
+    ("papi", "Vite starter using polkadot-api"),
+];
 
-    Ok(())
+const CUSTOM_TEMPLATE: &str = "custom               - Git remote or template config";
+
+/// Maps the `--template` value to a built-in template, anything else is a custom template.
+fn frontend_template(template: &str) -> FrontendTemplate {
+    match template {
+        "substrate-front-end" => FrontendTemplate::SubstrateFrontEnd,
+        "papi" => FrontendTemplate::Papi,
+        custom => FrontendTemplate::Custom(custom.to_string()),
+    }
 }
 
-#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
-#[interactive_clap(input_context = GlobalContext)]
-#[interactive_clap(output_context = FrontendContext)]
-pub struct Frontend;
+impl Frontend {
+    fn input_template(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        // Only prompted when the frontend has to be generated
+        Ok(Some(String::new()))
+    }
+
+    fn input_rpc(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        Ok(Some(String::new()))
+    }
+}
+
+fn select_template() -> color_eyre::eyre::Result<String> {
+    let mut variants = FRONTEND_TEMPLATES
+        .iter()
+        .map(|(name, description)| format!("{:<20} - {}", name, description))
+        .collect::<Vec<_>>();
+    variants.push(CUSTOM_TEMPLATE.to_string());
+    let selected = Select::new("Choose a template to generate the frontend from:", variants).raw_prompt()?;
+    if let Some((name, _)) = FRONTEND_TEMPLATES.get(selected.index) {
+        return Ok(name.to_string());
+    }
+
+    let source = Text::new("Where is the template?")
+        .with_help_message("A git remote, or a path to a template config (.toml)")
+        .prompt()?;
+    Ok(source)
+}
 
 #[derive(Debug, Clone)]
 pub struct FrontendContext;
@@ -31,28 +71,44 @@ pub struct FrontendContext;
 impl FrontendContext {
     pub fn from_previous_context(
         previous_context: GlobalContext,
-        _scope: &<Frontend as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+        scope: &<Frontend as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
     ) -> color_eyre::eyre::Result<Self> {
-        if let ProjectType::Chain(ChainInfo { frontend_path, .. }) =
-            &previous_context.config.project_type.clone().unwrap()
-        {
+        if let ProjectType::Chain(chain_info) = &previous_context.config.project_type.clone().unwrap() {
+            let cwd = previous_context.config.cwd();
+            let frontend_path = cwd.join(&chain_info.frontend_path);
             if !frontend_path.exists() {
-                println!("Could not locate your frontend directory.");
-                let is_generate = Confirm::new("Would you like to generate it? (y/n)").prompt()?;
-                if !is_generate {
-                    return Ok(Self);
-                }
-                if let Err(e) = clone_substrate_frontend_template(frontend_path) {
+                let template = if scope.template.is_empty() {
+                    println!("Could not locate your frontend directory.");
+                    let is_generate = Confirm::new("Would you like to generate it? (y/n)").prompt()?;
+                    if !is_generate {
+                        return Ok(Self);
+                    }
+                    select_template()?
+                } else {
+                    scope.template.clone()
+                };
+                if let Err(e) = generate_frontend(&frontend_template(&template), &frontend_path, cwd) {
                     return Err(color_eyre::eyre::eyre!(e));
                 }
             }
-            if let Err(e) = ops::frontend() {
+
+            let rpc = if scope.rpc.is_empty() {
+                default_rpc_url(&previous_context.config).map_err(|e| color_eyre::eyre::eyre!(e))?
+            } else {
+                scope.rpc.clone()
+            };
+            let start_node = !is_node_listening(&rpc)
+                && Confirm::new(&format!("No node is listening on {}, start one first?", rpc))
+                    .with_default(true)
+                    .prompt()?;
+            let opts = FrontendOptions { rpc, start_node };
+            if let Err(e) = ops::frontend(&opts, &previous_context.config, chain_info) {
                 return Err(color_eyre::eyre::eyre!(e));
             }
 
             Ok(Self)
         } else {
-            color_eyre::eyre::bail!("Incorrect project type")
+            color_eyre::eyre::bail!("Incorrect project type");
         }
     }
 }
@@ -0,0 +1,37 @@
+use substrate_manager::{
+    ops::substrate_inspect::{self, InspectOptions},
+    util::config::ProjectType,
+};
+
+use super::GlobalContext;
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = InspectContext)]
+pub struct Inspect {
+    #[interactive_clap(long)]
+    /// Print the runtime's pallets as JSON
+    json: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct InspectContext;
+
+impl InspectContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<Inspect as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        if let ProjectType::Chain(chain_info) = &previous_context.config.project_type.clone().unwrap()
+        {
+            let opts = InspectOptions { json: scope.json };
+            if let Err(e) = substrate_inspect::inspect(&opts, &previous_context.config, chain_info) {
+                return Err(color_eyre::eyre::eyre!(e));
+            }
+
+            Ok(Self)
+        } else {
+            color_eyre::eyre::bail!("Incorrect project type");
+        }
+    }
+}
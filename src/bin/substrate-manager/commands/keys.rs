@@ -0,0 +1,39 @@
+use substrate_manager::{
+    ops::substrate_keys::{self, KeysOptions},
+    util::config::ProjectType,
+};
+
+use super::GlobalContext;
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = KeysContext)]
+pub struct Keys {
+    #[interactive_clap(long)]
+    /// Print the secret phrase of each validator
+    show_secrets: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct KeysContext;
+
+impl KeysContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<Keys as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        if let ProjectType::Chain(chain_info) = &previous_context.config.project_type.clone().unwrap()
+        {
+            let opts = KeysOptions {
+                show_secrets: scope.show_secrets,
+            };
+            if let Err(e) = substrate_keys::keys(&opts, &previous_context.config, chain_info) {
+                return Err(color_eyre::eyre::eyre!(e));
+            }
+
+            Ok(Self)
+        } else {
+            color_eyre::eyre::bail!("Incorrect project type");
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use substrate_manager::{
+    ops::substrate_lint::{self, LintOptions},
+    util::config::ProjectType,
+};
+
+use super::GlobalContext;
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = LintContext)]
+pub struct Lint {
+    #[interactive_clap(long)]
+    /// Record the current pallet indices in Substrate.toml as the baseline to check against
+    record: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct LintContext;
+
+impl LintContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<Lint as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        if let ProjectType::Chain(chain_info) = &previous_context.config.project_type.clone().unwrap()
+        {
+            let opts = LintOptions {
+                record: scope.record,
+            };
+            if let Err(e) = substrate_lint::lint(&opts, &previous_context.config, chain_info) {
+                return Err(color_eyre::eyre::eyre!(e));
+            }
+
+            Ok(Self)
+        } else {
+            color_eyre::eyre::bail!("Incorrect project type");
+        }
+    }
+}
@@ -10,21 +10,36 @@ use substrate_manager::{
         CliError, CliResult, Config,
     },
 };
-use toml_edit::{value, Item};
+use toml_edit::value;
 
 use self::{
-    add_pallet::AddPallet, build::Build, deploy::Deploy, frontend::Frontend, new_chain::NewChain,
-    new_contract::NewContract, run::Run, test::Test,
+    add_pallet::AddPallet, bench::Bench, build::Build, build_chain::BuildChain, call::Call, deploy::Deploy,
+    frontend::Frontend, inspect::Inspect, keys::Keys, lint::Lint, network::Network, new_chain::NewChain,
+    new_pallet::NewPallet, node::Node, new_contract::NewContract, pallet::Pallet, run::Run, spec::Spec, test::Test,
+    try_runtime::TryRuntime, upgrade::Upgrade,
 };
 
 pub mod add_pallet;
+pub mod bench;
 pub mod build;
+pub mod build_chain;
+pub mod call;
 pub mod deploy;
 pub mod frontend;
+pub mod inspect;
+pub mod keys;
+pub mod lint;
+pub mod network;
 pub mod new_chain;
 pub mod new_contract;
+pub mod new_pallet;
+pub mod node;
+pub mod pallet;
 pub mod run;
+pub mod spec;
 pub mod test;
+pub mod try_runtime;
+pub mod upgrade;
 
 // pub fn builtin() -> Vec<Command> {
 //     vec![new_parachain::cli(), dev::cli(), frontend::cli()]
@@ -59,14 +74,60 @@ pub struct GlobalContext {
 #[strum_discriminants(derive(EnumMessage, EnumIter))]
 #[interactive_clap(disable_back)]
 #[non_exhaustive]
+#[expect(dead_code, reason = "interactive_clap only builds the `Cli` mirror of the menu")]
 /// What's your next move? (Select an option below)
 pub enum Chain {
     /// Use this to run the substrate chain node
     #[strum_discriminants(strum(message = "run          - ▶️  Start the chain node"))]
     Run(Run),
+    /// Use this to compile the node or only its runtime
+    #[strum_discriminants(strum(
+        message = "build        - 🏗️  Build the node or its runtime wasm"
+    ))]
+    Build(BuildChain),
+    /// Use this to bump the runtime version and prepare the upgrade call
+    #[strum_discriminants(strum(
+        message = "upgrade      - ⬆️  Prepare a runtime upgrade"
+    ))]
+    Upgrade(Upgrade),
+    /// Use this to launch several local nodes that form a network
+    #[strum_discriminants(strum(
+        message = "network      - 🕸️  Launch a local multi-node network"
+    ))]
+    Network(Network),
+    /// Use this to generate, patch and compare chain specs
+    #[strum_discriminants(strum(
+        message = "spec         - 📜 Manage the chain specs of your chain"
+    ))]
+    Spec(Spec),
+    /// Use this to generate the session keys of your local validators
+    #[strum_discriminants(strum(
+        message = "keys         - 🔑 Generate session keys for your validators"
+    ))]
+    Keys(Keys),
     /// Add pallets to your chain
     #[strum_discriminants(strum(message = "add          - 📦 Add pallets to your chain"))]
     Add(AddPallet),
+    /// Use this to create a new pallet in your chain's workspace and add it to the runtime
+    #[strum_discriminants(strum(
+        message = "new-pallet   - 🧩 Create a new pallet in your chain"
+    ))]
+    NewPallet(NewPallet),
+    /// Use this to add storage items, extrinsics, events or errors to a pallet of your chain
+    #[strum_discriminants(strum(
+        message = "pallet       - 🧱 Add storage, extrinsics, events or errors to a pallet"
+    ))]
+    Pallet(Pallet),
+    /// Use this to list the pallets of your runtime with their indices and `Config` types
+    #[strum_discriminants(strum(
+        message = "inspect      - 🔎 List the pallets of your runtime and their configuration"
+    ))]
+    Inspect(Inspect),
+    /// Use this to check that the pallets of your runtime keep explicit and stable indices
+    #[strum_discriminants(strum(
+        message = "lint         - 🩺 Check the pallet indices of your runtime"
+    ))]
+    Lint(Lint),
     /// Use this to run the frontent application that connects to the chain node
     #[strum_discriminants(strum(
         message = "frontend     - 📡 Launch the frontend interface for your chain"
@@ -75,6 +136,16 @@ pub enum Chain {
     /// Use this to run the tests for your chain
     #[strum_discriminants(strum(message = "test         - 🧪 Run the tests for your chain"))]
     Test(Test),
+    /// Use this to benchmark the pallets of your chain and generate their weights
+    #[strum_discriminants(strum(
+        message = "bench        - ⏱️  Benchmark pallets and generate their weights"
+    ))]
+    Bench(Bench),
+    /// Use this to check the storage migrations of a runtime upgrade against a snapshot of your chain
+    #[strum_discriminants(strum(
+        message = "try-runtime  - 🔬 Check the runtime upgrade's migrations against a snapshot"
+    ))]
+    TryRuntime(TryRuntime),
 }
 
 #[derive(Debug, Clone, interactive_clap::InteractiveClap)]
@@ -89,6 +160,7 @@ pub struct ContractCmd {
 #[strum_discriminants(derive(EnumMessage, EnumIter))]
 #[interactive_clap(disable_back)]
 #[non_exhaustive]
+#[expect(dead_code, reason = "interactive_clap only builds the `Cli` mirror of the menu")]
 /// What's your next move? (Select an option below)
 pub enum Contract {
     /// Use this to compile the smart contract into optimized WebAssembly bytecode, generate
@@ -97,11 +169,26 @@ pub enum Contract {
         message = "build          - 🏗️ Compile the contract into wasm (.contract file) for deployment"
     ))]
     Build(Build),
-    /// Use this to open the Substrate Smart Contract UI to deploy your smart contract
+    /// Use this to start a local contracts node to deploy your smart contract to
     #[strum_discriminants(strum(
-        message = "deploy         - 🚀 Deploy the contract using Substrate Smart Contract UI"
+        message = "node           - 🖥️  Start a local contracts node"
+    ))]
+    Node(Node),
+    /// Use this to deploy your smart contract to a node, or through the Substrate Contracts UI
+    #[strum_discriminants(strum(
+        message = "deploy         - 🚀 Deploy the contract to a node"
     ))]
     Deploy(Deploy),
+    /// Use this to query or call the messages of your deployed smart contract
+    #[strum_discriminants(strum(
+        message = "call           - 📞 Call a message of the deployed contract"
+    ))]
+    Call(Call),
+    /// Use this to add a new smart contract to your contract workspace
+    #[strum_discriminants(strum(
+        message = "new-contract   - 🦑 Add a new contract to the workspace"
+    ))]
+    NewContract(NewContract),
     /// Use this to run the tests for your smart contract
     #[strum_discriminants(strum(
         message = "test           - 🧪 Run tests for the smart contract"
@@ -122,6 +209,7 @@ pub struct MissingProjectCmd {
 #[strum_discriminants(derive(EnumMessage, EnumIter))]
 #[interactive_clap(disable_back)]
 #[non_exhaustive]
+#[expect(dead_code, reason = "interactive_clap only builds the `Cli` mirror of the menu")]
 /// What would you like to create today?
 pub enum MissingProject {
     #[strum_discriminants(strum(
@@ -206,7 +294,7 @@ impl GlobalContext {
                 config: Config::default().unwrap(),
             })
         } else {
-            color_eyre::eyre::bail!("Incorrect project type")
+            color_eyre::eyre::bail!("Incorrect project type");
         }
     }
 }
@@ -222,7 +310,9 @@ where
         Err(error) => error.exit(),
     };
 
-    println!("🚀 Welcome to Substrate Manager CLI 🚀");
+    // The banner and console command go to stderr, so that stdout only carries the command's
+    // output, e.g. JSON to pipe into other tools
+    eprintln!("🚀 Welcome to Substrate Manager CLI 🚀");
     if let Config {
         cwd: _,
         project_type: Some(project_type),
@@ -232,14 +322,14 @@ where
             substrate_manager::util::config::ProjectType::Chain(_) => "🪂",
             substrate_manager::util::config::ProjectType::Contract(_) => "🦑",
         };
-        println!("{} {}", emoji, project_type);
+        eprintln!("{} {}", emoji, project_type);
     } else {
-        println!("No projects found in current directory");
+        eprintln!("No projects found in current directory");
     }
     let cli_cmd = match A::from_cli(Some(cli), global_context) {
         interactive_clap::ResultFromCli::Ok(cli_cmd)
         | interactive_clap::ResultFromCli::Cancel(Some(cli_cmd)) => {
-            println!(
+            eprintln!(
                 "\nHere is your console command if you need to script it or re-run:\n{}",
                 shell_words::join(
                     std::iter::once(
@@ -253,7 +343,7 @@ where
             Ok(Some(cli_cmd))
         }
         interactive_clap::ResultFromCli::Cancel(None) => {
-            println!("Goodbye!");
+            eprintln!("Goodbye!");
             Ok(None)
         }
         interactive_clap::ResultFromCli::Back => {
@@ -261,7 +351,7 @@ where
         }
         interactive_clap::ResultFromCli::Err(optional_cli_cmd, err) => {
             if let Some(cli_cmd) = optional_cli_cmd {
-                println!(
+                eprintln!(
                     "\nHere is your console command if you need to script it or re-run:\n{}",
                     shell_words::join(
                         std::iter::once(
@@ -0,0 +1,70 @@
+use inquire::{validator::Validation, Text};
+use substrate_manager::{
+    ops::{
+        self,
+        substrate_network::{load_network_config, NetworkOptions},
+        substrate_testnet::{is_parachain_node, write_default_zombienet_config, zombienet_config_path},
+    },
+    util::config::ProjectType,
+};
+
+use super::GlobalContext;
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = NetworkContext)]
+pub struct Network {
+    #[interactive_clap(long)]
+    /// Wipe each node's base path before starting the network
+    purge: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct NetworkContext;
+
+impl NetworkContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<Network as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        if let ProjectType::Chain(chain_info) = &previous_context.config.project_type.clone().unwrap()
+        {
+            let config = &previous_context.config;
+            let network = load_network_config(config.cwd()).map_err(|e| color_eyre::eyre::eyre!(e))?;
+            let zombienet_path = zombienet_config_path(config.cwd(), network.as_ref());
+
+            // Parachains can't produce blocks on their own, they need a relay chain to run against
+            if !zombienet_path.exists()
+                && is_parachain_node(config, chain_info).map_err(|e| color_eyre::eyre::eyre!(e))?
+            {
+                println!("Your chain is a parachain, a local relay chain will be launched alongside it.");
+                let relay_command = Text::new("Where is your polkadot binary located?")
+                    .with_default("polkadot")
+                    .with_help_message("Either a command in your PATH or a path relative to the current directory")
+                    .with_validator(|p: &str| {
+                        if which::which(p).is_ok() || std::path::Path::new(p).exists() {
+                            Ok(Validation::Valid)
+                        } else {
+                            Ok(Validation::Invalid(
+                                "The binary you entered does not exist".into(),
+                            ))
+                        }
+                    })
+                    .prompt()?;
+
+                let path = write_default_zombienet_config(config, chain_info, &relay_command)
+                    .map_err(|e| color_eyre::eyre::eyre!(e))?;
+                println!("Network declared in `{}`", path.display());
+            }
+
+            let opts = NetworkOptions { purge: scope.purge };
+            if let Err(e) = ops::network(&opts, config, chain_info) {
+                return Err(color_eyre::eyre::eyre!(e));
+            }
+
+            Ok(Self)
+        } else {
+            color_eyre::eyre::bail!("Incorrect project type");
+        }
+    }
+}
@@ -1,44 +1,14 @@
 use std::path::Path;
 
-use clap::{Arg, ArgAction, ArgMatches, Command};
 use inquire::{Select, Text};
 
 use strum::{EnumDiscriminants, EnumIter, EnumMessage, IntoEnumIterator};
 use substrate_manager::ops;
 use substrate_manager::ops::substrate_new::NewOptions;
-use substrate_manager::util::{normalize_paths, command_prelude::*, CliResult, Config};
+use substrate_manager::util::{normalize_paths, Config};
 
 use super::GlobalContext;
 
-pub fn cli() -> Command {
-    subcommand("new_chain")
-        .about("Create a new substrate chain project at <path>")
-        .arg_quiet()
-        .arg(Arg::new("path").action(ArgAction::Set).required(true))
-        // .arg_new_opts()
-        .after_help("Run `substrate help new_chain` for more detailed information.\n")
-}
-
-pub fn exec(config: &mut Config, args: &ArgMatches) -> CliResult {
-    let path = args.get_one::<String>("path");
-    // let name = args.get_one::<String>("name");
-
-    let opts = NewOptions {
-        path: path.map(|p| config.cwd().join(p)).unwrap(),
-        // name: name.map(|s| s.to_string()),
-        name: None,
-        template: todo!(),
-    };
-    ops::new_chain(&opts, config)?;
-    let path = path.unwrap();
-    // let package_name = if let Some(name) = name { name } else { path };
-    let package_name = path;
-    // config
-    //     .shell()
-    //     .status("Created", format!("`{}` package", package_name))?;
-    Ok(())
-}
-
 #[derive(Debug, Clone, interactive_clap::InteractiveClap)]
 #[interactive_clap(input_context = GlobalContext)]
 #[interactive_clap(output_context = NewChainContext)]
@@ -1,8 +1,11 @@
 use std::path::Path;
 
-use inquire::Text;
+use inquire::{Select, Text};
 use substrate_manager::{
-    ops::{self, substrate_new::NewOptions},
+    ops::{
+        self,
+        substrate_new::{NewOptions, Template},
+    },
     util::{normalize_paths, Config},
 };
 
@@ -12,6 +15,10 @@ use super::GlobalContext;
 #[interactive_clap(input_context = GlobalContext)]
 #[interactive_clap(output_context = NewContractContext)]
 pub struct NewContract {
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Template of the contract: one of the built-in templates, a template config, a local directory or a git remote
+    template: String,
     // #[interactive_clap(skip_default_input_arg)]
     /// Enter the path for your new smart contract project (either abosulte or relative to the current directory):
     path: String,
@@ -23,9 +30,58 @@ pub struct NewContract {
 #[derive(Debug, Clone)]
 pub struct NewContractContext {
     global_context: Config,
+    template: String,
     path: String,
 }
 
+const CONTRACT_TEMPLATES: [(&str, &str); 7] = [
+    ("flipper", "A minimal contract flipping a boolean, from cargo-contract"),
+    ("erc20", "ERC-20 fungible token"),
+    ("psp22", "PSP22 fungible token, the ink! standard"),
+    ("erc721", "ERC-721 non-fungible token"),
+    ("psp34", "PSP34 non-fungible token, the ink! standard"),
+    ("multisig", "Multi-owner wallet executing transactions once confirmed"),
+    ("proxy", "Upgradeable proxy forwarding its calls to another contract"),
+];
+
+const CUSTOM_TEMPLATE: &str = "custom               - Git remote, local directory or template config";
+
+/// Maps the `--template` value to a built-in template, anything else is a custom template.
+fn contract_template(template: &str) -> Template {
+    match template {
+        "" | "flipper" => Template::CargoContract,
+        "erc20" => Template::Erc20,
+        "psp22" => Template::Psp22,
+        "erc721" => Template::Erc721,
+        "psp34" => Template::Psp34,
+        "multisig" => Template::Multisig,
+        "proxy" => Template::Proxy,
+        custom => Template::Custom(custom.to_string()),
+    }
+}
+
+impl NewContract {
+    fn input_template(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        let mut variants = CONTRACT_TEMPLATES
+            .iter()
+            .map(|(name, description)| format!("{:<20} - {}", name, description))
+            .collect::<Vec<_>>();
+        variants.push(CUSTOM_TEMPLATE.to_string());
+        let selected = Select::new("Choose a template to generate the contract from:", variants)
+            .raw_prompt()?;
+        if let Some((name, _)) = CONTRACT_TEMPLATES.get(selected.index) {
+            return Ok(Some(name.to_string()));
+        }
+
+        let source = Text::new("Where is the template?")
+            .with_help_message(
+                "A git remote, or a path to a contract directory or to a template config (.toml)",
+            )
+            .prompt()?;
+        Ok(Some(source))
+    }
+}
+
 #[derive(Debug, Clone, interactive_clap::InteractiveClap)]
 #[interactive_clap(input_context = NewContractContext)]
 #[interactive_clap(output_context = InputNameContext)]
@@ -45,6 +101,7 @@ impl NewContractContext {
     ) -> color_eyre::eyre::Result<Self> {
         Ok(Self {
             global_context: previous_context.config,
+            template: scope.template.clone(),
             path: scope.path.clone(),
         })
     }
@@ -63,7 +120,7 @@ impl InputNameContext {
         let opts = NewOptions {
             path,
             name: Some(name.clone()),
-            template: ops::substrate_new::Template::CargoContract,
+            template: contract_template(&previous_context.template),
         };
 
         if let Err(e) = ops::new_contract(&opts, &previous_context.global_context) {
@@ -0,0 +1,35 @@
+use substrate_manager::ops::{self, substrate_new::NewPalletOptions};
+
+use super::GlobalContext;
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = NewPalletContext)]
+pub struct NewPallet {
+    /// What's the name of your new pallet? (e.g. kitties, created in pallets/kitties)
+    name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewPalletContext;
+
+impl NewPalletContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<NewPallet as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        let opts = NewPalletOptions {
+            name: scope.name.clone(),
+        };
+        match ops::new_pallet(&opts, &previous_context.config) {
+            Ok(path) => {
+                println!(
+                    "\nEdit `{}` to write your pallet's logic, its tests and benchmarks are next to it.",
+                    path.join("src/lib.rs").display()
+                );
+                Ok(Self)
+            }
+            Err(e) => Err(color_eyre::eyre::eyre!(e)),
+        }
+    }
+}
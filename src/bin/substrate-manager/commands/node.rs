@@ -0,0 +1,28 @@
+use substrate_manager::{ops::substrate_contracts_node::contracts_node, util::config::ProjectType};
+
+use super::GlobalContext;
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = NodeContext)]
+pub struct Node;
+
+#[derive(Debug, Clone)]
+pub struct NodeContext;
+
+impl NodeContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        _scope: &<Node as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        if let ProjectType::Contract(_) = &previous_context.config.project_type.clone().unwrap() {
+            if let Err(e) = contracts_node(&previous_context.config) {
+                return Err(color_eyre::eyre::eyre!(e));
+            }
+
+            Ok(Self)
+        } else {
+            color_eyre::eyre::bail!("Incorrect project type");
+        }
+    }
+}
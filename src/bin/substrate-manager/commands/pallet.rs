@@ -0,0 +1,271 @@
+use inquire::{Select, Text};
+use strum::{EnumDiscriminants, EnumIter, EnumMessage, IntoEnumIterator};
+use substrate_manager::{
+    core::pallet_source::{parse_fields, parse_storage_keys, StorageItem, StorageKind, Variant},
+    ops::{
+        substrate_bench::local_pallets,
+        substrate_pallet::{self, PalletComponent, PalletComponentOptions},
+    },
+    util::config::ProjectType,
+};
+
+use super::GlobalContext;
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(context = GlobalContext)]
+pub struct Pallet {
+    #[interactive_clap(subcommand)]
+    command: PalletCommand,
+}
+
+#[derive(Debug, EnumDiscriminants, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(context = GlobalContext)]
+#[strum_discriminants(derive(EnumMessage, EnumIter))]
+#[non_exhaustive]
+#[expect(dead_code, reason = "interactive_clap only builds the `Cli` mirror of the menu")]
+/// What do you want to add to your pallet?
+pub enum PalletCommand {
+    /// Add a `#[pallet::storage]` item
+    #[strum_discriminants(strum(message = "storage  - 🗄️  Add a storage value or map"))]
+    Storage(AddStorage),
+    /// Add a dispatchable to the `#[pallet::call]` impl
+    #[strum_discriminants(strum(message = "call     - 📞 Add an extrinsic"))]
+    Call(AddCall),
+    /// Add a variant to the `#[pallet::event]` enum
+    #[strum_discriminants(strum(message = "event    - 📣 Add an event"))]
+    Event(AddEvent),
+    /// Add a variant to the `#[pallet::error]` enum
+    #[strum_discriminants(strum(message = "error    - 🚫 Add an error"))]
+    Error(AddError),
+}
+
+/// Prompts for one of the pallets of the chain's workspace.
+fn input_pallet(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+    let Some(ProjectType::Chain(chain_info)) = &context.config.project_type else {
+        color_eyre::eyre::bail!("Incorrect project type");
+    };
+    let pallets = local_pallets(&context.config, chain_info)
+        .map_err(|e| color_eyre::eyre::eyre!(e))?
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+    if pallets.is_empty() {
+        color_eyre::eyre::bail!("the workspace has no pallet, create one with `new-pallet` first");
+    }
+
+    Ok(Some(Select::new("Which pallet do you want to edit?", pallets).prompt()?))
+}
+
+/// Prompts for optional `name: Type` fields, e.g. the arguments of a call.
+fn input_fields(message: &str) -> color_eyre::eyre::Result<Option<String>> {
+    let fields = Text::new(message)
+        .with_help_message("e.g. `who: T::AccountId, amount: u32`, leave empty for none")
+        .prompt()?;
+    Ok(Some(fields))
+}
+
+fn add_component(
+    context: &GlobalContext,
+    pallet: &str,
+    component: PalletComponent,
+) -> color_eyre::eyre::Result<()> {
+    if let ProjectType::Chain(chain_info) = &context.config.project_type.clone().unwrap() {
+        let opts = PalletComponentOptions {
+            pallet: pallet.to_string(),
+            component,
+        };
+        substrate_pallet::add_component(&opts, &context.config, chain_info)
+            .map_err(|e| color_eyre::eyre::eyre!(e))
+    } else {
+        color_eyre::eyre::bail!("Incorrect project type");
+    }
+}
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = AddStorageContext)]
+pub struct AddStorage {
+    /// Which pallet do you want to edit?
+    #[interactive_clap(skip_default_input_arg)]
+    pallet: String,
+    /// What's the name of the storage item? (e.g. Owners)
+    name: String,
+    /// What kind of storage is it?
+    #[interactive_clap(skip_default_input_arg)]
+    kind: String,
+    /// What's the type of the stored value? (e.g. u32, BoundedVec<u8, T::MaxLength>)
+    value: String,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Keys of the map separated by `;`, each optionally preceded by its hasher, e.g. `T::AccountId; Twox64Concat u32`
+    keys: String,
+    #[interactive_clap(long)]
+    /// Return the value's default instead of `None` when it isn't set
+    value_query: bool,
+}
+
+impl AddStorage {
+    fn input_pallet(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_pallet(context)
+    }
+
+    fn input_kind(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        let kinds = StorageKind::iter().map(|kind| kind.to_string()).collect::<Vec<_>>();
+        Ok(Some(Select::new("What kind of storage is it?", kinds).prompt()?))
+    }
+
+    fn input_keys(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        // Prompted once the kind of storage is known
+        Ok(Some(String::new()))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AddStorageContext;
+
+impl AddStorageContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<AddStorage as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        let kind = scope.kind.parse::<StorageKind>().map_err(|e| color_eyre::eyre::eyre!(e))?;
+        let keys = if scope.keys.is_empty() && kind != StorageKind::Value {
+            let count = kind
+                .key_count()
+                .map_or_else(|| "one or more keys".to_string(), |count| format!("{} key(s)", count));
+            Text::new(&format!("What are the keys of the {}? ({})", kind, count))
+                .with_help_message("Separated by `;`, hashed with Blake2_128Concat unless preceded by Twox64Concat or Identity")
+                .prompt()?
+        } else {
+            scope.keys.clone()
+        };
+
+        let storage = StorageItem {
+            name: scope.name.clone(),
+            kind,
+            keys: parse_storage_keys(&keys).map_err(|e| color_eyre::eyre::eyre!(e))?,
+            value: scope.value.clone(),
+            value_query: scope.value_query,
+        };
+        add_component(&previous_context, &scope.pallet, PalletComponent::Storage(storage))?;
+
+        Ok(Self)
+    }
+}
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = AddCallContext)]
+pub struct AddCall {
+    /// Which pallet do you want to edit?
+    #[interactive_clap(skip_default_input_arg)]
+    pallet: String,
+    /// What's the name of the extrinsic? (e.g. transfer)
+    name: String,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Arguments of the extrinsic after its origin, e.g. `to: T::AccountId, amount: u32`
+    params: String,
+}
+
+impl AddCall {
+    fn input_pallet(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_pallet(context)
+    }
+
+    fn input_params(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_fields("What are the arguments of the extrinsic, after its origin?")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AddCallContext;
+
+impl AddCallContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<AddCall as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        let component = PalletComponent::Call {
+            name: scope.name.clone(),
+            params: parse_fields(&scope.params).map_err(|e| color_eyre::eyre::eyre!(e))?,
+        };
+        add_component(&previous_context, &scope.pallet, component)?;
+
+        Ok(Self)
+    }
+}
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = AddEventContext)]
+pub struct AddEvent {
+    /// Which pallet do you want to edit?
+    #[interactive_clap(skip_default_input_arg)]
+    pallet: String,
+    /// What's the name of the event? (e.g. Transferred)
+    name: String,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Fields of the event, e.g. `to: T::AccountId, amount: u32`
+    fields: String,
+}
+
+impl AddEvent {
+    fn input_pallet(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_pallet(context)
+    }
+
+    fn input_fields(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_fields("What are the fields of the event?")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AddEventContext;
+
+impl AddEventContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<AddEvent as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        let variant = Variant {
+            name: scope.name.clone(),
+            fields: parse_fields(&scope.fields).map_err(|e| color_eyre::eyre::eyre!(e))?,
+        };
+        add_component(&previous_context, &scope.pallet, PalletComponent::Event(variant))?;
+
+        Ok(Self)
+    }
+}
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = AddErrorContext)]
+pub struct AddError {
+    /// Which pallet do you want to edit?
+    #[interactive_clap(skip_default_input_arg)]
+    pallet: String,
+    /// What's the name of the error? (e.g. NotOwner)
+    name: String,
+}
+
+impl AddError {
+    fn input_pallet(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_pallet(context)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AddErrorContext;
+
+impl AddErrorContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<AddError as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        add_component(&previous_context, &scope.pallet, PalletComponent::Error(scope.name.clone()))?;
+
+        Ok(Self)
+    }
+}
@@ -2,109 +2,18 @@ use std::fs::File;
 use std::io::Read;
 
 use color_eyre::eyre::Context;
-use inquire::Select;
+use inquire::{validator::Validation, Select, Text};
 use substrate_manager::{
-    ops::{self, substrate_run::RunOptions},
+    ops::{
+        self,
+        substrate_run::{extract_chain_specs, RunOptions},
+    },
     util::config::{ChainInfo, ProjectType},
 };
 
 use super::GlobalContext;
 
-// TODO: compartmentalize this function into smaller functions
-fn extract_match_fields(content: &str) -> Vec<String> {
-    // Parse the source code using syn to extract match arms and patterns.
-    let parsed_code = syn::parse_file(content).expect("Failed to parse code");
-
-    let mut extracted_fields = Vec::new();
-
-    // Traverse the parsed code to find the SubstrateCli trait implementation and the load_spec method.
-    for item in parsed_code.items {
-        if let syn::Item::Impl(impl_item) = &item {
-            if let Some((_, path, ..)) = &impl_item.trait_ {
-                if path.segments.last().map(|s| s.ident.to_string())
-                    == Some("SubstrateCli".to_string())
-                {
-                    // Found SubstrateCli trait implementation.
-                    // Now traverse through the methods to find load_spec.
-                    for item in &impl_item.items {
-                        if let syn::ImplItem::Fn(method) = item {
-                            if method.sig.ident == "load_spec" {
-                                extracted_fields
-                                    .extend(extract_fields_from_load_spec(&method.block));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-         if let syn::Item::Fn(fn_item) = &item {
-            // Check if the function is the load_spec function declared outside the impl block.
-            if fn_item.sig.ident == "load_spec" {
-                extracted_fields.extend(extract_fields_from_load_spec(&fn_item.block));
-            }
-        }
-    }
-
-    extracted_fields
-}
-
-fn extract_fields_from_load_spec(block: &syn::Block) -> Vec<String> {
-    let mut extracted_fields = Vec::new();
-
-    for stmt in &block.stmts {
-        if let syn::Stmt::Expr(expr, _) = stmt {
-            if let syn::Expr::Call(call_expr) = expr {
-                for expr in &call_expr.args {
-                    if let syn::Expr::Match(match_expr) = expr {
-                        for arm in &match_expr.arms {
-                            if let Some(fields) = extract_field_names(&arm.pat) {
-                                extracted_fields.extend(fields);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    extracted_fields
-}
-
-fn extract_field_names(pat: &syn::Pat) -> Option<Vec<String>> {
-    match pat {
-        syn::Pat::Ident(_) => {
-            // TODO: Implement path identifier as argument
-            // return Some(vec!["path".to_string()]);
-        }
-        syn::Pat::Lit(syn::PatLit { lit, .. }) => {
-            // 'dev' => vec!['dev']
-            if let syn::Lit::Str(lit_str) = lit {
-                let value = lit_str.value();
-                if !value.is_empty() {
-                    return Some(vec![value]);
-                }
-            }
-        }
-        syn::Pat::Path(syn::PatPath { path, .. }) => {
-            // some::module::CONSTANT => 'constant'
-            if let Some(segment) = path.segments.last() {
-                return Some(vec![segment.ident.to_string().to_lowercase()]);
-            }
-        }
-        syn::Pat::Or(syn::PatOr { cases, .. }) => {
-            // 'dev' | 'test' | some::module::CONSTANT => vec!['dev', 'test', 'constant']
-            let fields = cases
-                .iter()
-                .filter_map(extract_field_names)
-                .flatten()
-                .collect();
-            return Some(fields);
-        }
-        _ => panic!("Couldn't extract field with unsupported field pattern"),
-    }
-
-    None
-}
+const CUSTOM_CHAIN_SPEC: &str = "custom chain spec file…";
 
 #[derive(Debug, Clone, interactive_clap::InteractiveClap)]
 #[interactive_clap(input_context = GlobalContext)]
@@ -117,32 +26,61 @@ pub struct Run {
 
 impl Run {
     fn input_chain(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
-        if let ProjectType::Chain(ChainInfo { node_path, .. }) =
-            &context.config.project_type.clone().unwrap()
-        {
-            let mut file = File::open(node_path.join("src/command.rs")).with_context(|| "Couldn't access the node's command.rs file.\nMake sure your node path is set correctly in Substrate.toml")?;
-            let mut content = String::new();
-            file.read_to_string(&mut content)?;
-
-            let variants = extract_match_fields(&content);
+        input_chain_spec(
+            context,
+            "What is the chain-specification command you want to run your chain with?",
+        )
+    }
+}
 
-            let select_submit = Select::new(
-                "What is the chain-specification command you want to run your chain with?",
-                variants,
-            )
-            .prompt();
+/// Prompts for one of the chain-specifications supported by the node, or a custom spec file.
+pub fn input_chain_spec(
+    context: &GlobalContext,
+    message: &str,
+) -> color_eyre::eyre::Result<Option<String>> {
+    if let ProjectType::Chain(ChainInfo { node_path, .. }) =
+        &context.config.project_type.clone().unwrap()
+    {
+        let mut file = File::open(node_path.join("src/command.rs")).with_context(|| "Couldn't access the node's command.rs file.\nMake sure your node path is set correctly in Substrate.toml")?;
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+
+        let specs =
+            extract_chain_specs(&content).map_err(|e| color_eyre::eyre::eyre!(e))?;
+
+        let mut variants = specs.ids;
+        // Offer a custom file whenever the node may load one, or if we couldn't tell
+        if specs.accepts_path || variants.is_empty() {
+            variants.push(CUSTOM_CHAIN_SPEC.to_string());
+        }
 
-            match select_submit {
-                Ok(value) => Ok(Some(value.clone())),
-                Err(
-                    inquire::error::InquireError::OperationCanceled
-                    | inquire::error::InquireError::OperationInterrupted,
-                ) => Ok(None),
-                Err(err) => Err(err.into()),
+        let select_submit = Select::new(message, variants).prompt();
+
+        match select_submit {
+            Ok(value) if value == CUSTOM_CHAIN_SPEC => {
+                let path = Text::new("Where is your chain spec file located?")
+                    .with_help_message("The path should be relative to the current directory")
+                    .with_validator(|p: &str| {
+                        if !p.is_empty() && std::path::Path::new(p).is_file() {
+                            Ok(Validation::Valid)
+                        } else {
+                            Ok(Validation::Invalid(
+                                "The path you entered does not exist or is not a file".into(),
+                            ))
+                        }
+                    })
+                    .prompt()?;
+                Ok(Some(path))
             }
-        } else {
-            color_eyre::eyre::bail!("Incorrect project type")
+            Ok(value) => Ok(Some(value)),
+            Err(
+                inquire::error::InquireError::OperationCanceled
+                | inquire::error::InquireError::OperationInterrupted,
+            ) => Ok(None),
+            Err(err) => Err(err.into()),
         }
+    } else {
+        color_eyre::eyre::bail!("Incorrect project type");
     }
 }
 
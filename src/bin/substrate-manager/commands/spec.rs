@@ -0,0 +1,250 @@
+use std::path::PathBuf;
+
+use inquire::{validator::Validation, Select, Text};
+use strum::{EnumDiscriminants, EnumIter, EnumMessage};
+use substrate_manager::{
+    ops::substrate_spec::{
+        self, list_specs, BuildSpecOptions, DiffSpecOptions, PatchSpecOptions, RawSpecOptions,
+    },
+    util::config::ProjectType,
+};
+
+use super::{run::input_chain_spec, GlobalContext};
+
+const OTHER_SPEC_FILE: &str = "other file…";
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(context = GlobalContext)]
+pub struct Spec {
+    #[interactive_clap(subcommand)]
+    command: SpecCommand,
+}
+
+#[derive(Debug, EnumDiscriminants, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(context = GlobalContext)]
+#[strum_discriminants(derive(EnumMessage, EnumIter))]
+#[non_exhaustive]
+#[expect(dead_code, reason = "interactive_clap only builds the `Cli` mirror of the menu")]
+/// What do you want to do with your chain specs?
+pub enum SpecCommand {
+    /// Generate a chain spec from one of the node's chain-specifications
+    #[strum_discriminants(strum(message = "build  - 🏗️ Generate a chain spec into specs/"))]
+    Build(BuildSpec),
+    /// Convert a plain chain spec into its raw form
+    #[strum_discriminants(strum(message = "raw    - 🧱 Convert a chain spec to raw"))]
+    Raw(RawSpec),
+    /// Patch genesis values of a plain chain spec
+    #[strum_discriminants(strum(message = "set    - ✏️  Patch genesis values of a chain spec"))]
+    Set(SetSpec),
+    /// Show the differences between two chain specs
+    #[strum_discriminants(strum(message = "diff   - 🔍 Compare two chain specs"))]
+    Diff(DiffSpec),
+}
+
+/// Prompts for a chain spec, offering the ones stored in `specs/` first.
+fn input_spec_file(
+    context: &GlobalContext,
+    message: &str,
+) -> color_eyre::eyre::Result<Option<String>> {
+    let mut variants = list_specs(&context.config)
+        .map_err(|e| color_eyre::eyre::eyre!(e))?
+        .into_iter()
+        .map(|path| {
+            path.strip_prefix(context.config.cwd())
+                .unwrap_or(&path)
+                .display()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+
+    if !variants.is_empty() {
+        variants.push(OTHER_SPEC_FILE.to_string());
+        match Select::new(message, variants).prompt() {
+            Ok(value) if value == OTHER_SPEC_FILE => {}
+            Ok(value) => return Ok(Some(value)),
+            Err(
+                inquire::error::InquireError::OperationCanceled
+                | inquire::error::InquireError::OperationInterrupted,
+            ) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    let path = Text::new(message)
+        .with_help_message("The path should be relative to the current directory")
+        .with_validator(|p: &str| {
+            if !p.is_empty() && std::path::Path::new(p).is_file() {
+                Ok(Validation::Valid)
+            } else {
+                Ok(Validation::Invalid(
+                    "The path you entered does not exist or is not a file".into(),
+                ))
+            }
+        })
+        .prompt()?;
+    Ok(Some(path))
+}
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = BuildSpecContext)]
+pub struct BuildSpec {
+    /// Which chain-specification do you want to generate a spec for?
+    #[interactive_clap(skip_default_input_arg)]
+    chain: String,
+    #[interactive_clap(long)]
+    /// Also generate the raw version of the spec
+    raw: bool,
+}
+
+impl BuildSpec {
+    fn input_chain(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_chain_spec(context, "Which chain-specification do you want to generate a spec for?")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BuildSpecContext;
+
+impl BuildSpecContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<BuildSpec as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        if let ProjectType::Chain(chain_info) = &previous_context.config.project_type.clone().unwrap()
+        {
+            let opts = BuildSpecOptions {
+                chain: scope.chain.clone(),
+                raw: scope.raw,
+            };
+            if let Err(e) = substrate_spec::build(&opts, &previous_context.config, chain_info) {
+                return Err(color_eyre::eyre::eyre!(e));
+            }
+
+            Ok(Self)
+        } else {
+            color_eyre::eyre::bail!("Incorrect project type");
+        }
+    }
+}
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = RawSpecContext)]
+pub struct RawSpec {
+    /// Which chain spec do you want to convert?
+    #[interactive_clap(skip_default_input_arg)]
+    spec: String,
+}
+
+impl RawSpec {
+    fn input_spec(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_spec_file(context, "Which chain spec do you want to convert?")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RawSpecContext;
+
+impl RawSpecContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<RawSpec as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        if let ProjectType::Chain(chain_info) = &previous_context.config.project_type.clone().unwrap()
+        {
+            let opts = RawSpecOptions {
+                spec: PathBuf::from(&scope.spec),
+            };
+            if let Err(e) = substrate_spec::raw(&opts, &previous_context.config, chain_info) {
+                return Err(color_eyre::eyre::eyre!(e));
+            }
+
+            Ok(Self)
+        } else {
+            color_eyre::eyre::bail!("Incorrect project type");
+        }
+    }
+}
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = SetSpecContext)]
+pub struct SetSpec {
+    /// Which chain spec do you want to patch?
+    #[interactive_clap(skip_default_input_arg)]
+    spec: String,
+    /// Which values do you want to set? (e.g. sudo=5Grw... para_id=2000 /genesis/runtime/aura/authorities=[])
+    #[interactive_clap(long)]
+    set: String,
+}
+
+impl SetSpec {
+    fn input_spec(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_spec_file(context, "Which chain spec do you want to patch?")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SetSpecContext;
+
+impl SetSpecContext {
+    pub fn from_previous_context(
+        _previous_context: GlobalContext,
+        scope: &<SetSpec as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        // Several assignments can be given at once, quoted like shell arguments
+        let assignments = shell_words::split(&scope.set)?;
+        let opts = PatchSpecOptions {
+            spec: PathBuf::from(&scope.spec),
+            assignments,
+        };
+        if let Err(e) = substrate_spec::patch(&opts) {
+            return Err(color_eyre::eyre::eyre!(e));
+        }
+
+        Ok(Self)
+    }
+}
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = DiffSpecContext)]
+pub struct DiffSpec {
+    /// Which chain spec do you want to compare?
+    #[interactive_clap(skip_default_input_arg)]
+    old: String,
+    /// Which chain spec do you want to compare it with?
+    #[interactive_clap(skip_default_input_arg)]
+    new: String,
+}
+
+impl DiffSpec {
+    fn input_old(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_spec_file(context, "Which chain spec do you want to compare?")
+    }
+
+    fn input_new(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        input_spec_file(context, "Which chain spec do you want to compare it with?")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffSpecContext;
+
+impl DiffSpecContext {
+    pub fn from_previous_context(
+        _previous_context: GlobalContext,
+        scope: &<DiffSpec as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        let opts = DiffSpecOptions {
+            old: PathBuf::from(&scope.old),
+            new: PathBuf::from(&scope.new),
+        };
+        if let Err(e) = substrate_spec::diff(&opts) {
+            return Err(color_eyre::eyre::eyre!(e));
+        }
+
+        Ok(Self)
+    }
+}
@@ -1,21 +1,180 @@
-use substrate_manager::ops;
+use std::path::PathBuf;
 
-use super::GlobalContext;
+use inquire::{list_option::ListOption, validator::Validation, MultiSelect, Select};
+use substrate_manager::{
+    ops::{
+        self,
+        substrate_test::{TestMode, TestOptions},
+    },
+    util::config::{ChainMemberKind, ProjectType},
+};
+
+use super::{build::input_contracts, GlobalContext};
 
 #[derive(Debug, Clone, interactive_clap::InteractiveClap)]
 #[interactive_clap(input_context = GlobalContext)]
 #[interactive_clap(output_context = TestContext)]
-pub struct Test;
+pub struct Test {
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Packages of the workspace to test, separated by commas
+    package: String,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Tests to run in contract projects: unit, e2e or all
+    mode: String,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Only run the tests whose name contains this filter
+    filter: String,
+    #[interactive_clap(long)]
+    /// Test with optimizations
+    release: bool,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Features to activate, separated by commas or spaces
+    features: String,
+    #[interactive_clap(long)]
+    /// Compile the tests without running them
+    no_run: bool,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Write a JUnit XML report of the tests to this path
+    junit: String,
+}
+
+const TEST_MODES: [(TestMode, &str); 3] = [
+    (TestMode::Unit, "unit  - Off-chain unit tests"),
+    (TestMode::E2e, "e2e   - End-to-end tests against a local contracts node"),
+    (TestMode::All, "all   - Unit and end-to-end tests"),
+];
+
+impl Test {
+    fn input_package(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        let Some(ProjectType::Chain(info)) = &context.config.project_type else {
+            return input_contracts(context, "Which contracts do you want to test?");
+        };
+
+        let members = info
+            .members(context.config.cwd())
+            .map_err(|e| color_eyre::eyre::eyre!(e))?;
+        let variants = members
+            .iter()
+            .map(|member| format!("{:<8} {}", member.kind, member.name))
+            .collect::<Vec<_>>();
+        // The node's tests are the slowest to build, so they are only run on demand
+        let defaults = members
+            .iter()
+            .enumerate()
+            .filter(|(_, member)| member.kind != ChainMemberKind::Node)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        let selected = MultiSelect::new("Which packages do you want to test?", variants)
+            .with_default(&defaults)
+            .with_validator(|selected: &[ListOption<&String>]| {
+                if selected.is_empty() {
+                    Ok(Validation::Invalid("Select at least one package".into()))
+                } else {
+                    Ok(Validation::Valid)
+                }
+            })
+            .raw_prompt()?;
+        let names = selected
+            .iter()
+            .map(|selected| members[selected.index].name.as_str())
+            .collect::<Vec<_>>();
+        Ok(Some(names.join(",")))
+    }
+
+    fn input_mode(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        if !matches!(context.config.project_type, Some(ProjectType::Contract(_))) {
+            return Ok(Some(String::new()));
+        }
+
+        let variants = TEST_MODES.iter().map(|(_, message)| *message).collect::<Vec<_>>();
+        let selected = Select::new("Which tests do you want to run?", variants).raw_prompt()?;
+        Ok(Some(TEST_MODES[selected.index].0.to_string()))
+    }
+
+    fn input_filter(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        Ok(Some(String::new()))
+    }
+
+    fn input_features(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        Ok(Some(String::new()))
+    }
+
+    fn input_junit(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        Ok(Some(String::new()))
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct TestContext;
 
 impl TestContext {
     pub fn from_previous_context(
-        _previous_context: GlobalContext,
-        _scope: &<Test as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+        previous_context: GlobalContext,
+        scope: &<Test as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
     ) -> color_eyre::eyre::Result<Self> {
-        if let Err(e) = ops::test() {
+        let packages = match &previous_context.config.project_type {
+            Some(ProjectType::Contract(info)) if info.workspace && !scope.package.is_empty() => info
+                .select(&scope.package)
+                .map_err(|e| color_eyre::eyre::eyre!(e))?
+                .into_iter()
+                .map(|contract| contract.name)
+                .collect(),
+            Some(ProjectType::Chain(info)) => {
+                let members = info
+                    .members(previous_context.config.cwd())
+                    .map_err(|e| color_eyre::eyre::eyre!(e))?;
+                let packages = scope
+                    .package
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|package| !package.is_empty())
+                    .map(str::to_string)
+                    .collect::<Vec<_>>();
+                if let Some(unknown) = packages
+                    .iter()
+                    .find(|package| !members.iter().any(|member| &member.name == *package))
+                {
+                    let names = members.iter().map(|member| member.name.as_str()).collect::<Vec<_>>();
+                    color_eyre::eyre::bail!(
+                        "unknown package `{}`, expected one of: {}",
+                        unknown,
+                        names.join(", ")
+                    );
+                }
+                packages
+            }
+            _ => Vec::new(),
+        };
+
+        let mode = match scope.mode.as_str() {
+            "" | "unit" => TestMode::Unit,
+            "e2e" => TestMode::E2e,
+            "all" => TestMode::All,
+            mode => {
+                color_eyre::eyre::bail!("unknown test mode `{}`, expected unit, e2e or all", mode);
+            }
+        };
+
+        let opts = TestOptions {
+            packages,
+            mode,
+            filter: (!scope.filter.is_empty()).then(|| scope.filter.clone()),
+            release: scope.release,
+            features: scope
+                .features
+                .split([',', ' '])
+                .filter(|feature| !feature.is_empty())
+                .map(str::to_string)
+                .collect(),
+            no_run: scope.no_run,
+            junit: (!scope.junit.is_empty()).then(|| PathBuf::from(&scope.junit)),
+        };
+        if let Err(e) = ops::test(&opts, &previous_context.config) {
             return Err(color_eyre::eyre::eyre!(e));
         }
         Ok(Self)
@@ -0,0 +1,156 @@
+use std::path::PathBuf;
+
+use inquire::{validator::Validation, Select, Text};
+use strum::{EnumDiscriminants, EnumIter, EnumMessage};
+use substrate_manager::{
+    ops::{
+        substrate_deploy::DEFAULT_RPC_URL,
+        substrate_try_runtime::{
+            self, list_snapshots, OnRuntimeUpgradeOptions, SnapshotOptions,
+        },
+    },
+    util::config::ProjectType,
+};
+
+use super::GlobalContext;
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(context = GlobalContext)]
+pub struct TryRuntime {
+    #[interactive_clap(subcommand)]
+    command: TryRuntimeCommand,
+}
+
+#[derive(Debug, EnumDiscriminants, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(context = GlobalContext)]
+#[strum_discriminants(derive(EnumMessage, EnumIter))]
+#[non_exhaustive]
+#[expect(dead_code, reason = "interactive_clap only builds the `Cli` mirror of the menu")]
+/// What do you want to do?
+pub enum TryRuntimeCommand {
+    /// Save the state of a running node, to check upgrades against it offline
+    #[strum_discriminants(strum(message = "snapshot  - 📸 Snapshot the state of a running node into snapshots/"))]
+    Snapshot(Snapshot),
+    /// Run the runtime upgrade and the checks of its migrations against a snapshot
+    #[strum_discriminants(strum(message = "upgrade   - 🧪 Check the runtime upgrade's migrations against a snapshot"))]
+    Upgrade(CheckUpgrade),
+}
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = SnapshotContext)]
+pub struct Snapshot {
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// What's the RPC endpoint of the node?
+    uri: String,
+    #[interactive_clap(long)]
+    #[interactive_clap(skip_default_input_arg)]
+    /// Name of the snapshot
+    name: String,
+}
+
+impl Snapshot {
+    fn input_uri(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        let uri = Text::new("What's the RPC endpoint of the node?")
+            .with_default(DEFAULT_RPC_URL)
+            .prompt()?;
+        Ok(Some(uri))
+    }
+
+    fn input_name(_context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        let name = Text::new("Name of the snapshot:")
+            .with_default("local")
+            .with_help_message("Saved to snapshots/<name>.snap")
+            .prompt()?;
+        Ok(Some(name))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SnapshotContext;
+
+impl SnapshotContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<Snapshot as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        let opts = SnapshotOptions {
+            uri: scope.uri.clone(),
+            name: scope.name.clone(),
+        };
+        if let Err(e) = substrate_try_runtime::create_snapshot(&opts, &previous_context.config) {
+            return Err(color_eyre::eyre::eyre!(e));
+        }
+
+        Ok(Self)
+    }
+}
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = CheckUpgradeContext)]
+pub struct CheckUpgrade {
+    /// Which snapshot do you want to run the upgrade against?
+    #[interactive_clap(skip_default_input_arg)]
+    snapshot: String,
+}
+
+impl CheckUpgrade {
+    fn input_snapshot(context: &GlobalContext) -> color_eyre::eyre::Result<Option<String>> {
+        let message = "Which snapshot do you want to run the upgrade against?";
+        let snapshots = list_snapshots(&context.config)
+            .map_err(|e| color_eyre::eyre::eyre!(e))?
+            .into_iter()
+            .map(|path| {
+                path.strip_prefix(context.config.cwd())
+                    .unwrap_or(&path)
+                    .display()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        if !snapshots.is_empty() {
+            return Ok(Some(Select::new(message, snapshots).prompt()?));
+        }
+
+        let path = Text::new(message)
+            .with_help_message("No snapshot in snapshots/, the path should be relative to the current directory")
+            .with_validator(|p: &str| {
+                if !p.is_empty() && std::path::Path::new(p).is_file() {
+                    Ok(Validation::Valid)
+                } else {
+                    Ok(Validation::Invalid(
+                        "The path you entered does not exist or is not a file".into(),
+                    ))
+                }
+            })
+            .prompt()?;
+        Ok(Some(path))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckUpgradeContext;
+
+impl CheckUpgradeContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<CheckUpgrade as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        if let ProjectType::Chain(chain_info) = &previous_context.config.project_type.clone().unwrap()
+        {
+            let opts = OnRuntimeUpgradeOptions {
+                snapshot: PathBuf::from(&scope.snapshot),
+            };
+            if let Err(e) =
+                substrate_try_runtime::on_runtime_upgrade(&opts, &previous_context.config, chain_info)
+            {
+                return Err(color_eyre::eyre::eyre!(e));
+            }
+
+            Ok(Self)
+        } else {
+            color_eyre::eyre::bail!("Incorrect project type");
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use substrate_manager::{
+    ops::substrate_upgrade::{upgrade, UpgradeOptions},
+    util::config::ProjectType,
+};
+
+use super::GlobalContext;
+
+#[derive(Debug, Clone, interactive_clap::InteractiveClap)]
+#[interactive_clap(input_context = GlobalContext)]
+#[interactive_clap(output_context = UpgradeContext)]
+pub struct Upgrade {
+    #[interactive_clap(long)]
+    /// Also bump transaction_version, for upgrades that change existing extrinsics
+    transaction_version: bool,
+    #[interactive_clap(long)]
+    /// Build the runtime reproducibly in an srtool container
+    deterministic: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpgradeContext;
+
+impl UpgradeContext {
+    pub fn from_previous_context(
+        previous_context: GlobalContext,
+        scope: &<Upgrade as interactive_clap::ToInteractiveClapContextScope>::InteractiveClapContextScope,
+    ) -> color_eyre::eyre::Result<Self> {
+        if let ProjectType::Chain(chain_info) = &previous_context.config.project_type.clone().unwrap()
+        {
+            let opts = UpgradeOptions {
+                transaction_version: scope.transaction_version,
+                deterministic: scope.deterministic,
+            };
+            if let Err(e) = upgrade(&opts, &previous_context.config, chain_info) {
+                return Err(color_eyre::eyre::eyre!(e));
+            }
+
+            Ok(Self)
+        } else {
+            color_eyre::eyre::bail!("Incorrect project type");
+        }
+    }
+}
@@ -1,4 +1,3 @@
-use clap::Command;
 use commands::{
     CliContractCmd, CliMissingProjectCmd, ContractCmd, MissingProjectCmd,
     ChainCmd, CliChainCmd, GlobalContext,
@@ -16,13 +15,10 @@ fn main() {
 
     let result = cli(&mut config);
 
-    match result {
-        Err(e) => {
-            // TODO: Use shell across entire lifetime, not only for errors
-            let mut shell = Shell::new();
-            substrate_manager::exit_with_error(e, &mut shell)
-        }
-        Ok(()) => {}
+    if let Err(e) = result {
+        // TODO: Use shell across entire lifetime, not only for errors
+        let mut shell = Shell::new();
+        substrate_manager::exit_with_error(e, &mut shell)
     }
 }
 
@@ -87,14 +83,14 @@ fn cli(config: &mut LazyConfig) -> CliResult {
 /// after that is done but some other paths (like fix or earlier errors) might need access to it,
 /// so this provides a way to share the instance and the implementation across these different
 /// accesses.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct LazyConfig {
     config: Option<Config>,
 }
 
 impl LazyConfig {
     pub fn new() -> Self {
-        Self { config: None }
+        Self::default()
     }
 
     /// Check whether the config is loaded
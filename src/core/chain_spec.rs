@@ -0,0 +1,265 @@
+use std::{fmt, fs, path::PathBuf};
+
+use anyhow::Context as _;
+use serde_json::Value;
+
+use crate::util::SubstrateResult;
+
+/// A JSON chain-specification file, as produced by the node's `build-spec` command.
+#[derive(Debug)]
+pub struct ChainSpec {
+    path: PathBuf,
+    json: Value,
+}
+
+impl ChainSpec {
+    pub fn read(path: PathBuf) -> SubstrateResult<Self> {
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("couldn't read chain spec `{}`", path.display()))?;
+        let json = serde_json::from_str(&content)
+            .with_context(|| format!("`{}` is not a valid chain spec", path.display()))?;
+
+        Ok(Self { path, json })
+    }
+
+    pub fn write(&self) -> SubstrateResult<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(&self.json)?)?;
+        Ok(())
+    }
+
+    pub fn json(&self) -> &Value {
+        &self.json
+    }
+
+    pub fn json_mut(&mut self) -> &mut Value {
+        &mut self.json
+    }
+
+    /// Whether the genesis storage is in raw (key/value) form.
+    pub fn is_raw(&self) -> bool {
+        self.json.pointer("/genesis/raw").is_some()
+    }
+
+    /// The human readable genesis config of the runtime, if the spec isn't raw.
+    ///
+    /// Depending on the Substrate version, it lives under `genesis.runtime`,
+    /// `genesis.runtimeGenesis.config` or `genesis.runtimeGenesis.patch`.
+    pub fn runtime_genesis_mut(&mut self) -> Option<&mut Value> {
+        let pointer = [
+            "/genesis/runtime",
+            "/genesis/runtimeGenesis/config",
+            "/genesis/runtimeGenesis/patch",
+        ]
+        .into_iter()
+        .find(|pointer| self.json.pointer(pointer).is_some())?;
+
+        self.json.pointer_mut(pointer)
+    }
+
+    /// Sets the value at a JSON pointer (e.g. `/genesis/runtime/sudo/key`),
+    /// creating any missing intermediate objects.
+    pub fn set(&mut self, pointer: &str, new_value: Value) -> SubstrateResult<()> {
+        set_pointer(&mut self.json, pointer, new_value)
+    }
+
+    /// Sets a value in the runtime genesis config, `pointer` being relative to it
+    /// (e.g. `/sudo/key`).
+    pub fn set_genesis(&mut self, pointer: &str, new_value: Value) -> SubstrateResult<()> {
+        let genesis = self
+            .runtime_genesis_mut()
+            .with_context(|| "cannot patch the genesis of a raw chain spec")?;
+        set_pointer(genesis, pointer, new_value)
+    }
+
+    /// Sets the id of the relay chain a parachain spec belongs to.
+    pub fn set_relay_chain(&mut self, relay_chain: &str) {
+        if let Some(spec) = self.json.as_object_mut() {
+            for key in ["relay_chain", "relayChain"] {
+                if let Some(extension) = spec.get_mut(key) {
+                    *extension = relay_chain.into();
+                }
+            }
+        }
+    }
+
+    /// Sets the parachain id, both in the spec extensions and in the `parachainInfo` pallet.
+    pub fn set_para_id(&mut self, para_id: u32) -> SubstrateResult<()> {
+        // Extensions are flattened into the top level object of the spec
+        if let Some(spec) = self.json.as_object_mut() {
+            for key in ["para_id", "paraId"] {
+                if let Some(extension) = spec.get_mut(key) {
+                    *extension = para_id.into();
+                }
+            }
+        }
+
+        let genesis = self
+            .runtime_genesis_mut()
+            .with_context(|| "cannot set the para id of a raw chain spec")?;
+        let parachain_info = genesis
+            .as_object_mut()
+            .with_context(|| "unexpected runtime genesis format")?
+            .entry("parachainInfo")
+            .or_insert_with(|| Value::Object(Default::default()));
+        parachain_info["parachainId"] = para_id.into();
+
+        Ok(())
+    }
+}
+
+fn set_pointer(mut current: &mut Value, pointer: &str, new_value: Value) -> SubstrateResult<()> {
+    if !pointer.starts_with('/') {
+        anyhow::bail!("`{}` is not a JSON pointer, it should start with `/`", pointer);
+    }
+
+    for token in pointer[1..].split('/') {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        current = match current {
+            Value::Object(map) => map
+                .entry(token)
+                .or_insert_with(|| Value::Object(Default::default())),
+            Value::Array(array) => {
+                let index = token
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|index| *index < array.len())
+                    .with_context(|| format!("invalid array index `{}` in `{}`", token, pointer))?;
+                &mut array[index]
+            }
+            _ => anyhow::bail!("`{}` goes through a value that isn't an object", pointer),
+        };
+    }
+    *current = new_value;
+
+    Ok(())
+}
+
+/// A single difference between two chain specs.
+#[derive(Debug, PartialEq)]
+pub enum SpecDifference {
+    Added(String, Value),
+    Removed(String, Value),
+    Changed(String, Value, Value),
+}
+
+impl fmt::Display for SpecDifference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Added(pointer, value) => write!(f, "+ {}: {}", pointer, abbreviate(value)),
+            Self::Removed(pointer, value) => write!(f, "- {}: {}", pointer, abbreviate(value)),
+            Self::Changed(pointer, old, new) => write!(
+                f,
+                "~ {}: {} -> {}",
+                pointer,
+                abbreviate(old),
+                abbreviate(new)
+            ),
+        }
+    }
+}
+
+/// Shortens long values, such as the runtime code, so that diffs stay readable.
+fn abbreviate(value: &Value) -> String {
+    const MAX_LEN: usize = 80;
+
+    let value = value.to_string();
+    if value.chars().count() > MAX_LEN {
+        let head = value.chars().take(MAX_LEN).collect::<String>();
+        format!("{}… ({} bytes)", head, value.len())
+    } else {
+        value
+    }
+}
+
+/// Lists the differences between two chain specs, keyed by JSON pointer.
+pub fn diff(old: &Value, new: &Value) -> Vec<SpecDifference> {
+    let mut differences = Vec::new();
+    diff_at("", old, new, &mut differences);
+    differences
+}
+
+fn diff_at(pointer: &str, old: &Value, new: &Value, differences: &mut Vec<SpecDifference>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (key, old_value) in old_map {
+                let child = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+                match new_map.get(key) {
+                    Some(new_value) => diff_at(&child, old_value, new_value, differences),
+                    None => differences.push(SpecDifference::Removed(child, old_value.clone())),
+                }
+            }
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    let child =
+                        format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+                    differences.push(SpecDifference::Added(child, new_value.clone()));
+                }
+            }
+        }
+        (Value::Array(old_array), Value::Array(new_array))
+            if old_array.len() == new_array.len() =>
+        {
+            for (index, (old_value, new_value)) in old_array.iter().zip(new_array).enumerate() {
+                diff_at(
+                    &format!("{}/{}", pointer, index),
+                    old_value,
+                    new_value,
+                    differences,
+                );
+            }
+        }
+        _ if old != new => differences.push(SpecDifference::Changed(
+            pointer.to_string(),
+            old.clone(),
+            new.clone(),
+        )),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_set_creates_missing_objects() {
+        let mut spec = ChainSpec {
+            path: PathBuf::new(),
+            json: json!({ "genesis": { "runtime": { "balances": { "balances": [] } } } }),
+        };
+
+        spec.set("/genesis/runtime/sudo/key", json!("5GrwvaEF")).unwrap();
+        spec.set("/genesis/runtime/balances/balances", json!([["5GrwvaEF", 1000]]))
+            .unwrap();
+
+        assert_eq!(
+            spec.json(),
+            &json!({ "genesis": { "runtime": {
+                "balances": { "balances": [["5GrwvaEF", 1000]] },
+                "sudo": { "key": "5GrwvaEF" },
+            } } })
+        );
+        spec.set_genesis("/sudo/key", json!("5FHneW46")).unwrap();
+        assert_eq!(spec.json()["genesis"]["runtime"]["sudo"]["key"], "5FHneW46");
+        assert!(spec.set("genesis", json!(1)).is_err());
+        assert!(spec.set("/genesis/runtime/sudo/key/nested", json!(1)).is_err());
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = json!({ "name": "Local", "para_id": 1000, "bootNodes": ["a"], "removed": 1 });
+        let new = json!({ "name": "Local", "para_id": 2000, "bootNodes": ["b"], "added": 2 });
+
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                SpecDifference::Changed("/bootNodes/0".into(), json!("a"), json!("b")),
+                SpecDifference::Changed("/para_id".into(), json!(1000), json!(2000)),
+                SpecDifference::Removed("/removed".into(), json!(1)),
+                SpecDifference::Added("/added".into(), json!(2)),
+            ]
+        );
+    }
+}
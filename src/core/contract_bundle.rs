@@ -0,0 +1,199 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Context as _;
+use serde_json::Value;
+
+use crate::util::SubstrateResult;
+
+/// An argument of a contract constructor or message.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ContractArg {
+    pub label: String,
+    /// The type's display name, e.g. `AccountId` or `Balance`
+    pub type_name: String,
+}
+
+/// A constructor or message of a contract, as described by its metadata.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ContractFunction {
+    pub label: String,
+    pub selector: String,
+    pub args: Vec<ContractArg>,
+    pub payable: bool,
+    /// Whether a message changes the contract's state, always true for constructors
+    pub mutates: bool,
+    pub return_type: Option<String>,
+}
+
+impl ContractFunction {
+    /// The function's signature, e.g. `transfer(to: AccountId, value: Balance)`.
+    pub fn signature(&self) -> String {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| format!("{}: {}", arg.label, arg.type_name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{}({})", self.label, args)
+    }
+}
+
+/// A `<name>.contract` bundle (or `<name>.json` metadata) produced by `cargo contract build`.
+#[derive(Debug)]
+pub struct ContractBundle {
+    path: PathBuf,
+    json: Value,
+}
+
+/// The last segment of a type's `displayName` path, e.g. `AccountId` for `["ink_primitives", "AccountId"]`.
+fn display_name(type_spec: &Value) -> Option<String> {
+    let segments = type_spec["displayName"]
+        .as_array()?
+        .iter()
+        .filter_map(Value::as_str)
+        .collect::<Vec<_>>();
+    segments.last().map(|name| name.to_string())
+}
+
+impl ContractBundle {
+    pub fn read(path: PathBuf) -> SubstrateResult<Self> {
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("couldn't read `{}`", path.display()))?;
+        let json = serde_json::from_str(&content)
+            .with_context(|| format!("`{}` is not valid contract metadata", path.display()))?;
+
+        Ok(Self { path, json })
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.json["contract"]["name"].as_str()
+    }
+
+    pub fn code_hash(&self) -> Option<&str> {
+        self.json["source"]["hash"].as_str()
+    }
+
+    /// The contract's wasm, only embedded in `.contract` bundles.
+    pub fn wasm(&self) -> SubstrateResult<Option<Vec<u8>>> {
+        let Some(wasm) = self.json["source"]["wasm"].as_str() else {
+            return Ok(None);
+        };
+
+        let hex = wasm.trim_start_matches("0x");
+        if hex.len() % 2 != 0 || !hex.is_ascii() {
+            anyhow::bail!("invalid wasm in the contract bundle");
+        }
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| "invalid wasm in the contract bundle")?;
+
+        Ok(Some(bytes))
+    }
+
+    /// The `spec` section, at the top level since ink! 4 and under `V3` before.
+    fn spec(&self) -> &Value {
+        match &self.json["spec"] {
+            Value::Null => &self.json["V3"]["spec"],
+            spec => spec,
+        }
+    }
+
+    fn functions(&self, kind: &str) -> Vec<ContractFunction> {
+        let Some(functions) = self.spec()[kind].as_array() else {
+            return Vec::new();
+        };
+
+        functions
+            .iter()
+            .map(|function| ContractFunction {
+                // ink! 3 metadata stores the label as a single item `name` array
+                label: function["label"]
+                    .as_str()
+                    .or_else(|| function["name"][0].as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                selector: function["selector"].as_str().unwrap_or_default().to_string(),
+                args: function["args"]
+                    .as_array()
+                    .map(|args| {
+                        args.iter()
+                            .map(|arg| ContractArg {
+                                label: arg["label"]
+                                    .as_str()
+                                    .or_else(|| arg["name"].as_str())
+                                    .unwrap_or_default()
+                                    .to_string(),
+                                type_name: display_name(&arg["type"]).unwrap_or_default(),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                payable: function["payable"].as_bool().unwrap_or(false),
+                mutates: function["mutates"].as_bool().unwrap_or(kind == "constructors"),
+                return_type: display_name(&function["returnType"]),
+            })
+            .collect()
+    }
+
+    pub fn constructors(&self) -> Vec<ContractFunction> {
+        self.functions("constructors")
+    }
+
+    pub fn messages(&self) -> Vec<ContractFunction> {
+        self.functions("messages")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_contract_bundle() {
+        let arg = |label: &str, ty: &[&str]| json!({ "label": label, "type": { "displayName": ty, "type": 0 } });
+        let bundle = ContractBundle {
+            path: PathBuf::new(),
+            json: json!({
+                "source": { "hash": "0x12ab", "wasm": "0x0061736d" },
+                "contract": { "name": "flipper" },
+                "spec": {
+                    "constructors": [
+                        { "label": "new", "selector": "0x9bae9d5e", "payable": false, "args": [arg("init_value", &["bool"])] },
+                    ],
+                    "messages": [
+                        { "label": "flip", "selector": "0x633aa551", "mutates": true, "payable": false, "args": [], "returnType": null },
+                        {
+                            "label": "get", "selector": "0x2f865bd9", "mutates": false, "payable": false, "args": [],
+                            "returnType": { "displayName": ["ink", "MessageResult"], "type": 1 },
+                        },
+                    ],
+                },
+            }),
+        };
+
+        assert_eq!(bundle.name(), Some("flipper"));
+        assert_eq!(bundle.code_hash(), Some("0x12ab"));
+        assert_eq!(bundle.wasm().unwrap(), Some(b"\0asm".to_vec()));
+
+        let constructors = bundle.constructors();
+        assert_eq!(constructors.len(), 1);
+        assert_eq!(constructors[0].signature(), "new(init_value: bool)");
+        assert!(constructors[0].mutates);
+
+        let messages = bundle.messages();
+        assert_eq!(
+            messages.iter().map(|m| m.signature()).collect::<Vec<_>>(),
+            ["flip()", "get()"]
+        );
+        assert!(!messages[1].mutates);
+        assert_eq!(messages[1].return_type.as_deref(), Some("MessageResult"));
+    }
+}
@@ -25,6 +25,14 @@ impl Manifest {
             .map_err(|e| anyhow::Error::from(e).context("could not parse input as TOML"))
     }
 
+    /// Reads the document, starting from an empty one if the file doesn't exist yet.
+    pub fn read_or_new_document(&mut self) -> SubstrateResult<Document> {
+        if !self.path.exists() {
+            return Ok(Document::new());
+        }
+        self.read_document()
+    }
+
     pub fn write_document(&mut self, document: Document) -> SubstrateResult<()> {
         let toml = document.to_string();
         let bytes = toml.as_bytes();
@@ -40,6 +48,7 @@ impl Manifest {
             .create(true)
             .read(true)
             .write(true)
+            .truncate(false)
             .append(false)
             .open(path)?;
 
@@ -1,4 +1,10 @@
 pub use self::shell::{Shell, Verbosity};
 
+pub mod chain_spec;
+pub mod contract_bundle;
+pub mod runtime_wasm;
 pub mod manifest;
+pub mod pallet_source;
+pub mod runtime_source;
 pub mod shell;
+pub mod test_report;
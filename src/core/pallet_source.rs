@@ -0,0 +1,624 @@
+use std::str::FromStr;
+
+use anyhow::Context as _;
+use proc_macro2::LineColumn;
+use syn::spanned::Spanned;
+
+use crate::{core::runtime_source::byte_offset, util::SubstrateResult};
+
+/// Hashers of storage map keys, the first one being the default.
+pub const HASHERS: [&str; 3] = ["Blake2_128Concat", "Twox64Concat", "Identity"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumIter)]
+#[strum(serialize_all = "kebab-case")]
+pub enum StorageKind {
+    Value,
+    Map,
+    DoubleMap,
+    NMap,
+}
+
+impl StorageKind {
+    /// How many keys the storage takes, `None` for any number of them.
+    pub fn key_count(self) -> Option<usize> {
+        match self {
+            StorageKind::Value => Some(0),
+            StorageKind::Map => Some(1),
+            StorageKind::DoubleMap => Some(2),
+            StorageKind::NMap => None,
+        }
+    }
+}
+
+impl FromStr for StorageKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "value" => Ok(StorageKind::Value),
+            "map" => Ok(StorageKind::Map),
+            "double-map" => Ok(StorageKind::DoubleMap),
+            "n-map" | "nmap" => Ok(StorageKind::NMap),
+            _ => anyhow::bail!("unknown storage kind `{}`, expected value, map, double-map or n-map", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageKey {
+    pub hasher: String,
+    pub ty: String,
+}
+
+impl FromStr for StorageKey {
+    type Err = anyhow::Error;
+
+    /// Parses `Twox64Concat u32`, or only the type for the default hasher.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (hasher, ty) = match s.split_once(char::is_whitespace) {
+            Some((hasher, ty)) if HASHERS.contains(&hasher) => (hasher, ty.trim()),
+            _ => (HASHERS[0], s),
+        };
+        parse_type(ty)?;
+        Ok(StorageKey {
+            hasher: hasher.to_string(),
+            ty: ty.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StorageItem {
+    pub name: String,
+    pub kind: StorageKind,
+    pub keys: Vec<StorageKey>,
+    pub value: String,
+    /// Whether reading a missing value returns its default instead of `None`
+    pub value_query: bool,
+}
+
+impl StorageItem {
+    fn declaration(&self) -> SubstrateResult<String> {
+        parse_ident(&self.name)?;
+        parse_type(&self.value)?;
+        match self.kind.key_count() {
+            Some(count) if count != self.keys.len() => {
+                anyhow::bail!("a {} storage takes {} key(s), got {}", self.kind, count, self.keys.len());
+            }
+            None if self.keys.is_empty() => anyhow::bail!("an n-map storage takes at least one key"),
+            _ => {}
+        }
+
+        let mut args = vec!["_".to_string()];
+        match self.kind {
+            StorageKind::Value => {}
+            StorageKind::Map | StorageKind::DoubleMap => {
+                for key in &self.keys {
+                    args.extend([key.hasher.clone(), key.ty.clone()]);
+                }
+            }
+            StorageKind::NMap => {
+                let keys = self
+                    .keys
+                    .iter()
+                    .map(|key| format!("NMapKey<{}, {}>", key.hasher, key.ty))
+                    .collect::<Vec<_>>();
+                args.push(match keys.as_slice() {
+                    [key] => key.clone(),
+                    keys => format!("({})", keys.join(", ")),
+                });
+            }
+        }
+        args.push(self.value.clone());
+        if self.value_query {
+            args.push("ValueQuery".to_string());
+        }
+
+        let storage = match self.kind {
+            StorageKind::Value => "StorageValue",
+            StorageKind::Map => "StorageMap",
+            StorageKind::DoubleMap => "StorageDoubleMap",
+            StorageKind::NMap => "StorageNMap",
+        };
+        Ok(format!("pub type {}<T> = {}<{}>;", self.name, storage, args.join(", ")))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CallItem {
+    pub name: String,
+    /// Arguments after the origin
+    pub params: Vec<(String, String)>,
+    /// Expression of the `#[pallet::weight]` attribute
+    pub weight: String,
+}
+
+/// An event or error variant, errors have no fields.
+#[derive(Debug, Clone)]
+pub struct Variant {
+    pub name: String,
+    pub fields: Vec<(String, String)>,
+}
+
+fn parse_ident(name: &str) -> SubstrateResult<()> {
+    syn::parse_str::<syn::Ident>(name).with_context(|| format!("`{}` is not a valid identifier", name))?;
+    Ok(())
+}
+
+fn parse_type(ty: &str) -> SubstrateResult<()> {
+    syn::parse_str::<syn::Type>(ty).with_context(|| format!("`{}` is not a valid type", ty))?;
+    Ok(())
+}
+
+/// Splits `text` on the commas that aren't nested in brackets, e.g. those of `BoundedVec<u8, T::MaxLen>`.
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (offset, c) in text.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&text[start..offset]);
+                start = offset + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// Parses `name: Type` pairs separated by commas, e.g. `who: T::AccountId, amount: u128`.
+pub fn parse_fields(text: &str) -> SubstrateResult<Vec<(String, String)>> {
+    split_top_level(text)
+        .into_iter()
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .map(|field| {
+            let (name, ty) = field
+                .split_once(':')
+                .with_context(|| format!("expected `name: Type`, got `{}`", field))?;
+            let (name, ty) = (name.trim(), ty.trim());
+            parse_ident(name)?;
+            parse_type(ty)?;
+            Ok((name.to_string(), ty.to_string()))
+        })
+        .collect()
+}
+
+/// Splits `text` into storage keys separated by semicolons, e.g. `T::AccountId; Twox64Concat u32`.
+pub fn parse_storage_keys(text: &str) -> SubstrateResult<Vec<StorageKey>> {
+    text.split(';')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(StorageKey::from_str)
+        .collect()
+}
+
+/// Whether `attr` is `#[pallet::<name>]`, with or without arguments.
+fn is_pallet_attr(attr: &syn::Attribute, name: &str) -> bool {
+    let segments = &attr.path().segments;
+    segments.len() == 2 && segments[0].ident == "pallet" && segments[1].ident == name
+}
+
+fn has_pallet_attr(attrs: &[syn::Attribute], name: &str) -> bool {
+    attrs.iter().any(|attr| is_pallet_attr(attr, name))
+}
+
+/// The items of the `#[frame_support::pallet] mod pallet`, with the span of the module's closing brace.
+fn pallet_items(file: &syn::File) -> SubstrateResult<(&[syn::Item], LineColumn)> {
+    let module = file
+        .items
+        .iter()
+        .find_map(|item| match item {
+            syn::Item::Mod(module)
+                if module
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path().segments.last().is_some_and(|s| s.ident == "pallet")) =>
+            {
+                Some(module)
+            }
+            _ => None,
+        })
+        .with_context(|| "no `#[frame_support::pallet]` module found in the pallet's lib.rs")?;
+    let (brace, items) = module
+        .content
+        .as_ref()
+        .with_context(|| "the pallet module is declared in another file")?;
+
+    Ok((items, brace.span.close().start()))
+}
+
+fn item_attrs(item: &syn::Item) -> &[syn::Attribute] {
+    match item {
+        syn::Item::Type(item) => &item.attrs,
+        syn::Item::Trait(item) => &item.attrs,
+        syn::Item::Enum(item) => &item.attrs,
+        syn::Item::Impl(item) => &item.attrs,
+        syn::Item::Struct(item) => &item.attrs,
+        _ => &[],
+    }
+}
+
+/// Offset of the start of the line containing `offset`.
+fn line_start(content: &str, offset: usize) -> usize {
+    content[..offset].rfind('\n').map_or(0, |newline| newline + 1)
+}
+
+/// The whitespace indenting the line containing `offset`.
+fn line_indent(content: &str, offset: usize) -> &str {
+    let line = &content[line_start(content, offset)..];
+    let line = line.split('\n').next().unwrap_or_default();
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// The file's indentation unit: tabs, as in Substrate, or four spaces.
+fn indent_unit(content: &str) -> &'static str {
+    if content.lines().any(|line| line.starts_with('\t')) {
+        "\t"
+    } else {
+        "    "
+    }
+}
+
+/// Indents each non-empty line of `text` with `indent`.
+fn indent_lines(text: &str, indent: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", indent, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Inserts `text` (unindented, tab-indented inside) as the last member of the block closing at `close`.
+fn insert_member(content: &str, close: LineColumn, text: &str, separate: bool) -> (usize, String) {
+    let close_offset = byte_offset(content, close);
+    let close_indent = line_indent(content, close_offset);
+    let unit = indent_unit(content);
+    let indent = format!("{}{}", close_indent, unit);
+    let text = indent_lines(&text.replace('\t', unit), &indent);
+    let separator = if separate { "\n" } else { "" };
+
+    let start = line_start(content, close_offset);
+    if content[start..close_offset].trim().is_empty() {
+        (start, format!("{}{}\n", separator, text))
+    } else {
+        // The block closes on the same line as its last member
+        (close_offset, format!("\n{}{}\n{}", separator, text, close_indent))
+    }
+}
+
+/// Applies the `(offset, text)` insertions, from the end of the file so that offsets stay valid.
+fn apply_insertions(content: &str, mut insertions: Vec<(usize, String)>) -> String {
+    insertions.sort_by_key(|(offset, _)| std::cmp::Reverse(*offset));
+    let mut content = content.to_string();
+    for (offset, text) in insertions {
+        content.insert_str(offset, &text);
+    }
+    content
+}
+
+/// Adds a `#[pallet::storage]` item after the pallet's last one, or after its `Config` trait.
+pub fn add_storage(content: &str, storage: &StorageItem) -> SubstrateResult<String> {
+    let declaration = storage.declaration()?;
+    let file = syn::parse_file(content).with_context(|| "couldn't parse the pallet's lib.rs")?;
+    let (items, close) = pallet_items(&file)?;
+
+    if items
+        .iter()
+        .any(|item| matches!(item, syn::Item::Type(item) if item.ident == storage.name))
+    {
+        anyhow::bail!("the pallet already has a `{}` type", storage.name);
+    }
+
+    let anchor = items
+        .iter()
+        .rev()
+        .find(|item| matches!(item, syn::Item::Type(_)) && has_pallet_attr(item_attrs(item), "storage"))
+        .or_else(|| items.iter().find(|item| has_pallet_attr(item_attrs(item), "config")));
+    let text = format!("#[pallet::storage]\n{}", declaration);
+    let insertion = match anchor {
+        Some(anchor) => {
+            let indent = line_indent(content, byte_offset(content, anchor.span().start()));
+            (
+                byte_offset(content, anchor.span().end()),
+                format!("\n\n{}", indent_lines(&text, indent)),
+            )
+        }
+        None => insert_member(content, close, &text, true),
+    };
+
+    Ok(apply_insertions(content, vec![insertion]))
+}
+
+/// Whether the pallet's `Config` declares a `WeightInfo` type for the weights of its calls.
+pub fn has_weight_info(content: &str) -> SubstrateResult<bool> {
+    let file = syn::parse_file(content).with_context(|| "couldn't parse the pallet's lib.rs")?;
+    let (items, _) = pallet_items(&file)?;
+    Ok(items.iter().any(|item| match item {
+        syn::Item::Trait(config) if has_pallet_attr(&config.attrs, "config") => config
+            .items
+            .iter()
+            .any(|item| matches!(item, syn::TraitItem::Type(ty) if ty.ident == "WeightInfo")),
+        _ => false,
+    }))
+}
+
+/// Adds a dispatchable to the `#[pallet::call]` impl, with the next call index.
+///
+/// Returns the updated source and the call's index.
+pub fn add_call(content: &str, call: &CallItem) -> SubstrateResult<(String, u8)> {
+    parse_ident(&call.name)?;
+    syn::parse_str::<syn::Expr>(&call.weight)
+        .with_context(|| format!("`{}` is not a valid weight expression", call.weight))?;
+    let file = syn::parse_file(content).with_context(|| "couldn't parse the pallet's lib.rs")?;
+    let (items, _) = pallet_items(&file)?;
+    let calls = items
+        .iter()
+        .find_map(|item| match item {
+            syn::Item::Impl(calls) if has_pallet_attr(&calls.attrs, "call") => Some(calls),
+            _ => None,
+        })
+        .with_context(|| "no `#[pallet::call]` impl found in the pallet")?;
+
+    let functions = calls
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::ImplItem::Fn(function) => Some(function),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if functions.iter().any(|function| function.sig.ident == call.name) {
+        anyhow::bail!("the pallet already has a `{}` call", call.name);
+    }
+    let explicit_indices = functions
+        .iter()
+        .flat_map(|function| &function.attrs)
+        .filter(|attr| is_pallet_attr(attr, "call_index"))
+        .filter_map(|attr| attr.parse_args::<syn::LitInt>().ok()?.base10_parse::<u8>().ok());
+    let index = explicit_indices
+        .map(|index| index as usize + 1)
+        .chain([functions.len()])
+        .max()
+        .unwrap_or_default();
+    let index = u8::try_from(index).with_context(|| "the pallet has no call index left")?;
+
+    let params = call
+        .params
+        .iter()
+        .map(|(name, ty)| format!(", {}: {}", name, ty))
+        .collect::<String>();
+    let text = format!(
+        "#[pallet::call_index({index})]\n\
+         #[pallet::weight({weight})]\n\
+         pub fn {name}(origin: OriginFor<T>{params}) -> DispatchResult {{\n\
+         \tlet _who = ensure_signed(origin)?;\n\
+         \n\
+         \tOk(())\n\
+         }}",
+        index = index,
+        weight = call.weight,
+        name = call.name,
+        params = params,
+    );
+    syn::parse_str::<syn::ImplItemFn>(&text).with_context(|| format!("couldn't generate the `{}` call", call.name))?;
+
+    let insertion = insert_member(content, calls.brace_token.span.close().start(), &text, !calls.items.is_empty());
+    Ok((apply_insertions(content, vec![insertion]), index))
+}
+
+/// Adds a weight function to the `WeightInfo` trait and to its implementations.
+///
+/// Returns `None` when `content` doesn't declare the `WeightInfo` trait.
+pub fn add_weight_function(content: &str, name: &str) -> SubstrateResult<Option<String>> {
+    let file = syn::parse_file(content).with_context(|| "couldn't parse the pallet's weights")?;
+    let Some(weight_info) = file.items.iter().find_map(|item| match item {
+        syn::Item::Trait(weight_info) if weight_info.ident == "WeightInfo" => Some(weight_info),
+        _ => None,
+    }) else {
+        return Ok(None);
+    };
+    let declared = weight_info
+        .items
+        .iter()
+        .any(|item| matches!(item, syn::TraitItem::Fn(function) if function.sig.ident == name));
+    if declared {
+        return Ok(Some(content.to_string()));
+    }
+
+    let mut insertions = vec![insert_member(
+        content,
+        weight_info.brace_token.span.close().start(),
+        &format!("fn {}() -> Weight;", name),
+        false,
+    )];
+    let body = format!(
+        "fn {}() -> Weight {{\n\t// Placeholder until the pallet's benchmarks measure it\n\tWeight::from_parts(10_000_000, 0)\n}}",
+        name
+    );
+    for item in &file.items {
+        let syn::Item::Impl(implementation) = item else {
+            continue;
+        };
+        let implements_weight_info = implementation
+            .trait_
+            .as_ref()
+            .and_then(|(_, path, _)| path.segments.last())
+            .is_some_and(|segment| segment.ident == "WeightInfo");
+        if implements_weight_info {
+            insertions.push(insert_member(
+                content,
+                implementation.brace_token.span.close().start(),
+                &body,
+                false,
+            ));
+        }
+    }
+
+    Ok(Some(apply_insertions(content, insertions)))
+}
+
+/// Adds a variant to the pallet's enum with the `#[pallet::<attr>]` attribute, e.g. `event`.
+fn add_variant(content: &str, attr: &str, variant: &Variant) -> SubstrateResult<String> {
+    parse_ident(&variant.name)?;
+    let file = syn::parse_file(content).with_context(|| "couldn't parse the pallet's lib.rs")?;
+    let (items, _) = pallet_items(&file)?;
+    let enumeration = items
+        .iter()
+        .find_map(|item| match item {
+            syn::Item::Enum(enumeration) if has_pallet_attr(&enumeration.attrs, attr) => Some(enumeration),
+            _ => None,
+        })
+        .with_context(|| format!("no `#[pallet::{}]` enum found in the pallet", attr))?;
+    if enumeration.variants.iter().any(|v| v.ident == variant.name) {
+        anyhow::bail!("the pallet's `{}` already has a `{}` variant", enumeration.ident, variant.name);
+    }
+
+    let text = if variant.fields.is_empty() {
+        format!("{},", variant.name)
+    } else {
+        let fields = variant
+            .fields
+            .iter()
+            .map(|(name, ty)| format!("{}: {}", name, ty))
+            .collect::<Vec<_>>();
+        format!("{} {{ {} }},", variant.name, fields.join(", "))
+    };
+
+    let mut insertions = vec![insert_member(
+        content,
+        enumeration.brace_token.span.close().start(),
+        &text,
+        false,
+    )];
+    if let Some(last) = enumeration.variants.last() {
+        if !enumeration.variants.trailing_punct() {
+            insertions.push((byte_offset(content, last.span().end()), ",".to_string()));
+        }
+    }
+
+    Ok(apply_insertions(content, insertions))
+}
+
+pub fn add_event(content: &str, variant: &Variant) -> SubstrateResult<String> {
+    add_variant(content, "event", variant)
+}
+
+pub fn add_error(content: &str, name: &str) -> SubstrateResult<String> {
+    let variant = Variant {
+        name: name.to_string(),
+        fields: Vec::new(),
+    };
+    add_variant(content, "error", &variant)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PALLET: &str = "#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::storage]
+	pub type Something<T> = StorageValue<_, u32>;
+
+	#[pallet::event]
+	pub enum Event<T: Config> {
+		SomethingStored { something: u32, who: T::AccountId }
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::do_something())]
+		pub fn do_something(origin: OriginFor<T>, something: u32) -> DispatchResult {
+			Ok(())
+		}
+	}
+}
+";
+
+    #[test]
+    fn test_add_components() {
+        let storage = StorageItem {
+            name: "Owners".to_string(),
+            kind: StorageKind::DoubleMap,
+            keys: parse_storage_keys("T::AccountId; Twox64Concat u32").unwrap(),
+            value: "BoundedVec<u8, ConstU32<32>>".to_string(),
+            value_query: true,
+        };
+        let content = add_storage(PALLET, &storage).unwrap();
+        assert!(content.contains(
+            "StorageValue<_, u32>;\n\n\t#[pallet::storage]\n\tpub type Owners<T> = StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, u32, BoundedVec<u8, ConstU32<32>>, ValueQuery>;\n\n\t#[pallet::event]"
+        ));
+
+        let call = CallItem {
+            name: "transfer".to_string(),
+            params: parse_fields("to: T::AccountId, amounts: BoundedVec<u32, ConstU32<4>>").unwrap(),
+            weight: "T::WeightInfo::transfer()".to_string(),
+        };
+        assert!(has_weight_info(&content).unwrap());
+        let (content, index) = add_call(&content, &call).unwrap();
+        assert_eq!(index, 1);
+        assert!(content.contains(
+            "\t\t}\n\n\t\t#[pallet::call_index(1)]\n\t\t#[pallet::weight(T::WeightInfo::transfer())]\n\t\tpub fn transfer(origin: OriginFor<T>, to: T::AccountId, amounts: BoundedVec<u32, ConstU32<4>>) -> DispatchResult {\n\t\t\tlet _who = ensure_signed(origin)?;\n\n\t\t\tOk(())\n\t\t}\n\t}\n}"
+        ));
+
+        let variant = Variant {
+            name: "Transferred".to_string(),
+            fields: parse_fields("to: T::AccountId").unwrap(),
+        };
+        let content = add_event(&content, &variant).unwrap();
+        assert!(content.contains(
+            "SomethingStored { something: u32, who: T::AccountId },\n\t\tTransferred { to: T::AccountId },\n\t}"
+        ));
+
+        let content = add_error(&content, "NotOwner").unwrap();
+        assert!(content.contains("pub enum Error<T> {\n\t\tNotOwner,\n\t}"));
+        assert!(add_error(&content, "NotOwner").is_err());
+        syn::parse_file(&content).unwrap();
+    }
+
+    #[test]
+    fn test_add_weight_function() {
+        let weights = "pub trait WeightInfo {
+	fn do_something() -> Weight;
+}
+
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn do_something() -> Weight {
+		Weight::from_parts(9_000_000, 0)
+	}
+}
+
+impl WeightInfo for () {
+	fn do_something() -> Weight {
+		Weight::from_parts(9_000_000, 0)
+	}
+}
+";
+        let content = add_weight_function(weights, "transfer").unwrap().unwrap();
+        assert!(content.contains("\tfn do_something() -> Weight;\n\tfn transfer() -> Weight;\n}"));
+        assert_eq!(content.matches("fn transfer() -> Weight {").count(), 2);
+        assert_eq!(add_weight_function("fn main() {}", "transfer").unwrap(), None);
+    }
+}
@@ -0,0 +1,503 @@
+use anyhow::Context as _;
+use proc_macro2::{Delimiter, LineColumn, TokenTree};
+use syn::{
+    spanned::Spanned,
+    visit::{self, Visit},
+};
+
+use crate::util::SubstrateResult;
+
+/// A pallet declared in `construct_runtime!`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RuntimePallet {
+    pub name: String,
+    pub index: u8,
+    /// Whether the index is declared (`= 7`) rather than implied by the pallet's position
+    pub explicit_index: bool,
+    /// Crate of the pallet, e.g. `pallet_balances`
+    pub crate_name: String,
+    /// Instance of instantiable pallets, e.g. `Instance1`
+    pub instance: Option<String>,
+    /// Parts listed in the declaration, empty when they're all included implicitly
+    pub parts: Vec<String>,
+}
+
+/// An `impl <pallet>::Config for Runtime` block.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RuntimeConfig {
+    /// Path of the pallet's crate, e.g. `pallet_balances`
+    pub crate_name: String,
+    /// Generic argument of `Config`, e.g. `Instance1`
+    pub instance: Option<String>,
+    /// The associated types with their values, as written in the source
+    pub types: Vec<(String, String)>,
+}
+
+/// A field of the runtime's `VERSION` constant that got incremented.
+#[derive(Debug, PartialEq, Eq)]
+pub struct VersionBump {
+    pub field: String,
+    pub old: u32,
+    pub new: u32,
+}
+
+/// An integer field of the runtime's `VERSION` constant, e.g. `spec_version: 100`.
+#[derive(Debug)]
+struct VersionField {
+    name: String,
+    value: u32,
+    start: LineColumn,
+    end: LineColumn,
+}
+
+#[derive(Default)]
+struct RuntimeVersionVisitor {
+    fields: Vec<VersionField>,
+}
+
+impl<'ast> Visit<'ast> for RuntimeVersionVisitor {
+    fn visit_item_const(&mut self, item: &'ast syn::ItemConst) {
+        let is_runtime_version = matches!(
+            item.ty.as_ref(),
+            syn::Type::Path(ty) if ty.path.segments.last().is_some_and(|s| s.ident == "RuntimeVersion")
+        );
+
+        if let (true, syn::Expr::Struct(expr)) = (is_runtime_version, item.expr.as_ref()) {
+            for field in &expr.fields {
+                let (syn::Member::Named(name), syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(lit), .. })) =
+                    (&field.member, &field.expr)
+                else {
+                    continue;
+                };
+                if let Ok(value) = lit.base10_parse::<u32>() {
+                    self.fields.push(VersionField {
+                        name: name.to_string(),
+                        value,
+                        start: lit.span().start(),
+                        end: lit.span().end(),
+                    });
+                }
+            }
+        }
+        visit::visit_item_const(self, item);
+    }
+}
+
+/// Converts a (1-based line, 0-based char column) location to a byte offset in `content`.
+pub(crate) fn byte_offset(content: &str, location: LineColumn) -> usize {
+    let line_start = content
+        .split_inclusive('\n')
+        .take(location.line - 1)
+        .map(str::len)
+        .sum::<usize>();
+    let column = content[line_start..]
+        .char_indices()
+        .nth(location.column)
+        .map(|(offset, _)| offset)
+        .unwrap_or(content.len() - line_start);
+
+    line_start + column
+}
+
+/// Increments the given fields (e.g. `spec_version`) of the runtime's `VERSION` constant.
+///
+/// Returns the updated source, formatting preserved, along with the bumps.
+pub fn bump_runtime_version(content: &str, fields: &[&str]) -> SubstrateResult<(String, Vec<VersionBump>)> {
+    let file = syn::parse_file(content).with_context(|| "couldn't parse the runtime's lib.rs")?;
+    let mut visitor = RuntimeVersionVisitor::default();
+    visitor.visit_file(&file);
+
+    let mut bumped = Vec::new();
+    let mut edits = Vec::new();
+    for name in fields {
+        let field = visitor
+            .fields
+            .iter()
+            .find(|field| field.name == *name)
+            .with_context(|| format!("no `{}` found in the runtime's `RuntimeVersion`", name))?;
+        let new_value = field.value + 1;
+        edits.push((
+            byte_offset(content, field.start),
+            byte_offset(content, field.end),
+            new_value,
+        ));
+        bumped.push(VersionBump {
+            field: field.name.clone(),
+            old: field.value,
+            new: new_value,
+        });
+    }
+
+    // Edit from the end of the file so that earlier offsets stay valid
+    edits.sort_by_key(|(start, _, _)| std::cmp::Reverse(*start));
+    let mut content = content.to_string();
+    for (start, end, value) in edits {
+        content.replace_range(start..end, &value.to_string());
+    }
+
+    Ok((content, bumped))
+}
+
+/// Parses the crate, instance and parts of a `construct_runtime!` entry, e.g.
+/// `Council: pallet_collective::<Instance1>::{Pallet, Call, Event<T>} = 5`.
+fn pallet_declaration(entry: &[TokenTree]) -> (String, Option<String>, Vec<String>) {
+    // Skip the attributes, the name and its colon
+    let declaration = entry
+        .iter()
+        .skip_while(|token| !matches!(token, TokenTree::Ident(_)))
+        .skip(2)
+        .take_while(|token| !matches!(token, TokenTree::Punct(punct) if punct.as_char() == '='))
+        .collect::<Vec<_>>();
+
+    let crate_name = declaration
+        .iter()
+        .find_map(|token| match token {
+            TokenTree::Ident(ident) => Some(ident.to_string()),
+            _ => None,
+        })
+        .unwrap_or_default();
+    let instance = declaration.windows(2).find_map(|tokens| match tokens {
+        [TokenTree::Punct(punct), TokenTree::Ident(ident)] if punct.as_char() == '<' => Some(ident.to_string()),
+        _ => None,
+    });
+    let parts = declaration
+        .windows(2)
+        .find_map(|tokens| match tokens {
+            [TokenTree::Punct(punct), TokenTree::Group(group)]
+                if punct.as_char() == ':' && group.delimiter() == Delimiter::Brace =>
+            {
+                Some(group)
+            }
+            _ => None,
+        })
+        .map(|group| {
+            let mut parts = Vec::new();
+            let mut part_started = false;
+            for token in group.stream() {
+                match token {
+                    TokenTree::Ident(ident) if !part_started => {
+                        parts.push(ident.to_string());
+                        part_started = true;
+                    }
+                    TokenTree::Punct(punct) if punct.as_char() == ',' => part_started = false,
+                    _ => {}
+                }
+            }
+            parts
+        })
+        .unwrap_or_default();
+
+    (crate_name, instance, parts)
+}
+
+/// The source text of `span`, with its whitespace collapsed into single spaces.
+fn source_text(content: &str, span: proc_macro2::Span) -> String {
+    content[byte_offset(content, span.start())..byte_offset(content, span.end())]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Lists the `impl <pallet>::Config for Runtime` blocks with their associated types.
+pub fn runtime_configs(content: &str) -> SubstrateResult<Vec<RuntimeConfig>> {
+    let file = syn::parse_file(content).with_context(|| "couldn't parse the runtime's lib.rs")?;
+
+    Ok(file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Impl(item) => Some(item),
+            _ => None,
+        })
+        .filter_map(|item| {
+            let (_, path, _) = item.trait_.as_ref()?;
+            let is_runtime = matches!(
+                item.self_ty.as_ref(),
+                syn::Type::Path(ty) if ty.path.is_ident("Runtime")
+            );
+            let segments = path.segments.iter().collect::<Vec<_>>();
+            let (config, crate_path) = segments.split_last()?;
+            if !is_runtime || config.ident != "Config" || crate_path.is_empty() {
+                return None;
+            }
+
+            let crate_name = crate_path
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("::");
+            let instance = match &config.arguments {
+                syn::PathArguments::AngleBracketed(arguments) => Some(source_text(content, arguments.args.span())),
+                _ => None,
+            };
+            let types = item
+                .items
+                .iter()
+                .filter_map(|item| match item {
+                    syn::ImplItem::Type(ty) => Some((ty.ident.to_string(), source_text(content, ty.ty.span()))),
+                    _ => None,
+                })
+                .collect();
+
+            Some(RuntimeConfig {
+                crate_name,
+                instance,
+                types,
+            })
+        })
+        .collect())
+}
+
+/// The entries of `construct_runtime!`, each as its tokens without the separating comma.
+fn construct_runtime_entries(content: &str) -> SubstrateResult<Vec<Vec<TokenTree>>> {
+    let file = syn::parse_file(content).with_context(|| "couldn't parse the runtime's lib.rs")?;
+    let construct_runtime = file
+        .items
+        .iter()
+        .find_map(|item| match item {
+            syn::Item::Macro(item)
+                if item.mac.path.segments.last().is_some_and(|s| s.ident == "construct_runtime") =>
+            {
+                Some(&item.mac)
+            }
+            _ => None,
+        })
+        .with_context(|| "no `construct_runtime!` found in the runtime's lib.rs")?;
+
+    // The pallets are in the last braced group, after the optional `where` clause
+    let pallets_group = construct_runtime
+        .tokens
+        .clone()
+        .into_iter()
+        .filter_map(|token| match token {
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => Some(group),
+            _ => None,
+        })
+        .last()
+        .with_context(|| "unexpected `construct_runtime!` format")?;
+
+    let mut entries = Vec::new();
+    let mut entry = Vec::new();
+    for token in pallets_group.stream() {
+        if matches!(&token, TokenTree::Punct(punct) if punct.as_char() == ',') {
+            entries.push(std::mem::take(&mut entry));
+        } else {
+            entry.push(token);
+        }
+    }
+    entries.push(entry);
+    entries.retain(|entry| !entry.is_empty());
+
+    Ok(entries)
+}
+
+/// The index literal of a `construct_runtime!` entry, after its `=`.
+fn explicit_index(entry: &[TokenTree]) -> Option<&proc_macro2::Literal> {
+    entry.windows(2).find_map(|tokens| match tokens {
+        [TokenTree::Punct(punct), TokenTree::Literal(literal)] if punct.as_char() == '=' => Some(literal),
+        _ => None,
+    })
+}
+
+/// Lists the pallets of `construct_runtime!` with their index, explicit (`= 7`) or implicit.
+pub fn construct_runtime_pallets(content: &str) -> SubstrateResult<Vec<RuntimePallet>> {
+    let mut pallets = Vec::new();
+    let mut next_index = 0u8;
+    for entry in construct_runtime_entries(content)? {
+        // `Name: path::to::pallet = 7` (the generic arguments and parts are grouped tokens)
+        let name = entry.iter().find_map(|token| match token {
+            TokenTree::Ident(ident) => Some(ident.to_string()),
+            _ => None,
+        });
+        let explicit_index = explicit_index(&entry).and_then(|literal| literal.to_string().parse::<u8>().ok());
+        if let Some(name) = name {
+            let index = explicit_index.unwrap_or(next_index);
+            let (crate_name, instance, parts) = pallet_declaration(&entry);
+            pallets.push(RuntimePallet {
+                name,
+                index,
+                explicit_index: explicit_index.is_some(),
+                crate_name,
+                instance,
+                parts,
+            });
+            next_index = index.saturating_add(1);
+        }
+    }
+
+    Ok(pallets)
+}
+
+/// Sets the index of the first pallet of the crate `crate_name` in `construct_runtime!`, leaving the
+/// rest of its entry (name, instance, parts) and the other entries as they are.
+///
+/// Returns the updated source, or `None` if the crate isn't declared.
+pub fn set_pallet_index(content: &str, crate_name: &str, index: u8) -> SubstrateResult<Option<String>> {
+    let Some(entry) = construct_runtime_entries(content)?
+        .into_iter()
+        .find(|entry| pallet_declaration(entry).0 == crate_name)
+    else {
+        return Ok(None);
+    };
+
+    let mut content = content.to_string();
+    match explicit_index(&entry) {
+        Some(literal) => {
+            let span = literal.span();
+            let range = byte_offset(&content, span.start())..byte_offset(&content, span.end());
+            content.replace_range(range, &index.to_string());
+        }
+        // Entries aren't empty, the index goes after their last token
+        None => {
+            if let Some(last) = entry.last() {
+                let end = byte_offset(&content, last.span().end());
+                content.insert_str(end, &format!(" = {}", index));
+            }
+        }
+    }
+
+    Ok(Some(content))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bump_runtime_version() {
+        let content = r#"
+            #[sp_version::runtime_version]
+            pub const VERSION: RuntimeVersion = RuntimeVersion {
+                spec_name: create_runtime_str!("node-template"),
+                impl_name: create_runtime_str!("node-template"),
+                authoring_version: 1,
+                // The version of the runtime specification. ✨
+                spec_version: 100,
+                impl_version: 1,
+                apis: RUNTIME_API_VERSIONS,
+                transaction_version: 1,
+                state_version: 1,
+            };
+        "#;
+
+        let (bumped, versions) =
+            bump_runtime_version(content, &["spec_version", "transaction_version"]).unwrap();
+        assert_eq!(
+            bumped,
+            content
+                .replace("spec_version: 100", "spec_version: 101")
+                .replace("transaction_version: 1", "transaction_version: 2")
+        );
+        assert_eq!(
+            versions,
+            [
+                VersionBump { field: "spec_version".into(), old: 100, new: 101 },
+                VersionBump { field: "transaction_version".into(), old: 1, new: 2 },
+            ]
+        );
+        assert!(bump_runtime_version(content, &["unknown_version"]).is_err());
+    }
+
+    #[test]
+    fn test_construct_runtime_pallets() {
+        let content = r#"
+            construct_runtime!(
+                pub enum Runtime where
+                    Block = Block,
+                    NodeBlock = opaque::Block,
+                    UncheckedExtrinsic = UncheckedExtrinsic,
+                {
+                    System: frame_system,
+                    Timestamp: pallet_timestamp,
+                    Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>} = 10,
+                    Sudo: pallet_sudo,
+                    Council: pallet_collective::<Instance1>::{Pallet, Call, Origin<T>, Event<T>},
+                }
+            );
+        "#;
+
+        let pallet = |name: &str, index, crate_name: &str, instance: Option<&str>, parts: &[&str]| RuntimePallet {
+            name: name.into(),
+            index,
+            explicit_index: index == 10,
+            crate_name: crate_name.into(),
+            instance: instance.map(Into::into),
+            parts: parts.iter().map(|part| part.to_string()).collect(),
+        };
+        let pallets = construct_runtime_pallets(content).unwrap();
+        assert_eq!(
+            pallets,
+            [
+                pallet("System", 0, "frame_system", None, &[]),
+                pallet("Timestamp", 1, "pallet_timestamp", None, &[]),
+                pallet("Balances", 10, "pallet_balances", None, &["Pallet", "Call", "Storage", "Config", "Event"]),
+                pallet("Sudo", 11, "pallet_sudo", None, &[]),
+                pallet("Council", 12, "pallet_collective", Some("Instance1"), &["Pallet", "Call", "Origin", "Event"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_pallet_index() {
+        let content = r#"
+            construct_runtime!(
+                pub enum Runtime {
+                    System: frame_system,
+                    AssetsExt: pallet_assets_ext,
+                    Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>} = 10,
+                    Assets: pallet_assets,
+                }
+            );
+        "#;
+
+        let updated = set_pallet_index(content, "pallet_assets", 12).unwrap().unwrap();
+        assert_eq!(
+            updated,
+            content.replace("Assets: pallet_assets,\n", "Assets: pallet_assets = 12,\n")
+        );
+        let updated = set_pallet_index(content, "pallet_balances", 5).unwrap().unwrap();
+        assert_eq!(updated, content.replace("Event<T>} = 10,", "Event<T>} = 5,"));
+        assert_eq!(set_pallet_index(content, "pallet_sudo", 1).unwrap(), None);
+    }
+
+    #[test]
+    fn test_runtime_configs() {
+        let content = r#"
+            impl pallet_balances::Config for Runtime {
+                type MaxLocks = ConstU32<50>;
+                type Balance = Balance;
+                type AccountStore =
+                    System;
+            }
+
+            impl pallet_collective::Config<CouncilCollective> for Runtime {
+                type RuntimeEvent = RuntimeEvent;
+            }
+
+            impl Get<u32> for Runtime {
+                fn get() -> u32 { 0 }
+            }
+        "#;
+
+        let configs = runtime_configs(content).unwrap();
+        assert_eq!(
+            configs,
+            [
+                RuntimeConfig {
+                    crate_name: "pallet_balances".into(),
+                    instance: None,
+                    types: vec![
+                        ("MaxLocks".into(), "ConstU32<50>".into()),
+                        ("Balance".into(), "Balance".into()),
+                        ("AccountStore".into(), "System".into()),
+                    ],
+                },
+                RuntimeConfig {
+                    crate_name: "pallet_collective".into(),
+                    instance: Some("CouncilCollective".into()),
+                    types: vec![("RuntimeEvent".into(), "RuntimeEvent".into())],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,288 @@
+use std::{borrow::Cow, fs, io::Read, path::PathBuf};
+
+use anyhow::Context as _;
+use blake2::{digest::consts::U32, Blake2b, Digest};
+
+use crate::util::SubstrateResult;
+
+/// Prefix of wasm blobs compressed by `sp-maybe-compressed-blob`.
+const ZSTD_PREFIX: [u8; 8] = [82, 188, 83, 118, 70, 219, 142, 5];
+
+/// Name of the custom wasm section holding the SCALE encoded `RuntimeVersion`.
+const RUNTIME_VERSION_SECTION: &str = "runtime_version";
+
+pub fn blake2_256(data: &[u8]) -> [u8; 32] {
+    Blake2b::<U32>::digest(data).into()
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    let hex = bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    format!("0x{}", hex)
+}
+
+/// SCALE compact encoding of `value`.
+pub fn encode_compact(value: u64) -> Vec<u8> {
+    match value {
+        0..=0x3f => vec![(value as u8) << 2],
+        0x40..=0x3fff => ((value as u16) << 2 | 0b01).to_le_bytes().to_vec(),
+        0x4000..=0x3fff_ffff => ((value as u32) << 2 | 0b10).to_le_bytes().to_vec(),
+        _ => {
+            let bytes = value.to_le_bytes();
+            let len = bytes.iter().rposition(|byte| *byte != 0).unwrap_or(0) + 1;
+            let mut encoded = vec![((len - 4) as u8) << 2 | 0b11];
+            encoded.extend(&bytes[..len]);
+            encoded
+        }
+    }
+}
+
+/// Encodes a `System::set_code(code)` call, `System` being at `system_index` in the runtime.
+pub fn set_code_call(system_index: u8, code: &[u8]) -> Vec<u8> {
+    let mut call = vec![system_index, 2];
+    call.extend(encode_compact(code.len() as u64));
+    call.extend(code);
+    call
+}
+
+/// Wraps `call` in a `Sudo::sudo(call)` call, `Sudo` being at `sudo_index` in the runtime.
+pub fn sudo_call(sudo_index: u8, call: &[u8]) -> Vec<u8> {
+    let mut sudo = vec![sudo_index, 0];
+    sudo.extend(call);
+    sudo
+}
+
+/// Encodes a `ParachainSystem::authorize_upgrade(code_hash, true)` call, at the
+/// pallet index used by the Cumulus templates.
+pub fn parachain_authorize_upgrade_call(code_hash: &[u8; 32]) -> Vec<u8> {
+    let mut call = vec![1, 2];
+    call.extend(code_hash);
+    call.push(1);
+    call
+}
+
+/// The version of a runtime, as declared by its `VERSION` constant.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RuntimeVersion {
+    pub spec_name: String,
+    pub impl_name: String,
+    pub authoring_version: u32,
+    pub spec_version: u32,
+    pub impl_version: u32,
+    /// Only encoded by runtimes that are recent enough
+    pub transaction_version: Option<u32>,
+}
+
+/// A runtime wasm blob, as produced by `substrate-wasm-builder`.
+#[derive(Debug)]
+pub struct RuntimeWasm {
+    path: PathBuf,
+    code: Vec<u8>,
+}
+
+impl RuntimeWasm {
+    pub fn read(path: PathBuf) -> SubstrateResult<Self> {
+        let code =
+            fs::read(&path).with_context(|| format!("couldn't read `{}`", path.display()))?;
+
+        Ok(Self { path, code })
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// The code as stored on chain, compressed or not.
+    pub fn code(&self) -> &[u8] {
+        &self.code
+    }
+
+    pub fn blake2_256(&self) -> [u8; 32] {
+        blake2_256(&self.code)
+    }
+
+    /// The plain wasm module, decompressing the code if needed.
+    pub fn decompressed(&self) -> SubstrateResult<Cow<'_, [u8]>> {
+        match self.code.strip_prefix(&ZSTD_PREFIX) {
+            Some(compressed) => {
+                let mut decoder = ruzstd::StreamingDecoder::new(compressed)
+                    .map_err(|e| anyhow::anyhow!("invalid compressed wasm: {}", e))?;
+                let mut wasm = Vec::new();
+                decoder
+                    .read_to_end(&mut wasm)
+                    .with_context(|| "couldn't decompress the wasm")?;
+                Ok(Cow::Owned(wasm))
+            }
+            None => Ok(Cow::Borrowed(&self.code)),
+        }
+    }
+
+    pub fn runtime_version(&self) -> SubstrateResult<RuntimeVersion> {
+        let wasm = self.decompressed()?;
+        let section = custom_section(&wasm, RUNTIME_VERSION_SECTION)?
+            .with_context(|| format!("no `{}` section in the wasm", RUNTIME_VERSION_SECTION))?;
+
+        decode_runtime_version(section)
+    }
+}
+
+struct Input<'a>(&'a [u8]);
+
+impl<'a> Input<'a> {
+    fn take(&mut self, len: usize) -> SubstrateResult<&'a [u8]> {
+        if self.0.len() < len {
+            anyhow::bail!("unexpected end of input");
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> SubstrateResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> SubstrateResult<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn leb128(&mut self) -> SubstrateResult<usize> {
+        let mut value = 0usize;
+        for shift in (0..35).step_by(7) {
+            let byte = self.u8()?;
+            value |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        anyhow::bail!("invalid LEB128 integer")
+    }
+
+    /// SCALE compact encoded integer.
+    fn compact(&mut self) -> SubstrateResult<usize> {
+        let first = self.u8()?;
+        Ok(match first & 0b11 {
+            0b00 => (first >> 2) as usize,
+            0b01 => (u16::from_le_bytes([first, self.u8()?]) >> 2) as usize,
+            0b10 => {
+                let rest = self.take(3)?;
+                (u32::from_le_bytes([first, rest[0], rest[1], rest[2]]) >> 2) as usize
+            }
+            _ => {
+                let len = (first >> 2) as usize + 4;
+                let mut bytes = [0u8; 8];
+                if len > bytes.len() {
+                    anyhow::bail!("compact integer too large");
+                }
+                bytes[..len].copy_from_slice(self.take(len)?);
+                u64::from_le_bytes(bytes) as usize
+            }
+        })
+    }
+
+    fn string(&mut self) -> SubstrateResult<String> {
+        let len = self.compact()?;
+        Ok(String::from_utf8(self.take(len)?.to_vec())?)
+    }
+}
+
+/// Finds the content of the custom section called `name` in a wasm module.
+fn custom_section<'a>(wasm: &'a [u8], name: &str) -> SubstrateResult<Option<&'a [u8]>> {
+    let mut input = Input(wasm);
+    if input.take(4)? != b"\0asm" {
+        anyhow::bail!("not a wasm module");
+    }
+    input.take(4)?;
+
+    while !input.0.is_empty() {
+        let id = input.u8()?;
+        let size = input.leb128()?;
+        let mut payload = Input(input.take(size)?);
+        if id == 0 {
+            let name_len = payload.leb128()?;
+            if payload.take(name_len)? == name.as_bytes() {
+                return Ok(Some(payload.0));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+fn decode_runtime_version(data: &[u8]) -> SubstrateResult<RuntimeVersion> {
+    let mut input = Input(data);
+    let spec_name = input.string()?;
+    let impl_name = input.string()?;
+    let authoring_version = input.u32()?;
+    let spec_version = input.u32()?;
+    let impl_version = input.u32()?;
+    // Runtime APIs: (8 bytes id, u32 version) pairs
+    let apis = input.compact()?;
+    input.take(apis * 12)?;
+    let transaction_version = input.u32().ok();
+
+    Ok(RuntimeVersion {
+        spec_name,
+        impl_name,
+        authoring_version,
+        spec_version,
+        impl_version,
+        transaction_version,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_runtime_version() {
+        let mut version = vec![];
+        for name in ["node-template", "node-template"] {
+            version.push((name.len() as u8) << 2);
+            version.extend(name.as_bytes());
+        }
+        for number in [1u32, 105, 1] {
+            version.extend(number.to_le_bytes());
+        }
+        version.push(1 << 2);
+        version.extend([0xdf, 0x6a, 0xcb, 0x68, 0x99, 0x07, 0x60, 0x9b, 4, 0, 0, 0]);
+        version.extend(2u32.to_le_bytes());
+
+        let mut section = vec![RUNTIME_VERSION_SECTION.len() as u8];
+        section.extend(RUNTIME_VERSION_SECTION.as_bytes());
+        section.extend(version);
+
+        let mut code = b"\0asm\x01\0\0\0".to_vec();
+        // An empty type section comes before the custom section
+        code.extend([1, 1, 0, 0]);
+        code.push(section.len() as u8);
+        code.extend(section);
+
+        let wasm = RuntimeWasm {
+            path: PathBuf::new(),
+            code,
+        };
+        assert_eq!(
+            wasm.runtime_version().unwrap(),
+            RuntimeVersion {
+                spec_name: "node-template".into(),
+                impl_name: "node-template".into(),
+                authoring_version: 1,
+                spec_version: 105,
+                impl_version: 1,
+                transaction_version: Some(2),
+            }
+        );
+        assert_eq!(encode_compact(1), [0x04]);
+        assert_eq!(encode_compact(64), [0x01, 0x01]);
+        assert_eq!(encode_compact(1_000_000), [0x02, 0x09, 0x3d, 0x00]);
+        assert_eq!(encode_compact(1 << 32), [0x07, 0, 0, 0, 0, 1]);
+        assert_eq!(
+            to_hex(&blake2_256(b"")),
+            "0x0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8"
+        );
+    }
+}
@@ -454,7 +454,7 @@ impl ShellOut {
         match *self {
             ShellOut::Stream { ref mut stdout, .. } => {
                 stdout.reset()?;
-                stdout.set_color(&color)?;
+                stdout.set_color(color)?;
                 write!(stdout, "{}", fragment)?;
                 stdout.reset()?;
             }
@@ -474,7 +474,7 @@ impl ShellOut {
         match *self {
             ShellOut::Stream { ref mut stderr, .. } => {
                 stderr.reset()?;
-                stderr.set_color(&color)?;
+                stderr.set_color(color)?;
                 write!(stderr, "{}", fragment)?;
                 stderr.reset()?;
             }
@@ -529,7 +529,7 @@ mod imp {
             let mut winsize: libc::winsize = mem::zeroed();
             // The .into() here is needed for FreeBSD which defines TIOCGWINSZ
             // as c_uint but ioctl wants c_ulong.
-            if libc::ioctl(libc::STDERR_FILENO, libc::TIOCGWINSZ.into(), &mut winsize) < 0 {
+            if libc::ioctl(libc::STDERR_FILENO, libc::TIOCGWINSZ, &mut winsize) < 0 {
                 return TtyWidth::NoTty;
             }
             if winsize.ws_col > 0 {
@@ -0,0 +1,329 @@
+use std::{collections::HashMap, fmt::Write as _, path::Path, time::Duration};
+
+use termcolor::Color::Red;
+
+use crate::{core::Shell, util::SubstrateResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestOutcome {
+    Passed,
+    Failed,
+    Ignored,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TestCase {
+    pub name: String,
+    pub outcome: TestOutcome,
+    /// What a failed test printed, including its panic message
+    pub output: String,
+}
+
+/// The tests of one crate, from its unit, integration and doc tests.
+#[derive(Debug, Default)]
+pub struct TestSuite {
+    pub name: String,
+    pub cases: Vec<TestCase>,
+    pub duration: Duration,
+}
+
+impl TestSuite {
+    pub fn count(&self, outcome: TestOutcome) -> usize {
+        self.cases.iter().filter(|case| case.outcome == outcome).count()
+    }
+}
+
+/// Parses a libtest result line, e.g. `test tests::it_works ... ok`.
+fn parse_test_line(line: &str) -> Option<(&str, TestOutcome)> {
+    let (name, outcome) = line.strip_prefix("test ")?.rsplit_once(" ... ")?;
+    let outcome = match outcome {
+        "ok" => TestOutcome::Passed,
+        "FAILED" => TestOutcome::Failed,
+        outcome if outcome.starts_with("ignored") => TestOutcome::Ignored,
+        _ => return None,
+    };
+    Some((name, outcome))
+}
+
+/// The test target a cargo `Running` line is about, e.g. `pallet_template` for
+/// `Running unittests src/lib.rs (target/debug/deps/pallet_template-1a2b3c)`.
+fn running_target(line: &str) -> Option<&str> {
+    let binary = match line.rsplit_once('(') {
+        Some((_, binary)) => binary.trim_end_matches(')'),
+        None => line,
+    };
+    let file_name = Path::new(binary).file_stem()?.to_str()?;
+    Some(file_name.rsplit_once('-').map_or(file_name, |(name, _)| name))
+}
+
+/// Parses the duration of a `test result: ... finished in 0.02s` line.
+fn parse_finished_in(line: &str) -> Option<Duration> {
+    let seconds = line.rsplit_once("finished in ")?.1.trim().strip_suffix('s')?;
+    seconds.parse().ok().map(Duration::from_secs_f64)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Results of one or more `cargo test` runs, built from their (merged stdout and stderr) output.
+#[derive(Debug, Default)]
+pub struct TestReport {
+    pub suites: Vec<TestSuite>,
+    /// Crate of each test target, e.g. `pallet_template` for its integration test `tests/foo.rs`
+    target_crates: HashMap<String, String>,
+    current: Option<usize>,
+    /// Failed test whose output is being read
+    capturing: Option<String>,
+}
+
+impl TestReport {
+    /// Groups the results of the given test targets under their crate, instead of a suite per test
+    /// binary. Targets and crates are named with underscores, as in cargo's output.
+    pub fn add_target_crates(&mut self, target_crates: impl IntoIterator<Item = (String, String)>) {
+        self.target_crates.extend(target_crates);
+    }
+
+    /// Attributes the next results to the suite of the crate of `target`.
+    fn start_target(&mut self, target: &str) {
+        let name = self.target_crates.get(target).cloned();
+        self.start_suite(name.as_deref().unwrap_or(target));
+    }
+
+    /// Attributes the next results to the `name` suite, until cargo reports running another crate.
+    pub fn start_suite(&mut self, name: &str) {
+        let index = match self.suites.iter().position(|suite| suite.name == name) {
+            Some(index) => index,
+            None => {
+                self.suites.push(TestSuite {
+                    name: name.to_string(),
+                    ..Default::default()
+                });
+                self.suites.len() - 1
+            }
+        };
+        self.current = Some(index);
+    }
+
+    fn current_suite(&mut self) -> &mut TestSuite {
+        if self.current.is_none() {
+            self.start_suite("tests");
+        }
+        &mut self.suites[self.current.unwrap()]
+    }
+
+    pub fn parse_line(&mut self, line: &str) {
+        let trimmed = line.trim();
+        if let Some(name) = self.capturing.clone() {
+            if trimmed == "failures:" || trimmed.starts_with("---- ") || trimmed.starts_with("test result:") {
+                self.capturing = None;
+            } else {
+                if let Some(case) = self.current_suite().cases.iter_mut().find(|case| case.name == name) {
+                    case.output.push_str(line);
+                    case.output.push('\n');
+                }
+                return;
+            }
+        }
+
+        if let Some(running) = trimmed.strip_prefix("Running ") {
+            if let Some(target) = running_target(running) {
+                self.start_target(target);
+            }
+        } else if let Some(target) = trimmed.strip_prefix("Doc-tests ") {
+            self.start_target(target);
+        } else if trimmed.starts_with("test result:") {
+            let duration = parse_finished_in(trimmed).unwrap_or_default();
+            self.current_suite().duration += duration;
+        } else if let Some(name) = trimmed
+            .strip_prefix("---- ")
+            .and_then(|rest| rest.strip_suffix(" stdout ----"))
+        {
+            self.capturing = Some(name.to_string());
+        } else if let Some((name, outcome)) = parse_test_line(trimmed) {
+            self.current_suite().cases.push(TestCase {
+                name: name.to_string(),
+                outcome,
+                output: String::new(),
+            });
+        }
+    }
+
+    /// Suites that ran at least one test.
+    fn ran_suites(&self) -> impl Iterator<Item = &TestSuite> {
+        self.suites.iter().filter(|suite| !suite.cases.is_empty())
+    }
+
+    pub fn count(&self, outcome: TestOutcome) -> usize {
+        self.suites.iter().map(|suite| suite.count(outcome)).sum()
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.suites.iter().map(|suite| suite.duration).sum()
+    }
+
+    /// Prints the passed, failed and ignored tests of each crate, then the failed tests.
+    pub fn print_summary(&self, shell: &mut Shell) -> SubstrateResult<()> {
+        if self.ran_suites().next().is_none() {
+            return shell.note("no tests were run");
+        }
+
+        let width = self
+            .ran_suites()
+            .map(|suite| suite.name.len())
+            .chain(["crate".len()])
+            .max()
+            .unwrap_or_default();
+        let row = |cells: [String; 5]| {
+            let [name, passed, failed, ignored, time] = cells;
+            format!(
+                "{:<width$}  {:>7}  {:>7}  {:>7}  {:>9}",
+                name,
+                passed,
+                failed,
+                ignored,
+                time,
+                width = width
+            )
+        };
+        let counts = |name: &str, count: &dyn Fn(TestOutcome) -> usize, duration: Duration| {
+            row([
+                name.to_string(),
+                count(TestOutcome::Passed).to_string(),
+                count(TestOutcome::Failed).to_string(),
+                count(TestOutcome::Ignored).to_string(),
+                format!("{:.2}s", duration.as_secs_f64()),
+            ])
+        };
+
+        let total = self.ran_suites().map(|suite| suite.cases.len()).sum::<usize>();
+        let crates = self.ran_suites().count();
+        shell.status("Summary", format!("{} tests in {} crates", total, crates))?;
+        let out = shell.out();
+        let header = ["crate", "passed", "failed", "ignored", "time"].map(str::to_string);
+        writeln!(out, "{}", row(header))?;
+        for suite in self.ran_suites() {
+            writeln!(out, "{}", counts(&suite.name, &|outcome| suite.count(outcome), suite.duration))?;
+        }
+        writeln!(out, "{}", counts("total", &|outcome| self.count(outcome), self.duration()))?;
+
+        for suite in self.ran_suites() {
+            for case in suite.cases.iter().filter(|case| case.outcome == TestOutcome::Failed) {
+                shell.status_with_color("FAILED", format!("{} {}", suite.name, case.name), Red)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The report in the JUnit XML format read by CI dashboards.
+    pub fn junit(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            xml,
+            "<testsuites tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">",
+            self.ran_suites().map(|suite| suite.cases.len()).sum::<usize>(),
+            self.count(TestOutcome::Failed),
+            self.count(TestOutcome::Ignored),
+            self.duration().as_secs_f64()
+        );
+        for suite in self.ran_suites() {
+            let name = escape_xml(&suite.name);
+            let _ = writeln!(
+                xml,
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">",
+                name,
+                suite.cases.len(),
+                suite.count(TestOutcome::Failed),
+                suite.count(TestOutcome::Ignored),
+                suite.duration.as_secs_f64()
+            );
+            for case in &suite.cases {
+                let _ = write!(
+                    xml,
+                    "    <testcase classname=\"{}\" name=\"{}\"",
+                    name,
+                    escape_xml(&case.name)
+                );
+                let _ = match case.outcome {
+                    TestOutcome::Passed => writeln!(xml, "/>"),
+                    TestOutcome::Ignored => writeln!(xml, "><skipped/></testcase>"),
+                    TestOutcome::Failed => writeln!(
+                        xml,
+                        "><failure message=\"test failed\">{}</failure></testcase>",
+                        escape_xml(case.output.trim_end())
+                    ),
+                };
+            }
+            xml.push_str("  </testsuite>\n");
+        }
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const OUTPUT: &str = "   Compiling pallet-template v4.0.0-dev
+    Finished test [unoptimized + debuginfo] target(s) in 0.12s
+     Running unittests src/lib.rs (target/debug/deps/pallet_template-1a2b3c4d)
+
+running 3 tests
+test tests::it_works_for_default_value ... ok
+test tests::correct_error_for_none_value ... FAILED
+test benchmarking::bench_do_something ... ignored
+
+failures:
+
+---- tests::correct_error_for_none_value stdout ----
+thread 'tests::correct_error_for_none_value' panicked at src/tests.rs:20:3:
+assertion failed: false
+
+failures:
+    tests::correct_error_for_none_value
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.25s
+
+     Running tests/integration.rs (target/debug/deps/integration-5e6f7a8b)
+
+running 1 test
+test it_stores_a_value ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.25s
+
+   Doc-tests pallet_template
+
+running 1 test
+test src/lib.rs - Pallet (line 12) ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.50s
+";
+
+    #[test]
+    fn test_parse_output() {
+        let mut report = TestReport::default();
+        report.add_target_crates([("integration".to_string(), "pallet_template".to_string())]);
+        OUTPUT.lines().for_each(|line| report.parse_line(line));
+
+        assert_eq!(report.suites.len(), 1);
+        let suite = &report.suites[0];
+        assert_eq!(suite.name, "pallet_template");
+        assert_eq!(suite.count(TestOutcome::Passed), 3);
+        assert_eq!(suite.count(TestOutcome::Failed), 1);
+        assert_eq!(suite.count(TestOutcome::Ignored), 1);
+        assert_eq!(suite.duration, Duration::from_millis(1000));
+        assert_eq!(
+            suite.cases[1].output,
+            "thread 'tests::correct_error_for_none_value' panicked at src/tests.rs:20:3:\nassertion failed: false\n\n"
+        );
+
+        let junit = report.junit();
+        assert!(junit.contains("<testsuite name=\"pallet_template\" tests=\"5\" failures=\"1\" skipped=\"1\" time=\"1.000\">"));
+        assert!(junit.contains("<failure message=\"test failed\">thread &apos;tests::correct_error_for_none_value&apos; panicked"));
+    }
+}
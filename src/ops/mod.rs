@@ -3,14 +3,28 @@ pub use self::substrate_build::build;
 pub use self::substrate_deploy::deploy;
 pub use self::substrate_run::run;
 pub use self::substrate_frontend::frontend;
+pub use self::substrate_network::network;
 pub use self::substrate_new::new_contract;
 pub use self::substrate_new::new_chain;
+pub use self::substrate_new::new_pallet;
 pub use self::substrate_test::test;
 
 pub mod substrate_add;
+pub mod substrate_bench;
 pub mod substrate_build;
+pub mod substrate_call;
+pub mod substrate_contracts_node;
 pub mod substrate_deploy;
 pub mod substrate_run;
+pub mod substrate_spec;
 pub mod substrate_frontend;
+pub mod substrate_inspect;
+pub mod substrate_keys;
+pub mod substrate_lint;
+pub mod substrate_network;
 pub mod substrate_new;
+pub mod substrate_pallet;
 pub mod substrate_test;
+pub mod substrate_testnet;
+pub mod substrate_try_runtime;
+pub mod substrate_upgrade;
@@ -8,7 +8,7 @@ use anyhow::Ok;
 use regex::Regex;
 
 use crate::{
-    core::manifest::Manifest,
+    core::{manifest::Manifest, runtime_source::{construct_runtime_pallets, set_pallet_index}},
     util::{to_pascal_case, to_snake_case, Config, SubstrateResult},
 };
 
@@ -27,6 +27,11 @@ pub struct AddOptions {
 
     /// Source of the crate
     pub source: CrateSource,
+    /// Items of the pallet's `Config` implementation for the runtime, e.g. `type RuntimeEvent = RuntimeEvent;`,
+    /// a placeholder is written when empty
+    pub config_items: Vec<String>,
+    /// Index of the pallet in `construct_runtime!`, the next free one when `None`
+    pub index: Option<u8>,
 }
 
 pub enum CrateSource {
@@ -57,12 +62,107 @@ pub fn add_pallet_std_to_manifest(
     Ok(())
 }
 
+/// Enables the pallet's `feature` with the runtime's feature of the same name, e.g. `runtime-benchmarks`.
+///
+/// Returns whether the runtime declares that feature.
+pub fn add_pallet_feature_to_manifest(
+    cwd: &Path,
+    runtime_path: &Path,
+    crate_spec: &str,
+    feature: &str,
+) -> SubstrateResult<bool> {
+    let mut runtime_manifest = Manifest::new(cwd.join(runtime_path).join("Cargo.toml"));
+    let mut runtime_document = runtime_manifest.read_document()?;
+    let Some(feature_array) = runtime_document
+        .get_mut("features")
+        .and_then(|features| features.get_mut(feature))
+        .and_then(|feature| feature.as_array_mut())
+    else {
+        return Ok(false);
+    };
+
+    let pallet_feature = format!("{}/{}", crate_spec, feature);
+    if !feature_array.iter().any(|f| f.as_str() == Some(pallet_feature.as_str())) {
+        feature_array.push::<String>(pallet_feature);
+        runtime_manifest.write_document(runtime_document)?;
+    }
+
+    Ok(true)
+}
+
+/// Adds the pallet to the runtime's `define_benchmarks!`, so that the node can benchmark it.
+///
+/// Returns whether the runtime defines its benchmarks with that macro.
+pub fn add_pallet_to_benchmarks(cwd: &Path, runtime_path: &Path, crate_spec: &str) -> SubstrateResult<bool> {
+    let runtime_lib_path = cwd.join(runtime_path).join("src/lib.rs");
+    let mod_name = to_snake_case(crate_spec);
+    let define_benchmarks = Regex::new(r"define_benchmarks!\((?P<benches>[^;]*?)[ \t]*\);")?;
+
+    let original = fs::read_to_string(&runtime_lib_path)?;
+    let Some(caps) = define_benchmarks.captures(&original) else {
+        return Ok(false);
+    };
+    let benches = caps.name("benches").unwrap();
+    if benches.as_str().contains(&format!("[{},", mod_name)) {
+        return Ok(true);
+    }
+
+    // Entries are indented one level deeper than the closing parenthesis
+    let indent = benches
+        .as_str()
+        .lines()
+        .last()
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .unwrap_or("\t\t");
+    let entry = format!("{}[{}, {}]\n", indent, mod_name, to_pascal_case(&mod_name));
+    let mut buffer = original.clone();
+    buffer.insert_str(benches.end(), &entry);
+    fs::write(runtime_lib_path, buffer)?;
+
+    Ok(true)
+}
+
+/// The index of the pallet `mod_name` in `construct_runtime!`: the requested one, its current one, or the
+/// next free one.
+fn pallet_index(runtime_lib: &str, mod_name: &str, index: Option<u8>) -> SubstrateResult<u8> {
+    let pallets = construct_runtime_pallets(runtime_lib)?;
+    let existing_index = pallets
+        .iter()
+        .find(|pallet| pallet.crate_name == mod_name)
+        .map(|pallet| pallet.index);
+    match (index, existing_index) {
+        (Some(index), _) => {
+            if let Some(pallet) = pallets
+                .iter()
+                .find(|pallet| pallet.index == index && pallet.crate_name != mod_name)
+            {
+                anyhow::bail!("index {} is already used by `{}` in `construct_runtime!`", index, pallet.name);
+            }
+            Ok(index)
+        }
+        (None, Some(index)) => Ok(index),
+        // Indices of removed pallets aren't reused, their storage may still be on-chain
+        (None, None) => match pallets.iter().map(|pallet| pallet.index).max() {
+            Some(last) => last
+                .checked_add(1)
+                .ok_or_else(|| anyhow::anyhow!("no pallet index left in `construct_runtime!`")),
+            None => Ok(0),
+        },
+    }
+}
+
 // Inspired by parity's substrate-deps: https://github.com/paritytech/substrate-deps/blob/master/src/runtime.rs#L11
+/// Implements the pallet's `Config` for the runtime and declares it in `construct_runtime!` with an explicit
+/// index, so that reordering the pallets can't change it.
+///
+/// Returns the line of its `Config` implementation and its index.
 pub fn add_pallet_to_runtime(
     cwd: &Path,
     runtime_path: &Path,
     crate_spec: &str,
-) -> SubstrateResult<Option<usize>> {
+    config_items: &[String],
+    index: Option<u8>,
+) -> SubstrateResult<(Option<usize>, u8)> {
     let runtime_lib_path = cwd.join(runtime_path).join("src/lib.rs");
     let mod_name = to_snake_case(crate_spec);
 
@@ -83,17 +183,25 @@ pub fn add_pallet_to_runtime(
     )?;
 
     let mut pallet_trait_impl = format!("impl {}::Config for Runtime {{ \n", mod_name);
-    pallet_trait_impl.push_str(&format!("	/* {} Trait config goes here */ \n", mod_name));
+    if config_items.is_empty() {
+        pallet_trait_impl.push_str(&format!("	/* {} Trait config goes here */ \n", mod_name));
+    }
+    for item in config_items {
+        pallet_trait_impl.push_str(&format!("	{}\n", item));
+    }
     pallet_trait_impl.push('}');
 
+    let original = fs::read_to_string(&runtime_lib_path)?;
+    let index = pallet_index(&original, &mod_name, index)?;
+
     let pallet_config = format!(
         r"
-        {}: {},",
+        {}: {} = {},",
         to_pascal_case(&mod_name),
-        mod_name
+        mod_name,
+        index
     );
 
-    let original = fs::read_to_string(&runtime_lib_path)?;
     let mut buffer = original.clone();
     let mut line_number: Option<usize> = None;
     if pallet_trait_existing.is_match(&original) {
@@ -111,38 +219,36 @@ pub fn add_pallet_to_runtime(
         buffer.insert_str(mat.start(), format!("{}\n\n", pallet_trait_impl).as_str());
     };
 
-    let modified = buffer.clone();
-    let caps = construct_runtime
-        .captures(&modified)
-        .ok_or_else(|| anyhow::anyhow!("couldn't find construct_runtime call"))?;
-    let pallets = caps.name("pallets").ok_or_else(|| {
-        anyhow::anyhow!("couldn't find runtime pallets config inside construct_runtime",)
-    })?;
-
-    let existing_pallets = modified.get(pallets.start()..pallets.end()).unwrap();
-    let line_number_with_mod_name = existing_pallets
-        .lines()
-        .position(|line| line.contains(&mod_name));
-
-    if let Some(line_number) = line_number_with_mod_name {
-        let line_to_replace = existing_pallets.lines().nth(line_number).unwrap();
-        let new_existing_pallets = existing_pallets.replace(line_to_replace, &pallet_config[1..]);
-
-        let new_buffer = modified.replacen(existing_pallets, &new_existing_pallets, 1);
-        fs::write(runtime_lib_path, new_buffer)?;
-    } else {
-        // Insert the pallet_config at the end of pallets
-        buffer.insert_str(pallets.end() - 2, &pallet_config);
-        fs::write(runtime_lib_path, buffer)?;
-    }
+    // A pallet already declared keeps its entry, only its index is set
+    let buffer = match set_pallet_index(&buffer, &mod_name, index)? {
+        Some(updated) => updated,
+        None => {
+            let caps = construct_runtime
+                .captures(&buffer)
+                .ok_or_else(|| anyhow::anyhow!("couldn't find construct_runtime call"))?;
+            let pallets_end = caps
+                .name("pallets")
+                .ok_or_else(|| anyhow::anyhow!("couldn't find runtime pallets config inside construct_runtime",))?
+                .end();
+            // Insert the pallet_config at the end of pallets
+            let mut buffer = buffer;
+            buffer.insert_str(pallets_end - 2, &pallet_config);
+            buffer
+        }
+    };
+    fs::write(runtime_lib_path, buffer)?;
 
-    Ok(line_number)
+    Ok((line_number, index))
 }
 
 // TODO:
 // - Make sure the crate is a valid pallet
 // - Try to implement pallet's `Config` trait for runtime by scraping docs to try to find the default implementation
 pub fn add_pallet(opts: &AddOptions, config: &Config) -> SubstrateResult<()> {
+    // Check the requested index before changing anything
+    let runtime_lib = fs::read_to_string(config.cwd().join(&opts.package_path).join("src/lib.rs"))?;
+    pallet_index(&runtime_lib, &to_snake_case(&opts.crate_spec), opts.index)?;
+
     let crate_source_arg = match &opts.source {
         CrateSource::DefaultRegistry => vec![],
         CrateSource::Git(url, branch) => {
@@ -157,12 +263,13 @@ pub fn add_pallet(opts: &AddOptions, config: &Config) -> SubstrateResult<()> {
     };
 
     let status = Command::new("cargo")
+        .current_dir(config.cwd())
         .arg("add")
         .arg("-p")
         .arg(&opts.package_name)
         .arg(&opts.crate_spec)
         .arg("--features")
-        .arg(&opts.features.join(","))
+        .arg(opts.features.join(","))
         .arg("--no-default-features")
         .args(crate_source_arg)
         .status()?;
@@ -176,13 +283,18 @@ pub fn add_pallet(opts: &AddOptions, config: &Config) -> SubstrateResult<()> {
 
     add_pallet_std_to_manifest(config.cwd(), &opts.package_path, &opts.crate_spec)?;
 
-    let trait_line_number =
-        add_pallet_to_runtime(config.cwd(), &opts.package_path, &opts.crate_spec)?;
+    let (trait_line_number, index) = add_pallet_to_runtime(
+        config.cwd(),
+        &opts.package_path,
+        &opts.crate_spec,
+        &opts.config_items,
+        opts.index,
+    )?;
     println!(
-        "\nPallet `{}` has been successfully added to the runtime!",
-        opts.crate_spec
+        "\nPallet `{}` has been successfully added to the runtime at index {}!",
+        opts.crate_spec, index
     );
-    if let Some(line_number) = trait_line_number {
+    if let (Some(line_number), true) = (trait_line_number, opts.config_items.is_empty()) {
         println!(
             "Don't forget to implement the `Config` trait in `runtime/src/lib.rs`, line: {}",
             line_number
@@ -0,0 +1,211 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::Context as _;
+
+use crate::{
+    core::Shell,
+    ops::substrate_run::node_binary_name,
+    templates::FRAME_WEIGHT_TEMPLATE,
+    util::{
+        config::{ChainInfo, ChainMemberKind},
+        to_snake_case, Config, SubstrateResult,
+    },
+};
+
+pub const DEFAULT_STEPS: u64 = 50;
+pub const DEFAULT_REPEAT: u64 = 20;
+
+/// Target directory of the node built with benchmarks, so that it doesn't replace the regular node.
+const BENCHMARKS_TARGET_DIR: &str = "target/benchmarks";
+
+/// Weight template of the project, used instead of the embedded one when present.
+const PROJECT_WEIGHT_TEMPLATE: &str = ".maintain/frame-weight-template.hbs";
+
+pub struct BenchOptions {
+    /// Pallets to benchmark, as listed by the node, e.g. `pallet_template`
+    pub pallets: Vec<String>,
+    pub steps: u64,
+    pub repeat: u64,
+}
+
+/// A pallet with benchmarks in the runtime, and its benchmarked extrinsics.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PalletBenchmarks {
+    pub pallet: String,
+    pub extrinsics: Vec<String>,
+}
+
+/// Builds the node with the `runtime-benchmarks` feature, returning the path of its binary.
+pub fn build_benchmark_node(config: &Config, chain: &ChainInfo) -> SubstrateResult<PathBuf> {
+    let mut command = Command::new("cargo");
+    command
+        .current_dir(config.cwd())
+        .args(["+nightly", "build", "--release", "--features", "runtime-benchmarks"])
+        .args(["--target-dir", BENCHMARKS_TARGET_DIR]);
+    if let Some(node_name) = &chain.node_name {
+        command.args(["-p", node_name]);
+    }
+    let status = command.status()?;
+    if !status.success() {
+        anyhow::bail!("failed to build the node with benchmarks");
+    }
+
+    Ok(config
+        .cwd()
+        .join(BENCHMARKS_TARGET_DIR)
+        .join("release")
+        .join(node_binary_name(config, chain)?))
+}
+
+/// Parses the `pallet, extrinsic` lines printed by `benchmark pallet --list`.
+fn parse_benchmark_list(output: &str) -> Vec<PalletBenchmarks> {
+    let mut benchmarks: Vec<PalletBenchmarks> = Vec::new();
+    for (pallet, extrinsic) in output
+        .lines()
+        .filter_map(|line| line.split_once(','))
+        .map(|(pallet, extrinsic)| (pallet.trim(), extrinsic.trim()))
+        // Skip the header
+        .filter(|(pallet, _)| *pallet != "pallet")
+    {
+        match benchmarks.iter_mut().find(|benchmark| benchmark.pallet == pallet) {
+            Some(benchmark) => benchmark.extrinsics.push(extrinsic.to_string()),
+            None => benchmarks.push(PalletBenchmarks {
+                pallet: pallet.to_string(),
+                extrinsics: vec![extrinsic.to_string()],
+            }),
+        }
+    }
+    benchmarks
+}
+
+/// The pallets of the runtime that have benchmarks, according to the node built with them.
+pub fn list_benchmarks(binary: &Path) -> SubstrateResult<Vec<PalletBenchmarks>> {
+    let output = Command::new(binary)
+        .args(["benchmark", "pallet", "--chain", "dev", "--list"])
+        .output()
+        .with_context(|| format!("failed to run `{}`", binary.display()))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "failed to list the benchmarks:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(parse_benchmark_list(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// The pallets of the chain's workspace, by the name the runtime's benchmarks know them by.
+pub fn local_pallets(config: &Config, chain: &ChainInfo) -> SubstrateResult<Vec<(String, PathBuf)>> {
+    Ok(chain
+        .members(config.cwd())?
+        .into_iter()
+        .filter(|member| member.kind == ChainMemberKind::Pallet)
+        .map(|member| (to_snake_case(&member.name), member.path))
+        .collect())
+}
+
+/// The weight template for local pallets: the project's own, or the embedded one written to the
+/// target directory.
+fn weight_template(config: &Config) -> SubstrateResult<PathBuf> {
+    let template = config.cwd().join(PROJECT_WEIGHT_TEMPLATE);
+    if template.exists() {
+        return Ok(template);
+    }
+
+    let template = config.cwd().join("target").join("frame-weight-template.hbs");
+    fs::create_dir_all(config.cwd().join("target"))?;
+    fs::write(&template, FRAME_WEIGHT_TEMPLATE)?;
+    Ok(template)
+}
+
+/// Benchmarks the selected pallets with the given node binary.
+///
+/// The weights of local pallets are written to their `src/weights.rs`, those of other pallets to
+/// the runtime's `src/weights` directory.
+pub fn bench(opts: &BenchOptions, config: &Config, chain: &ChainInfo, binary: &Path) -> SubstrateResult<()> {
+    let mut shell = Shell::new();
+    let local_pallets = local_pallets(config, chain)?;
+    let template = weight_template(config)?;
+
+    for pallet in &opts.pallets {
+        let local_path = local_pallets
+            .iter()
+            .find(|(name, _)| name == pallet)
+            .map(|(_, path)| config.cwd().join(path));
+        let output = match &local_path {
+            Some(path) => path.join("src/weights.rs"),
+            None => config.cwd().join(&chain.runtime_path).join("src/weights").join(format!("{}.rs", pallet)),
+        };
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        shell.status("Benchmarking", pallet)?;
+        let mut command = Command::new(binary);
+        command
+            .current_dir(config.cwd())
+            .args(["benchmark", "pallet", "--chain", "dev", "--pallet", pallet, "--extrinsic", "*"])
+            .args(["--steps", &opts.steps.to_string(), "--repeat", &opts.repeat.to_string()])
+            .arg("--output")
+            .arg(&output);
+        // Other pallets get the node's default template, which implements their `WeightInfo` for the runtime
+        if local_path.is_some() {
+            command.arg("--template").arg(&template);
+        }
+        let status = command.status()?;
+        if !status.success() {
+            anyhow::bail!("failed to benchmark `{}`", pallet);
+        }
+        shell.status("Wrote", output.display())?;
+
+        if let Some(path) = local_path {
+            let lib = fs::read_to_string(path.join("src/lib.rs")).unwrap_or_default();
+            if !lib.contains("mod weights;") {
+                shell.note(format!(
+                    "add `pub mod weights;` to `{}` and use `weights::SubstrateWeight<Runtime>` as the pallet's `WeightInfo`",
+                    path.join("src/lib.rs").display()
+                ))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_benchmark_list() {
+        let output = "pallet, benchmark
+frame_benchmarking, addition
+frame_benchmarking, subtraction
+pallet_template, do_something
+pallet_balances, transfer_allow_death
+";
+
+        let benchmarks = parse_benchmark_list(output);
+        assert_eq!(
+            benchmarks,
+            [
+                PalletBenchmarks {
+                    pallet: "frame_benchmarking".to_string(),
+                    extrinsics: vec!["addition".to_string(), "subtraction".to_string()],
+                },
+                PalletBenchmarks {
+                    pallet: "pallet_template".to_string(),
+                    extrinsics: vec!["do_something".to_string()],
+                },
+                PalletBenchmarks {
+                    pallet: "pallet_balances".to_string(),
+                    extrinsics: vec!["transfer_allow_death".to_string()],
+                },
+            ]
+        );
+    }
+}
@@ -1,11 +1,584 @@
-use std::process::Command;
+use std::{
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
 
-use crate::util::SubstrateResult;
+use anyhow::Context as _;
+use cargo_util::ProcessError;
+use serde_json::{json, Value};
+use toml_edit::{value, Item, Table};
 
-pub fn build() -> SubstrateResult<()> {
-    Command::new("cargo-contract")
-        .args(["contract", "build"])
+use crate::{
+    core::{
+        contract_bundle::ContractBundle,
+        manifest::Manifest,
+        runtime_wasm::{
+            blake2_256, parachain_authorize_upgrade_call, set_code_call, to_hex, RuntimeWasm,
+        },
+        Shell,
+    },
+    ops::substrate_testnet::is_parachain_node,
+    util::{
+        config::{ChainInfo, ContractMember},
+        to_snake_case, Config, SubstrateResult,
+    },
+};
+
+/// Directory, relative to the project root, where runtime wasm artifacts are copied.
+pub const ARTIFACTS_DIR: &str = "artifacts";
+
+/// The srtool image deterministic builds run in, unless `build.srtool_image` is set in Substrate.toml.
+///
+/// A tag can be re-pushed, so the first build resolves it to its digest and records the digest
+/// (`docker.io/paritytech/srtool@sha256:…`) as `build.srtool_image`.
+pub const DEFAULT_SRTOOL_IMAGE: &str = "docker.io/paritytech/srtool:1.70.0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildTarget {
+    /// The node, which embeds the runtime
+    Node,
+    /// Only the runtime wasm
+    Runtime,
+}
+
+pub struct ChainBuildOptions {
+    pub target: BuildTarget,
+    /// Cargo profile, e.g. `release` or `debug`
+    pub profile: String,
+    /// Copy the runtime wasm to the artifacts directory
+    pub copy_artifacts: bool,
+    /// Build the runtime in a pinned srtool container
+    pub deterministic: bool,
+    /// Blake2-256 hash, or srtool JSON report, the runtime wasm must match
+    pub verify: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct ContractBuildOptions {
+    /// Build with optimizations, cargo-contract builds in debug mode by default
+    pub release: bool,
+    /// Build in a docker container so that the code can be verified
+    pub verifiable: bool,
+    pub manifest_path: Option<PathBuf>,
+    /// Forward cargo-contract's JSON output, the summary goes to stderr instead
+    pub output_json: bool,
+    pub target_dir: Option<PathBuf>,
+}
+
+/// Finds the `.contract` bundle cargo-contract produced for the contract at `manifest_path`.
+pub(crate) fn find_contract_bundle(manifest_path: &Path, target_dir: Option<&Path>) -> SubstrateResult<PathBuf> {
+    let contract_dir = manifest_path.parent().unwrap_or(Path::new("."));
+    let ink_dir = target_dir
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| contract_dir.join("target"))
+        .join("ink");
+
+    // Artifacts are named after the lib target, i.e. the package name in snake case
+    let package_name = Manifest::new(manifest_path.to_path_buf())
+        .read_document()
+        .ok()
+        .and_then(|document| {
+            document
+                .get("package")
+                .and_then(|package| package.get("name"))
+                .and_then(|name| name.as_str())
+                .map(|name| name.replace('-', "_"))
+        });
+    if let Some(name) = package_name {
+        // Workspace members build into the workspace's target directory, under their own name
+        let ink_dirs = match target_dir {
+            Some(_) => vec![ink_dir.clone()],
+            None => contract_dir
+                .ancestors()
+                .map(|dir| dir.join("target").join("ink"))
+                .collect(),
+        };
+        let bundle = ink_dirs.iter().find_map(|dir| {
+            [
+                dir.join(format!("{}.contract", name)),
+                dir.join(&name).join(format!("{}.contract", name)),
+            ]
+            .into_iter()
+            .find(|bundle| bundle.exists())
+        });
+        if let Some(bundle) = bundle {
+            return Ok(bundle);
+        }
+    }
+
+    let pattern = format!("{}/*.contract", glob::Pattern::escape(&ink_dir.to_string_lossy()));
+    glob::glob(&pattern)?
+        .flatten()
+        .max_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .with_context(|| format!("no contract bundle found under `{}`", ink_dir.display()))
+}
+
+/// Describes the code hash, size and interface of a built contract.
+pub fn contract_summary(bundle: &ContractBundle) -> SubstrateResult<String> {
+    let mut summary = String::new();
+    writeln!(
+        summary,
+        "Contract `{}`: {}",
+        bundle.name().unwrap_or("unknown"),
+        bundle.path().display()
+    )?;
+    writeln!(summary, "  code hash  {}", bundle.code_hash().unwrap_or("unknown"))?;
+    if let Some(wasm) = bundle.wasm()? {
+        writeln!(
+            summary,
+            "  wasm size  {:.1} KB ({} bytes)",
+            wasm.len() as f64 / 1000.0,
+            wasm.len()
+        )?;
+    }
+
+    writeln!(summary, "\nConstructors:")?;
+    for constructor in bundle.constructors() {
+        let payable = if constructor.payable { " (payable)" } else { "" };
+        writeln!(summary, "  {}{}", constructor.signature(), payable)?;
+    }
+    writeln!(summary, "\nMessages:")?;
+    for message in bundle.messages() {
+        let mut flags = Vec::new();
+        if !message.mutates {
+            flags.push("query");
+        }
+        if message.payable {
+            flags.push("payable");
+        }
+        let flags = if flags.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", flags.join(", "))
+        };
+        let return_type = message
+            .return_type
+            .as_ref()
+            .map(|return_type| format!(" -> {}", return_type))
+            .unwrap_or_default();
+        writeln!(summary, "  {}{}{}", message.signature(), return_type, flags)?;
+    }
+
+    Ok(summary)
+}
+
+pub fn build(opts: &ContractBuildOptions, config: &Config) -> SubstrateResult<ContractBundle> {
+    let manifest_path = config
+        .cwd()
+        .join(opts.manifest_path.as_deref().unwrap_or(Path::new("Cargo.toml")));
+    let target_dir = opts.target_dir.as_ref().map(|dir| config.cwd().join(dir));
+
+    let mut command = Command::new("cargo-contract");
+    command
+        .current_dir(config.cwd())
+        .args(["contract", "build", "--manifest-path"])
+        .arg(&manifest_path);
+    if opts.release {
+        command.arg("--release");
+    }
+    if opts.verifiable {
+        command.arg("--verifiable");
+    }
+    if let Some(target_dir) = &target_dir {
+        command.arg("--target-dir").arg(target_dir);
+    }
+
+    let bundle_path = if opts.output_json {
+        command.arg("--output-json").stdout(Stdio::piped());
+        let output = command.output()?;
+        if !output.status.success() {
+            return Err(ProcessError::new("`cargo contract build` failed", Some(output.status), None).into());
+        }
+        // Forward the JSON untouched so that it can be piped into other tools, everything else goes to stderr
+        print!("{}", String::from_utf8_lossy(&output.stdout));
+
+        let result = serde_json::from_slice::<Value>(&output.stdout)
+            .with_context(|| "unexpected JSON output from cargo-contract")?;
+        match result["metadata_result"]["dest_bundle"].as_str() {
+            Some(bundle) => config.cwd().join(bundle),
+            None => find_contract_bundle(&manifest_path, target_dir.as_deref())?,
+        }
+    } else {
+        let status = command.status()?;
+        if !status.success() {
+            return Err(ProcessError::new("`cargo contract build` failed", Some(status), None).into());
+        }
+        find_contract_bundle(&manifest_path, target_dir.as_deref())?
+    };
+
+    let bundle = ContractBundle::read(bundle_path)?;
+    let summary = contract_summary(&bundle)?;
+    if opts.output_json {
+        eprint!("\n{}", summary);
+    } else {
+        print!("\n{}", summary);
+    }
+
+    Ok(bundle)
+}
+
+/// Builds each of `contracts`, unless a single contract is targeted with `opts.manifest_path`.
+pub fn build_contracts(
+    opts: &ContractBuildOptions,
+    contracts: &[ContractMember],
+    config: &Config,
+) -> SubstrateResult<Vec<ContractBundle>> {
+    if opts.manifest_path.is_some() {
+        return Ok(vec![build(opts, config)?]);
+    }
+
+    let mut bundles = Vec::new();
+    for (index, contract) in contracts.iter().enumerate() {
+        if contracts.len() > 1 {
+            eprintln!("{}Building `{}`...", if index > 0 { "\n" } else { "" }, contract.name);
+        }
+        let opts = ContractBuildOptions {
+            manifest_path: Some(contract.path.join("Cargo.toml")),
+            ..opts.clone()
+        };
+        bundles.push(build(&opts, config)?);
+    }
+    Ok(bundles)
+}
+
+/// The built-in cargo profiles, followed by the custom ones of the workspace (e.g. `production`).
+pub fn build_profiles(config: &Config) -> Vec<String> {
+    let mut profiles = vec!["release".to_string(), "debug".to_string()];
+
+    let workspace_document = Manifest::new(config.cwd().join("Cargo.toml")).read_document();
+    if let Some(custom) = workspace_document
+        .ok()
+        .as_ref()
+        .and_then(|document| document.get("profile"))
+        .and_then(|profile| profile.as_table_like())
+    {
+        for (name, _) in custom.iter() {
+            if !["release", "dev", "test", "bench"].contains(&name) {
+                profiles.push(name.to_string());
+            }
+        }
+    }
+
+    profiles
+}
+
+/// Directory of `target/` where cargo puts the artifacts of `profile`.
+fn profile_dir(profile: &str) -> &str {
+    match profile {
+        "dev" | "debug" => "debug",
+        profile => profile,
+    }
+}
+
+/// Finds the compressed runtime wasm produced by the last build of `profile` in `target_dir`.
+pub fn find_runtime_wasm(target_dir: &Path, chain: &ChainInfo, profile: &str) -> SubstrateResult<PathBuf> {
+    let wbuild_dir = target_dir.join(profile_dir(profile)).join("wbuild");
+    let pattern = format!(
+        "{}/*/*.compact.compressed.wasm",
+        glob::Pattern::escape(&wbuild_dir.to_string_lossy())
+    );
+    let candidates = glob::glob(&pattern)?.flatten().collect::<Vec<_>>();
+
+    let expected_name = chain
+        .runtime_name
+        .as_deref()
+        .map(|name| format!("{}.compact.compressed.wasm", to_snake_case(name)));
+    if let Some(wasm) = candidates.iter().find(|path| {
+        path.file_name()
+            .map(|name| Some(name.to_string_lossy().to_string()) == expected_name)
+            .unwrap_or(false)
+    }) {
+        return Ok(wasm.clone());
+    }
+
+    // Workspaces with several runtimes: fall back to the most recently built one
+    candidates
+        .into_iter()
+        .max_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .with_context(|| format!("no runtime wasm found under `{}`", wbuild_dir.display()))
+}
+
+/// Prints the size, hash and version of a runtime wasm.
+pub fn print_runtime_wasm(config: &Config, wasm: &RuntimeWasm) -> SubstrateResult<()> {
+    let version = wasm.runtime_version()?;
+    let size = wasm.code().len();
+
+    println!(
+        "Runtime wasm: {}",
+        wasm.path()
+            .strip_prefix(config.cwd())
+            .unwrap_or(wasm.path())
+            .display()
+    );
+    println!(
+        "  size          {:.2} MB ({} bytes)",
+        size as f64 / 1_000_000.0,
+        size
+    );
+    println!("  blake2-256    {}", to_hex(&wasm.blake2_256()));
+    println!("  spec_version  {} ({})", version.spec_version, version.spec_name);
+
+    Ok(())
+}
+
+/// Copies the runtime wasm to the artifacts directory, returning the copy's path.
+pub fn copy_artifact(config: &Config, wasm: &Path) -> SubstrateResult<PathBuf> {
+    let artifacts_dir = config.cwd().join(ARTIFACTS_DIR);
+    fs::create_dir_all(&artifacts_dir)?;
+
+    let artifact = artifacts_dir.join(wasm.file_name().with_context(|| "invalid wasm path")?);
+    fs::copy(wasm, &artifact)?;
+
+    Ok(artifact)
+}
+
+pub fn build_chain(opts: &ChainBuildOptions, config: &Config, chain: &ChainInfo) -> SubstrateResult<RuntimeWasm> {
+    if opts.deterministic {
+        return build_deterministic(opts, config, chain);
+    }
+
+    let package = match opts.target {
+        BuildTarget::Node => chain.node_name.as_ref(),
+        BuildTarget::Runtime => chain.runtime_name.as_ref(),
+    };
+
+    let mut command = Command::new("cargo");
+    command.current_dir(config.cwd()).args(["+nightly", "build"]);
+    match opts.profile.as_str() {
+        "dev" | "debug" => {}
+        "release" => {
+            command.arg("--release");
+        }
+        profile => {
+            command.args(["--profile", profile]);
+        }
+    }
+    if let Some(package) = package {
+        command.args(["-p", package]);
+    }
+    let status = command.status()?;
+    if !status.success() {
+        anyhow::bail!("failed to build the chain");
+    }
+
+    let wasm = RuntimeWasm::read(find_runtime_wasm(
+        &config.cwd().join("target"),
+        chain,
+        &opts.profile,
+    )?)?;
+    report_runtime_wasm(opts, config, &wasm)?;
+
+    Ok(wasm)
+}
+
+fn report_runtime_wasm(opts: &ChainBuildOptions, config: &Config, wasm: &RuntimeWasm) -> SubstrateResult<()> {
+    println!();
+    print_runtime_wasm(config, wasm)?;
+
+    if let Some(expected) = &opts.verify {
+        verify_runtime_wasm(config, wasm, expected)?;
+    }
+    if opts.copy_artifacts {
+        let artifact = copy_artifact(config, wasm.path())?;
+        println!("\nRuntime wasm copied to `{}`", artifact.display());
+    }
+
+    Ok(())
+}
+
+/// The container runtime used for deterministic builds.
+fn container_runtime() -> SubstrateResult<PathBuf> {
+    ["docker", "podman"]
+        .into_iter()
+        .find_map(|runtime| which::which(runtime).ok())
+        .with_context(|| "deterministic builds need a container runtime, install docker or podman")
+}
+
+fn srtool_image(config: &Config) -> SubstrateResult<Option<String>> {
+    let manifest_path = config.cwd().join("Substrate.toml");
+    if !manifest_path.exists() {
+        return Ok(None);
+    }
+    let document = Manifest::new(manifest_path).read_document()?;
+    Ok(document
+        .get("build")
+        .and_then(|build| build.get("srtool_image"))
+        .and_then(|image| image.as_str())
+        .map(|image| image.to_string()))
+}
+
+/// Whether `image` is pinned by digest, e.g. `docker.io/paritytech/srtool@sha256:…`.
+fn is_pinned(image: &str) -> bool {
+    image.contains("@sha256:")
+}
+
+/// Pulls `image` and returns its digest reference.
+fn resolve_digest(container_runtime: &Path, image: &str) -> SubstrateResult<String> {
+    let status = Command::new(container_runtime).args(["pull", image]).status()?;
+    if !status.success() {
+        anyhow::bail!("failed to pull `{}`", image);
+    }
+
+    let output = Command::new(container_runtime)
+        .args(["image", "inspect", "--format", "{{index .RepoDigests 0}}", image])
+        .output()?;
+    let digest = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || !is_pinned(&digest) {
+        anyhow::bail!("couldn't resolve the digest of `{}`", image);
+    }
+
+    Ok(digest)
+}
+
+/// Records the digest the default image resolved to as `build.srtool_image`, keeping its tag in a comment.
+fn record_srtool_image(config: &Config, image: &str) -> SubstrateResult<()> {
+    let mut manifest = Manifest::new(config.cwd().join("Substrate.toml"));
+    let mut document = manifest.read_or_new_document()?;
+    if !document.contains_key("build") {
+        document.insert("build", Item::Table(Table::new()));
+    }
+    let mut image = value(image);
+    if let Some(image) = image.as_value_mut() {
+        image.decor_mut().set_suffix(format!(" # {}", DEFAULT_SRTOOL_IMAGE));
+    }
+    document["build"]["srtool_image"] = image;
+
+    manifest.write_document(document)
+}
+
+/// Builds the runtime in an srtool container, so that anyone can reproduce the same wasm.
+pub fn build_deterministic(opts: &ChainBuildOptions, config: &Config, chain: &ChainInfo) -> SubstrateResult<RuntimeWasm> {
+    if opts.target != BuildTarget::Runtime {
+        anyhow::bail!("deterministic builds only produce the runtime, build the `runtime` target");
+    }
+
+    let container_runtime = container_runtime()?;
+    let image = match srtool_image(config)? {
+        Some(image) => {
+            if !is_pinned(&image) {
+                Shell::new().warn(format!(
+                    "`{}` is pinned by tag only, pin it by digest for the build to be verifiable",
+                    image
+                ))?;
+            }
+            image
+        }
+        None => {
+            let image = resolve_digest(&container_runtime, DEFAULT_SRTOOL_IMAGE)?;
+            record_srtool_image(config, &image)?;
+            println!("Pinned `{}` to `{}` in Substrate.toml", DEFAULT_SRTOOL_IMAGE, image);
+            image
+        }
+    };
+    let package = chain
+        .runtime_name
+        .as_deref()
+        .with_context(|| "couldn't determine the runtime's package name")?;
+    let runtime_dir = chain
+        .runtime_path
+        .strip_prefix(config.cwd())
+        .unwrap_or(&chain.runtime_path);
+
+    println!("Building `{}` in `{}`...\n", package, image);
+    let status = Command::new(container_runtime)
+        .arg("run")
+        .arg("--rm")
+        .args(["-e", &format!("PACKAGE={}", package)])
+        .args(["-e", &format!("RUNTIME_DIR={}", runtime_dir.display())])
+        .args(["-e", &format!("PROFILE={}", opts.profile)])
+        .arg("-v")
+        .arg(format!("{}:/build", config.cwd().display()))
+        .arg(&image)
+        .args(["build", "--app"])
         .status()?;
+    if !status.success() {
+        anyhow::bail!("the deterministic build failed");
+    }
+
+    // srtool keeps its build separate from the regular target directory
+    let target_dir = config.cwd().join(runtime_dir).join("target/srtool");
+    let wasm = RuntimeWasm::read(find_runtime_wasm(&target_dir, chain, &opts.profile)?)?;
+
+    let report = srtool_report(config, chain, &wasm, &image)?;
+    let artifacts_dir = config.cwd().join(ARTIFACTS_DIR);
+    fs::create_dir_all(&artifacts_dir)?;
+    let report_path = artifacts_dir.join(format!("{}-srtool.json", to_snake_case(package)));
+    fs::write(&report_path, serde_json::to_string_pretty(&report)?)?;
+
+    report_runtime_wasm(opts, config, &wasm)?;
+    println!(
+        "  proposal hash {}",
+        report["runtimes"]["compressed"]["prop"].as_str().unwrap_or_default()
+    );
+    println!("\nsrtool report written to `{}`", report_path.display());
+
+    Ok(wasm)
+}
+
+/// Describes a runtime wasm the way `srtool build --json` does.
+pub fn srtool_report(config: &Config, chain: &ChainInfo, wasm: &RuntimeWasm, image: &str) -> SubstrateResult<Value> {
+    let version = wasm.runtime_version()?;
+    let code_hash = wasm.blake2_256();
+    // Like srtool, assumes `System` is the first pallet of the runtime
+    let proposal_hash = to_hex(&blake2_256(&set_code_call(0, wasm.code())));
+    let parachain_authorize_upgrade_hash = is_parachain_node(config, chain)?
+        .then(|| to_hex(&blake2_256(&parachain_authorize_upgrade_call(&code_hash))));
+    let wasm_path = wasm.path().strip_prefix(config.cwd()).unwrap_or(wasm.path());
+
+    Ok(json!({
+        "gen": format!("substrate-manager v{}", env!("CARGO_PKG_VERSION")),
+        "image": image,
+        "pkg": chain.runtime_name,
+        "runtimes": {
+            "compressed": {
+                "size": wasm.code().len().to_string(),
+                "prop": proposal_hash,
+                "blake2_256": to_hex(&code_hash),
+                "wasm": wasm_path,
+                "subwasm": {
+                    "size": wasm.code().len(),
+                    "core_version": {
+                        "specName": version.spec_name,
+                        "implName": version.impl_name,
+                        "authoringVersion": version.authoring_version,
+                        "specVersion": version.spec_version,
+                        "implVersion": version.impl_version,
+                        "transactionVersion": version.transaction_version,
+                    },
+                    "proposal_hash": proposal_hash,
+                    "parachain_authorize_upgrade_hash": parachain_authorize_upgrade_hash,
+                    "blake2_256": to_hex(&code_hash),
+                },
+            },
+        },
+    }))
+}
+
+/// Checks the wasm against a published digest: either a blake2-256 hash or the path to an srtool JSON report.
+fn verify_runtime_wasm(config: &Config, wasm: &RuntimeWasm, expected: &str) -> SubstrateResult<()> {
+    let report_path = config.cwd().join(expected);
+    let expected = if report_path.is_file() {
+        let report = serde_json::from_str::<Value>(&fs::read_to_string(&report_path)?)
+            .with_context(|| format!("`{}` is not a valid srtool report", report_path.display()))?;
+        report["runtimes"]["compressed"]["blake2_256"]
+            .as_str()
+            .with_context(|| format!("no compressed runtime hash in `{}`", report_path.display()))?
+            .to_string()
+    } else {
+        expected.to_string()
+    };
+
+    let actual = to_hex(&wasm.blake2_256());
+    if actual.trim_start_matches("0x") != expected.trim_start_matches("0x").to_lowercase() {
+        anyhow::bail!(
+            "the runtime wasm doesn't match the published digest\n  expected  {}\n  actual    {}",
+            expected,
+            actual
+        );
+    }
+    println!("\n✅ The runtime wasm matches the published digest");
 
     Ok(())
 }
@@ -0,0 +1,201 @@
+use anyhow::Context as _;
+use serde_json::Value;
+
+use crate::{
+    ops::{
+        substrate_contracts_node::ensure_node_reachable,
+        substrate_deploy::{
+            cargo_contract, cargo_contract_json, contract_bundle, last_deployment, network_name,
+            Limits, Signer,
+        },
+    },
+    util::{config::ContractMember, Config, SubstrateResult},
+};
+
+pub struct CallOptions {
+    pub contract: ContractMember,
+    pub url: String,
+    /// Address of the contract, defaults to its last deployment on the network
+    pub address: Option<String>,
+    pub message: String,
+    pub args: Vec<String>,
+    /// Secret URI of the caller, e.g. `//Alice`
+    pub suri: String,
+}
+
+/// Formats a value decoded by cargo-contract, e.g. `{"Tuple":{"ident":"Ok","values":[{"Bool":true}]}}` as `Ok(true)`.
+fn format_value(value: &Value) -> String {
+    let Some((kind, inner)) = value.as_object().and_then(|object| object.iter().next()) else {
+        return match value {
+            Value::String(string) if string == "Unit" => "()".to_string(),
+            Value::String(string) => string.clone(),
+            value => value.to_string(),
+        };
+    };
+
+    let ident = inner["ident"].as_str().unwrap_or_default();
+    match (kind.as_str(), inner) {
+        ("Tuple", _) => {
+            let values = inner["values"]
+                .as_array()
+                .map(|values| values.iter().map(format_value).collect::<Vec<_>>())
+                .unwrap_or_default();
+            if ident.is_empty() || !values.is_empty() {
+                format!("{}({})", ident, values.join(", "))
+            } else {
+                ident.to_string()
+            }
+        }
+        ("Map", _) => {
+            let fields = match &inner["map"] {
+                Value::Object(map) => map
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", key, format_value(value)))
+                    .collect::<Vec<_>>(),
+                Value::Array(entries) => entries
+                    .iter()
+                    .map(|entry| format!("{}: {}", format_value(&entry[0]), format_value(&entry[1])))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            format!("{} {{ {} }}", ident, fields.join(", ")).trim_start().to_string()
+        }
+        ("Seq", _) => {
+            let elems = inner["elems"]
+                .as_array()
+                .map(|elems| elems.iter().map(format_value).collect::<Vec<_>>())
+                .unwrap_or_default();
+            format!("[{}]", elems.join(", "))
+        }
+        ("Hex", Value::Object(hex)) => hex
+            .get("s")
+            .and_then(Value::as_str)
+            .map(|s| format!("0x{}", s.trim_start_matches("0x")))
+            .unwrap_or_else(|| inner.to_string()),
+        (_, Value::String(string)) => string.clone(),
+        (_, inner) => inner.to_string(),
+    }
+}
+
+/// Prints the events cargo-contract decoded from a submitted call.
+fn print_events(result: &Value) {
+    let events = result
+        .get("events")
+        .unwrap_or(result)
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    if events.is_empty() {
+        return;
+    }
+
+    println!("\nEvents:");
+    for event in events {
+        println!(
+            "  {}::{}",
+            event["pallet"].as_str().unwrap_or_default(),
+            event["name"].as_str().unwrap_or_default()
+        );
+        for field in event["fields"].as_array().into_iter().flatten() {
+            println!(
+                "    {}: {}",
+                field["name"].as_str().unwrap_or_default(),
+                format_value(&field["value"])
+            );
+        }
+    }
+}
+
+/// Calls a message of a deployed contract: queries are only dry-run, mutating messages are submitted.
+pub fn call(opts: &CallOptions, config: &Config) -> SubstrateResult<()> {
+    ensure_node_reachable(&opts.url)?;
+    let bundle = contract_bundle(config, &opts.contract)?;
+    let name = bundle.name().unwrap_or("unknown").to_string();
+    let message = bundle
+        .messages()
+        .into_iter()
+        .find(|message| message.label == opts.message)
+        .with_context(|| format!("the contract has no `{}` message", opts.message))?;
+    if message.args.len() != opts.args.len() {
+        anyhow::bail!(
+            "`{}` expects {} argument(s), got {}",
+            message.signature(),
+            message.args.len(),
+            opts.args.len()
+        );
+    }
+
+    let address = match &opts.address {
+        Some(address) => address.clone(),
+        None => last_deployment(config, &opts.url, &name)?
+            .and_then(|deployment| deployment.address)
+            .with_context(|| {
+                format!(
+                    "no deployment of `{}` recorded for the `{}` network, deploy it first or pass its address",
+                    name,
+                    network_name(&opts.url)
+                )
+            })?,
+    };
+    println!("Calling `{}` on `{}` at {}\n", message.signature(), name, address);
+
+    let signer = Signer::new(&opts.suri)?;
+    let call = || {
+        let mut command = cargo_contract(config, "call", &opts.url, signer.uri(), bundle.path());
+        command.args(["--contract", &address, "--message", &opts.message]);
+        if !opts.args.is_empty() {
+            command.arg("--args").args(&opts.args);
+        }
+        command
+    };
+
+    let dry_run = cargo_contract_json(&mut call(), "call")?;
+    let reverted = dry_run["reverted"].as_bool().unwrap_or(false);
+    if !message.mutates || reverted {
+        let outcome = if reverted { "Reverted" } else { "Result" };
+        println!("{}: {}", outcome, format_value(&dry_run["data"]));
+        if reverted {
+            anyhow::bail!("`{}` reverted", message.label);
+        }
+        return Ok(());
+    }
+
+    let limits = Limits::from_dry_run(&dry_run);
+    println!("Dry run result: {}", format_value(&dry_run["data"]));
+    println!("Estimated limits:");
+    limits.print();
+
+    let mut command = call();
+    command.args(["--execute", "--skip-confirm"]);
+    limits.apply(&mut command);
+    let result = cargo_contract_json(&mut command, "call")?;
+
+    println!("`{}` submitted", message.label);
+    print_events(&result);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_format_value() {
+        let ok = json!({ "Tuple": { "ident": "Ok", "values": [{ "Bool": true }] } });
+        assert_eq!(format_value(&ok), "Ok(true)");
+
+        let transfer = json!({
+            "Map": {
+                "ident": "Transfer",
+                "map": { "from": { "Tuple": { "ident": "None", "values": [] } }, "value": { "UInt": 100 } },
+            }
+        });
+        assert_eq!(format_value(&transfer), "Transfer { from: None, value: 100 }");
+
+        let seq = json!({ "Seq": { "elems": [{ "UInt": 1 }, { "Literal": "5Grw" }, "Unit"] } });
+        assert_eq!(format_value(&seq), "[1, 5Grw, ()]");
+    }
+}
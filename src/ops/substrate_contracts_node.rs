@@ -0,0 +1,195 @@
+use std::{
+    env, fs,
+    net::{SocketAddr, TcpStream},
+    path::PathBuf,
+    process::Command,
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use serde_derive::Deserialize;
+
+use crate::{
+    ops::{substrate_deploy::network_name, substrate_network::DEFAULT_RPC_PORT},
+    util::{
+        process::{is_interrupted, ProcessGroup},
+        Config, SubstrateResult,
+    },
+};
+
+const CONTRACTS_NODE_BINARY: &str = "substrate-contracts-node";
+
+/// How long the node gets to open its RPC port after being started.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Local node contracts are deployed to, declared under `[node]` in `Substrate.toml`.
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct ContractsNodeConfig {
+    /// Path to the node binary, defaults to `substrate-contracts-node` from the `PATH`
+    pub binary: Option<PathBuf>,
+    pub rpc_port: Option<u16>,
+    /// Extra arguments passed verbatim to the node
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContractsNodeManifest {
+    node: Option<ContractsNodeConfig>,
+}
+
+pub fn load_contracts_node_config(config: &Config) -> SubstrateResult<ContractsNodeConfig> {
+    let manifest_path = config.cwd().join("Substrate.toml");
+    if !manifest_path.exists() {
+        return Ok(ContractsNodeConfig::default());
+    }
+
+    let content = fs::read_to_string(&manifest_path)?;
+    let manifest = toml_edit::de::from_str::<ContractsNodeManifest>(&content)
+        .with_context(|| "invalid `[node]` section in Substrate.toml")?;
+
+    Ok(manifest.node.unwrap_or_default())
+}
+
+/// Whether something accepts connections on the local RPC `port`.
+pub(crate) fn rpc_ready(port: u16) -> bool {
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+    TcpStream::connect_timeout(&address, Duration::from_millis(200)).is_ok()
+}
+
+/// The URL of the project's local contracts node, if one is running.
+pub fn running_node_url(config: &Config) -> Option<String> {
+    let port = load_contracts_node_config(config)
+        .ok()?
+        .rpc_port
+        .unwrap_or(DEFAULT_RPC_PORT);
+    rpc_ready(port).then(|| format!("ws://127.0.0.1:{}", port))
+}
+
+/// The port of a local RPC endpoint, e.g. `ws://127.0.0.1:9944`.
+pub(crate) fn rpc_port(url: &str) -> u16 {
+    url.rsplit(':')
+        .next()
+        .and_then(|port| port.trim_end_matches('/').parse().ok())
+        .unwrap_or(DEFAULT_RPC_PORT)
+}
+
+/// Fails early, with a hint, when `url` points to a local node that isn't running.
+pub(crate) fn ensure_node_reachable(url: &str) -> SubstrateResult<()> {
+    if network_name(url) != "local" {
+        return Ok(());
+    }
+
+    if !rpc_ready(rpc_port(url)) {
+        anyhow::bail!(
+            "no node is listening on `{}`, start one with the `node` command first",
+            url
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn contracts_node_binary(config: &Config, node: &ContractsNodeConfig) -> SubstrateResult<PathBuf> {
+    match &node.binary {
+        Some(binary) => {
+            let binary = config.cwd().join(binary);
+            if !binary.exists() {
+                anyhow::bail!("node binary `{}` does not exist", binary.display());
+            }
+            Ok(binary)
+        }
+        None => which::which(CONTRACTS_NODE_BINARY).with_context(|| {
+            format!(
+                "`{}` not found, install it with `cargo install contracts-node` or set `node.binary` in Substrate.toml",
+                CONTRACTS_NODE_BINARY
+            )
+        }),
+    }
+}
+
+/// A local dev node started on a temporary base path, stopped and cleaned up when dropped.
+pub(crate) struct LocalContractsNode {
+    group: ProcessGroup,
+    base_path: PathBuf,
+    pub binary: PathBuf,
+    pub url: String,
+}
+
+impl LocalContractsNode {
+    /// Starts the project's contracts node and waits until its RPC port is open.
+    ///
+    /// The node's output is only shown when `verbose`, and `None` is returned if Ctrl-C was pressed
+    /// while waiting.
+    pub(crate) fn start(config: &Config, verbose: bool) -> SubstrateResult<Option<Self>> {
+        let node = load_contracts_node_config(config)?;
+        let rpc_port = node.rpc_port.unwrap_or(DEFAULT_RPC_PORT);
+        if rpc_ready(rpc_port) {
+            anyhow::bail!(
+                "port {} is already in use, is a node already running? Otherwise set `node.rpc_port` in Substrate.toml",
+                rpc_port
+            );
+        }
+
+        let binary = contracts_node_binary(config, &node)?;
+        let base_path = env::temp_dir().join(format!("substrate-manager-node-{}", std::process::id()));
+        fs::create_dir_all(&base_path)?;
+
+        let mut command = Command::new(&binary);
+        command
+            .current_dir(config.cwd())
+            .arg("--dev")
+            .arg("--base-path")
+            .arg(&base_path)
+            .args(["--rpc-port", &rpc_port.to_string()])
+            .args(["--rpc-cors", "all"])
+            .args(&node.args);
+
+        let mut local_node = Self {
+            group: ProcessGroup::new("node".len()),
+            base_path,
+            binary,
+            url: format!("ws://127.0.0.1:{}", rpc_port),
+        };
+        println!("Starting `{}` in dev mode\n", local_node.binary.display());
+        if verbose {
+            local_node.group.spawn("node", &mut command)?;
+        } else {
+            local_node.group.spawn_quiet("node", &mut command)?;
+        }
+
+        let started = Instant::now();
+        while !rpc_ready(rpc_port) {
+            local_node.group.ensure_running()?;
+            if is_interrupted() {
+                return Ok(None);
+            }
+            if started.elapsed() > STARTUP_TIMEOUT {
+                anyhow::bail!("the node didn't open its RPC port {} in time", rpc_port);
+            }
+            thread::sleep(Duration::from_millis(200));
+        }
+
+        Ok(Some(local_node))
+    }
+}
+
+impl Drop for LocalContractsNode {
+    fn drop(&mut self) {
+        self.group.shutdown();
+        fs::remove_dir_all(&self.base_path).ok();
+    }
+}
+
+/// Starts a local dev node on a temporary base path, until Ctrl-C is pressed.
+pub fn contracts_node(config: &Config) -> SubstrateResult<()> {
+    let Some(mut node) = LocalContractsNode::start(config, true)? else {
+        return Ok(());
+    };
+
+    println!(
+        "\nNode ready at {}, deploy and call will use it. Press Ctrl-C to stop it.\n",
+        node.url
+    );
+    node.group.wait()
+}
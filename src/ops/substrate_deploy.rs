@@ -1,13 +1,445 @@
-use anyhow::Ok;
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::Path,
+    process::{Command, Stdio},
+};
 
-use crate::util::SubstrateResult;
+use anyhow::Context as _;
+use cargo_util::ProcessError;
+use serde_derive::Deserialize;
+use serde_json::Value;
+use toml_edit::{value, Array, ArrayOfTables, Document, Item, Table};
 
-// TODO:
-// - Ask the user for their desired chain to deploy to and include it in the URL as the `rpc` arg
-pub fn deploy() -> SubstrateResult<()> {
-    let url = "https://contracts-ui.substrate.io/";
+use crate::{
+    core::{contract_bundle::ContractBundle, manifest::Manifest},
+    ops::{
+        substrate_build::{self, find_contract_bundle, ContractBuildOptions},
+        substrate_contracts_node::ensure_node_reachable,
+    },
+    util::{config::ContractMember, secret_file::SecretFile, Config, SubstrateResult},
+};
+
+pub const DEFAULT_RPC_URL: &str = "ws://127.0.0.1:9944";
+
+/// File, relative to the project root, recording the deployed contracts of each network.
+pub const DEPLOYMENTS_FILE: &str = "deployments.toml";
+
+/// Dev accounts endowed on development chains, usable as signers without a secret.
+pub const DEV_ACCOUNTS: [&str; 6] = ["//Alice", "//Bob", "//Charlie", "//Dave", "//Eve", "//Ferdie"];
+
+const CONTRACTS_UI_URL: &str = "https://contracts-ui.substrate.io/";
+
+/// A contract deployment (or a code upload, without an address) recorded in `deployments.toml`.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct Deployment {
+    pub name: String,
+    pub address: Option<String>,
+    pub code_hash: String,
+    pub constructor: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// The dev account that signed the deployment, custom signers aren't recorded
+    pub deployer: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct NetworkDeployments {
+    pub url: String,
+    #[serde(default)]
+    pub contracts: Vec<Deployment>,
+}
+
+pub struct DeployOptions {
+    pub contract: ContractMember,
+    pub url: String,
+    pub constructor: String,
+    pub args: Vec<String>,
+    /// Secret URI of the signer, e.g. `//Alice`
+    pub suri: String,
+}
+
+pub struct UploadOptions {
+    pub contract: ContractMember,
+    pub url: String,
+    pub suri: String,
+}
+
+/// The network a deployment is recorded under: `local` for local nodes, the RPC host otherwise.
+pub fn network_name(url: &str) -> String {
+    let host = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .split(['/', ':'])
+        .next()
+        .unwrap_or_default();
+
+    match host {
+        "" | "localhost" | "127.0.0.1" | "0.0.0.0" => "local".to_string(),
+        host => host.to_string(),
+    }
+}
+
+/// Reads `deployments.toml`, keyed by network name.
+pub fn load_deployments(config: &Config) -> SubstrateResult<BTreeMap<String, NetworkDeployments>> {
+    let path = config.cwd().join(DEPLOYMENTS_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let content = fs::read_to_string(&path)?;
+    toml_edit::de::from_str(&content).with_context(|| format!("invalid `{}`", DEPLOYMENTS_FILE))
+}
+
+/// The most recent instantiation of the contract named `name` on the node at `url`.
+pub fn last_deployment(config: &Config, url: &str, name: &str) -> SubstrateResult<Option<Deployment>> {
+    let mut deployments = load_deployments(config)?;
+    Ok(deployments
+        .remove(&network_name(url))
+        .and_then(|network| {
+            network
+                .contracts
+                .into_iter()
+                .rev()
+                .find(|deployment| deployment.name == name && deployment.address.is_some())
+        }))
+}
+
+fn add_deployment(document: &mut Document, url: &str, deployment: &Deployment) -> SubstrateResult<()> {
+    let name = network_name(url);
+    let network = document
+        .entry(&name)
+        .or_insert(toml_edit::table())
+        .as_table_mut()
+        .with_context(|| format!("`{}` in `{}` should be a `[{}]` table", name, DEPLOYMENTS_FILE, name))?;
+    network.insert("url", value(url));
+
+    let mut entry = Table::new();
+    entry.insert("name", value(&deployment.name));
+    if let Some(address) = &deployment.address {
+        entry.insert("address", value(address));
+    }
+    entry.insert("code_hash", value(&deployment.code_hash));
+    if let Some(constructor) = &deployment.constructor {
+        entry.insert("constructor", value(constructor));
+        entry.insert("args", value(deployment.args.iter().collect::<Array>()));
+    }
+    if let Some(deployer) = &deployment.deployer {
+        entry.insert("deployer", value(deployer));
+    }
+
+    match network
+        .entry("contracts")
+        .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+    {
+        Item::ArrayOfTables(contracts) => contracts.push(entry),
+        _ => anyhow::bail!(
+            "`{}.contracts` in `{}` should be an array of `[[{}.contracts]]` tables",
+            name,
+            DEPLOYMENTS_FILE,
+            name
+        ),
+    }
+
+    Ok(())
+}
+
+fn record_deployment(config: &Config, url: &str, deployment: &Deployment) -> SubstrateResult<()> {
+    let mut manifest = Manifest::new(config.cwd().join(DEPLOYMENTS_FILE));
+    let mut document = manifest
+        .read_or_new_document()
+        .with_context(|| format!("couldn't record the deployment in `{}`", DEPLOYMENTS_FILE))?;
+    add_deployment(&mut document, url, deployment)?;
+    manifest.write_document(document)?;
+
+    println!(
+        "Deployment recorded in `{}` under `[{}]`",
+        DEPLOYMENTS_FILE,
+        network_name(url)
+    );
+    Ok(())
+}
+
+/// The contract's bundle, built first if cargo-contract hasn't produced one yet.
+pub fn contract_bundle(config: &Config, contract: &ContractMember) -> SubstrateResult<ContractBundle> {
+    let manifest_path = config.cwd().join(&contract.path).join("Cargo.toml");
+    if let Ok(path) = find_contract_bundle(&manifest_path, None) {
+        return ContractBundle::read(path);
+    }
+
+    println!("No bundle found for `{}`, building the contract first\n", contract.name);
+    let opts = ContractBuildOptions {
+        release: false,
+        verifiable: false,
+        manifest_path: Some(manifest_path),
+        output_json: false,
+        target_dir: None,
+    };
+    let bundle = substrate_build::build(&opts, config)?;
+    println!();
+    Ok(bundle)
+}
+
+/// Runs a cargo-contract command with `--output-json` and parses its result.
+pub(crate) fn cargo_contract_json(command: &mut Command, description: &str) -> SubstrateResult<Value> {
+    let output = command
+        .arg("--output-json")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .output()?;
+    if !output.status.success() {
+        return Err(ProcessError::new(
+            &format!("`cargo contract {}` failed", description),
+            Some(output.status),
+            Some(&output),
+        )
+        .into());
+    }
+
+    serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("unexpected JSON output from `cargo contract {}`", description))
+}
+
+/// The signer passed to cargo-contract's `--suri`.
+///
+/// Dev accounts are passed as is, other secret URIs through a [`SecretFile`] so that they don't show
+/// on cargo-contract's command line.
+pub(crate) struct Signer {
+    uri: String,
+    _secret_file: Option<SecretFile>,
+}
+
+impl Signer {
+    pub(crate) fn new(suri: &str) -> SubstrateResult<Self> {
+        if DEV_ACCOUNTS.contains(&suri) {
+            return Ok(Self {
+                uri: suri.to_string(),
+                _secret_file: None,
+            });
+        }
+
+        let path = env::temp_dir().join(format!("substrate-manager-{}.suri", std::process::id()));
+        let secret_file = SecretFile::new(path, suri)?;
+        Ok(Self {
+            uri: secret_file.uri().to_string(),
+            _secret_file: Some(secret_file),
+        })
+    }
+
+    pub(crate) fn uri(&self) -> &str {
+        &self.uri
+    }
+}
+
+pub(crate) fn cargo_contract(config: &Config, subcommand: &str, url: &str, suri: &str, bundle: &Path) -> Command {
+    let mut command = Command::new("cargo-contract");
+    command
+        .current_dir(config.cwd())
+        .args(["contract", subcommand, "--url", url, "--suri", suri])
+        .arg(bundle);
+    command
+}
+
+/// Weights and deposits are either numbers or, for large values, strings.
+fn json_u128(value: &Value) -> Option<u128> {
+    match value {
+        // Exact with `arbitrary_precision`, including the values above `u64::MAX`
+        Value::Number(number) => number.to_string().parse().ok(),
+        Value::String(string) => string.replace(',', "").parse().ok(),
+        _ => None,
+    }
+}
+
+/// Gas and storage deposit limits, as estimated by a cargo-contract dry run.
+pub(crate) struct Limits {
+    ref_time: Option<u128>,
+    proof_size: Option<u128>,
+    storage_deposit: Option<u128>,
+}
+
+impl Limits {
+    pub(crate) fn from_dry_run(dry_run: &Value) -> Self {
+        let gas = &dry_run["gas_required"];
+        Self {
+            ref_time: json_u128(&gas["ref_time"]),
+            proof_size: json_u128(&gas["proof_size"]),
+            storage_deposit: json_u128(&dry_run["storage_deposit"]["Charge"]),
+        }
+    }
+
+    pub(crate) fn print(&self) {
+        if let (Some(ref_time), Some(proof_size)) = (self.ref_time, self.proof_size) {
+            println!("  gas              ref_time {}, proof_size {}", ref_time, proof_size);
+        }
+        println!("  storage deposit  {}", self.storage_deposit.unwrap_or(0));
+        println!();
+    }
+
+    pub(crate) fn apply(&self, command: &mut Command) {
+        if let (Some(ref_time), Some(proof_size)) = (self.ref_time, self.proof_size) {
+            command
+                .args(["--gas", &ref_time.to_string()])
+                .args(["--proof-size", &proof_size.to_string()]);
+        }
+        if let Some(storage_deposit) = self.storage_deposit {
+            command.args(["--storage-deposit-limit", &storage_deposit.to_string()]);
+        }
+    }
+}
+
+fn deployer(suri: &str) -> Option<String> {
+    DEV_ACCOUNTS.contains(&suri).then(|| suri.to_string())
+}
+
+/// Instantiates the contract on the node at `opts.url`, uploading its code if needed.
+pub fn deploy(opts: &DeployOptions, config: &Config) -> SubstrateResult<()> {
+    ensure_node_reachable(&opts.url)?;
+    let bundle = contract_bundle(config, &opts.contract)?;
+    let constructor = bundle
+        .constructors()
+        .into_iter()
+        .find(|constructor| constructor.label == opts.constructor)
+        .with_context(|| format!("the contract has no `{}` constructor", opts.constructor))?;
+    if constructor.args.len() != opts.args.len() {
+        anyhow::bail!(
+            "`{}` expects {} argument(s), got {}",
+            constructor.signature(),
+            constructor.args.len(),
+            opts.args.len()
+        );
+    }
+
+    let signer = Signer::new(&opts.suri)?;
+    let instantiate = || {
+        let mut command = cargo_contract(config, "instantiate", &opts.url, signer.uri(), bundle.path());
+        command.args(["--constructor", &opts.constructor]);
+        if !opts.args.is_empty() {
+            command.arg("--args").args(&opts.args);
+        }
+        command
+    };
+
+    // Dry run first to estimate the gas and storage deposit limits
+    let dry_run = cargo_contract_json(&mut instantiate(), "instantiate")?;
+    if dry_run["reverted"].as_bool().unwrap_or(false) {
+        anyhow::bail!("the constructor reverted during the dry run: {}", dry_run["data"]);
+    }
+    let limits = Limits::from_dry_run(&dry_run);
+    println!("Estimated limits for `{}`:", constructor.signature());
+    limits.print();
+
+    let mut command = instantiate();
+    command.args(["--execute", "--skip-confirm"]);
+    limits.apply(&mut command);
+    let result = cargo_contract_json(&mut command, "instantiate")?;
+
+    let address = result["contract"]
+        .as_str()
+        .with_context(|| "cargo-contract didn't report the contract's address")?;
+    let code_hash = result["code_hash"]
+        .as_str()
+        .or(bundle.code_hash())
+        .unwrap_or_default();
+    println!("Contract `{}` instantiated", bundle.name().unwrap_or("unknown"));
+    println!("  address    {}", address);
+    println!("  code hash  {}", code_hash);
+
+    let deployment = Deployment {
+        name: bundle.name().unwrap_or("unknown").to_string(),
+        address: Some(address.to_string()),
+        code_hash: code_hash.to_string(),
+        constructor: Some(opts.constructor.clone()),
+        args: opts.args.clone(),
+        deployer: deployer(&opts.suri),
+    };
+    record_deployment(config, &opts.url, &deployment)
+}
+
+/// Only uploads the contract's code, e.g. for contracts instantiated by other contracts.
+pub fn upload(opts: &UploadOptions, config: &Config) -> SubstrateResult<()> {
+    ensure_node_reachable(&opts.url)?;
+    let bundle = contract_bundle(config, &opts.contract)?;
+    let signer = Signer::new(&opts.suri)?;
+    let mut command = cargo_contract(config, "upload", &opts.url, signer.uri(), bundle.path());
+    command.arg("--execute");
+    let result = cargo_contract_json(&mut command, "upload")?;
+
+    let code_hash = result["code_hash"]
+        .as_str()
+        .or(bundle.code_hash())
+        .unwrap_or_default();
+    println!("Code of `{}` uploaded", bundle.name().unwrap_or("unknown"));
+    println!("  code hash  {}", code_hash);
+
+    let deployment = Deployment {
+        name: bundle.name().unwrap_or("unknown").to_string(),
+        address: None,
+        code_hash: code_hash.to_string(),
+        constructor: None,
+        args: Vec::new(),
+        deployer: deployer(&opts.suri),
+    };
+    record_deployment(config, &opts.url, &deployment)
+}
+
+/// Opens the Substrate Contracts UI, connected to the node at `url`.
+pub fn open_contracts_ui(url: &str) -> SubstrateResult<()> {
+    let url = format!("{}?rpc={}", CONTRACTS_UI_URL, url);
     println!("If your browser doesn't automatically open, please open the following URL in your browser:\n{}\n", url);
     open::that(url)?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_network_name() {
+        assert_eq!(network_name(DEFAULT_RPC_URL), "local");
+        assert_eq!(network_name("ws://localhost:9944"), "local");
+        assert_eq!(
+            network_name("wss://rococo-contracts-rpc.polkadot.io"),
+            "rococo-contracts-rpc.polkadot.io"
+        );
+    }
+
+    #[test]
+    fn test_json_u128() {
+        let dry_run = serde_json::from_str::<Value>(
+            r#"{"gas_required":{"ref_time":1234,"proof_size":"5,678"},"storage_deposit":{"Charge":340282366920938463463374607431768211455}}"#,
+        )
+        .unwrap();
+        let limits = Limits::from_dry_run(&dry_run);
+        assert_eq!(limits.ref_time, Some(1234));
+        assert_eq!(limits.proof_size, Some(5678));
+        assert_eq!(limits.storage_deposit, Some(u128::MAX));
+        assert_eq!(json_u128(&serde_json::json!(-1)), None);
+    }
+
+    #[test]
+    fn test_add_deployment() {
+        let deployment = Deployment {
+            name: "flipper".to_string(),
+            address: Some("5Fflipper".to_string()),
+            code_hash: "0x12ab".to_string(),
+            constructor: Some("new".to_string()),
+            args: vec!["true".to_string()],
+            deployer: Some("//Alice".to_string()),
+        };
+        let mut document = Document::new();
+        add_deployment(&mut document, DEFAULT_RPC_URL, &deployment).unwrap();
+        add_deployment(&mut document, "ws://localhost:9944", &deployment).unwrap();
+
+        let deployments =
+            toml_edit::de::from_str::<BTreeMap<String, NetworkDeployments>>(&document.to_string())
+                .unwrap();
+        assert_eq!(deployments.len(), 1);
+        assert_eq!(deployments["local"].url, "ws://localhost:9944");
+        assert_eq!(deployments["local"].contracts, [deployment.clone(), deployment.clone()]);
+
+        let mut document = "local = { url = \"ws://127.0.0.1:9944\" }\n".parse::<Document>().unwrap();
+        assert!(add_deployment(&mut document, DEFAULT_RPC_URL, &deployment).is_err());
+    }
+}
@@ -1,33 +1,468 @@
-use std::{process::Command, path::{Path, PathBuf}};
-
-use anyhow::Ok;
-
-use crate::util::SubstrateResult;
-
-pub fn clone_substrate_frontend_template(path: &Path) -> SubstrateResult<()> {
-    Command::new("git")
-        .args([
-            "clone",
-            "git@github.com:substrate-developer-hub/substrate-front-end-template.git",
-            path.to_str().unwrap()
-        ])
-        .status()?;
-    Command::new("yarn")
-        .current_dir(path)
-        .args(["install"])
-        .status()?;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use regex::{Captures, Regex};
+use strum::Display;
+
+use crate::{
+    core::Shell,
+    ops::{
+        substrate_contracts_node::{rpc_port, rpc_ready},
+        substrate_deploy::{network_name, DEFAULT_RPC_URL},
+        substrate_network::{load_network_config, DEFAULT_RPC_PORT},
+        substrate_new::{copy_template_dir, git_clone},
+        substrate_run::ensure_node_binary,
+    },
+    templates::{load_template_config, TemplateConfig},
+    util::{
+        config::ChainInfo,
+        process::{is_interrupted, ProcessGroup},
+        Config, SubstrateResult,
+    },
+};
+
+/// How long the node started for the frontend gets to open its RPC port.
+const NODE_STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Env files read by the frontend toolchains, the last one overriding the others.
+const ENV_FILES: [&str; 4] = [".env.example", ".env", ".env.development", ".env.local"];
+
+pub struct FrontendOptions {
+    /// RPC endpoint of the node the frontend connects to
+    pub rpc: String,
+    /// Start a dev node listening on `rpc` first, stopped when the frontend exits
+    pub start_node: bool,
+}
+
+#[derive(Debug, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum FrontendTemplate {
+    /// The React front-end template of the Substrate Developer Hub, using polkadot.js
+    SubstrateFrontEnd,
+    /// A starter using polkadot-api
+    Papi,
+    // Path to a template config, or a git remote
+    Custom(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum PackageManager {
+    Yarn,
+    Npm,
+    Pnpm,
+}
+
+impl PackageManager {
+    /// The package manager of the project at `path`, from its lockfile or its `packageManager` field,
+    /// npm otherwise.
+    pub fn detect(path: &Path) -> PackageManager {
+        let lockfiles = [
+            ("pnpm-lock.yaml", PackageManager::Pnpm),
+            ("yarn.lock", PackageManager::Yarn),
+            ("package-lock.json", PackageManager::Npm),
+        ];
+        if let Some((_, package_manager)) = lockfiles.iter().find(|(lockfile, _)| path.join(lockfile).exists()) {
+            return *package_manager;
+        }
+
+        // e.g. `"packageManager": "pnpm@8.6.0"`
+        let declared = fs::read_to_string(path.join("package.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|package| package["packageManager"].as_str().map(str::to_string));
+        match declared.as_deref().and_then(|declared| declared.split('@').next()) {
+            Some("pnpm") => PackageManager::Pnpm,
+            Some("yarn") => PackageManager::Yarn,
+            _ => PackageManager::Npm,
+        }
+    }
+
+    pub fn binary(self) -> SubstrateResult<PathBuf> {
+        which::which(self.to_string()).with_context(|| format!("`{}` not found, install it to run the frontend", self))
+    }
+
+    /// Runs `<package manager> <args>` in `path`, failing with `description` when it doesn't succeed.
+    pub fn run(self, path: &Path, args: &[&str], description: &str) -> SubstrateResult<()> {
+        let status = Command::new(self.binary()?)
+            .current_dir(path)
+            .args(args)
+            .status()
+            .with_context(|| format!("failed to run `{}`", self))?;
+        if !status.success() {
+            anyhow::bail!("`{} {}` failed to {}", self, args.join(" "), description);
+        }
+        Ok(())
+    }
+}
+
+/// The versions of the packages of the repository cloned at `clone_path`, by name.
+///
+/// Listed from git, as the sparse checkout only holds the template.
+fn workspace_versions(clone_path: &Path) -> SubstrateResult<HashMap<String, String>> {
+    let output = Command::new("git")
+        .current_dir(clone_path)
+        .args(["ls-tree", "-r", "--name-only", "HEAD"])
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!("failed to list the files of the template's repository");
+    }
+
+    let mut versions = HashMap::new();
+    let files = String::from_utf8_lossy(&output.stdout);
+    for file in files
+        .lines()
+        .filter(|file| (*file == "package.json" || file.ends_with("/package.json")) && !file.contains("node_modules/"))
+    {
+        let output = Command::new("git")
+            .current_dir(clone_path)
+            .args(["show", &format!("HEAD:{}", file)])
+            .output()?;
+        let Ok(package) = serde_json::from_slice::<serde_json::Value>(&output.stdout) else {
+            continue;
+        };
+        if let (Some(name), Some(version)) = (package["name"].as_str(), package["version"].as_str()) {
+            versions.insert(name.to_string(), version.to_string());
+        }
+    }
+
+    Ok(versions)
+}
+
+/// Replaces the `workspace:` dependencies of a template taken from a monorepo with the versions of
+/// those packages at the cloned commit, since the frontend is installed outside of that workspace.
+fn resolve_workspace_dependencies(clone_path: &Path, path: &Path) -> SubstrateResult<()> {
+    let manifest_path = path.join("package.json");
+    let manifest = fs::read_to_string(&manifest_path)?;
+    // e.g. `"polkadot-api": "workspace:*"`
+    let dependency = Regex::new(r#""([^"]+)"(\s*:\s*)"workspace:([^"]*)""#)?;
+    if !dependency.is_match(&manifest) {
+        return Ok(());
+    }
+
+    let versions = workspace_versions(clone_path)?;
+    let mut missing = Vec::new();
+    let manifest = dependency.replace_all(&manifest, |captures: &Captures| {
+        let (name, separator, range) = (&captures[1], &captures[2], &captures[3]);
+        let Some(version) = versions.get(name) else {
+            missing.push(name.to_string());
+            return captures[0].to_string();
+        };
+        let range = match range {
+            "*" | "" => version.clone(),
+            "^" | "~" => format!("{}{}", range, version),
+            range => range.to_string(),
+        };
+        format!(r#""{}"{}"{}""#, name, separator, range)
+    });
+    if !missing.is_empty() {
+        anyhow::bail!(
+            "the template depends on {} from its repository's workspace, which couldn't be found",
+            missing.join(", ")
+        );
+    }
+
+    fs::write(manifest_path, manifest.as_ref())?;
     Ok(())
 }
 
-/*
- * TODO:
- * Error handling
-*/
-pub fn frontend() -> SubstrateResult<()> {
-    Command::new("yarn")
-        .current_dir("frontend")
-        .args(["start"])
-        .status()?;
+/// Generates the frontend at `path` from a template, then installs its dependencies.
+///
+/// Custom templates are resolved relative to `cwd`, as a template config file or else a git remote.
+pub fn generate_frontend(template: &FrontendTemplate, path: &Path, cwd: &Path) -> SubstrateResult<()> {
+    let template_config = match template {
+        FrontendTemplate::Custom(source) if cwd.join(source).is_file() => {
+            load_template_config(&cwd.join(source).to_string_lossy())?
+        }
+        FrontendTemplate::Custom(source) => TemplateConfig {
+            remote: source.clone(),
+            branch: String::new(),
+            template_path: String::new(),
+        },
+        template => load_template_config(&template.to_string())?,
+    };
+    if path.exists() {
+        anyhow::bail!("`{}` already exists", path.display());
+    }
+
+    let parent = path.parent().unwrap_or(Path::new(""));
+    fs::create_dir_all(parent)?;
+    let clone_path = parent.join(format!(
+        ".{}-template",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    if clone_path.exists() {
+        fs::remove_dir_all(&clone_path)?;
+    }
+
+    let result = (|| {
+        git_clone(&template_config, &clone_path)?;
+        if !template_config.template_path.is_empty() {
+            let status = Command::new("git")
+                .current_dir(&clone_path)
+                .args(["sparse-checkout", "set", &template_config.template_path])
+                .status()?;
+            if !status.success() {
+                anyhow::bail!("failed to check out `{}`", template_config.template_path);
+            }
+        }
+
+        let template_path = clone_path.join(&template_config.template_path);
+        if !template_path.join("package.json").exists() {
+            anyhow::bail!(
+                "no frontend found at `{}` in `{}`",
+                template_config.template_path,
+                template_config.remote
+            );
+        }
+        copy_template_dir(&template_path, path)?;
+        resolve_workspace_dependencies(&clone_path, path)
+    })();
+    fs::remove_dir_all(&clone_path).ok();
+    result?;
+
+    PackageManager::detect(path).run(path, &["install"], "install the frontend's dependencies")
+}
+
+/// The RPC endpoint of the chain's node: the first node of the `[network]` declared in
+/// `Substrate.toml`, or else the dev node's.
+pub fn default_rpc_url(config: &Config) -> SubstrateResult<String> {
+    let rpc_port = load_network_config(config.cwd())?
+        .and_then(|network| network.nodes.first().map(|node| node.rpc_port.unwrap_or(DEFAULT_RPC_PORT)));
+    Ok(match rpc_port {
+        Some(rpc_port) => format!("ws://127.0.0.1:{}", rpc_port),
+        None => DEFAULT_RPC_URL.to_string(),
+    })
+}
+
+/// Whether a node accepts connections at `url`, always assumed for remote endpoints.
+pub fn is_node_listening(url: &str) -> bool {
+    network_name(url) != "local" || rpc_ready(rpc_port(url))
+}
+
+/// The variable and value assigned by a line of an env file, e.g. `VAR` for `export VAR=value`.
+///
+/// `None` for comments, and for lines that don't assign a variable with a valid name.
+fn env_assignment(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return None;
+    }
+    let (name, value) = line.strip_prefix("export ").unwrap_or(line).split_once('=')?;
+    let name = name.trim();
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then_some((name, value.trim()))
+}
+
+/// The variables of an env file holding a node endpoint, i.e. whose value is a WebSocket URL.
+fn endpoint_variables(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(env_assignment)
+        .filter(|(_, value)| {
+            let value = value.trim_matches(['"', '\'']);
+            value.starts_with("ws://") || value.starts_with("wss://")
+        })
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// Sets each of `variables` to `url` in the env file `content`, appending the missing ones.
+fn set_env_variables(content: &str, variables: &[String], url: &str) -> String {
+    let mut lines = content.lines().map(str::to_string).collect::<Vec<_>>();
+    for variable in variables {
+        let line = format!("{}={}", variable, url);
+        let existing = lines
+            .iter_mut()
+            .find(|line| env_assignment(line).is_some_and(|(name, _)| name == variable));
+        match existing {
+            Some(existing) => *existing = line,
+            None => lines.push(line),
+        }
+    }
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
+/// Points the frontend at `url` through its `.env.local`, using the endpoint variables of its env
+/// files, or `REACT_APP_PROVIDER_SOCKET` for the Substrate front-end template.
+///
+/// Returns the variables that were set, empty if the frontend doesn't read its endpoint from one.
+fn configure_endpoint(path: &Path, url: &str) -> SubstrateResult<Vec<String>> {
+    let mut variables = Vec::new();
+    for env_file in ENV_FILES {
+        let content = fs::read_to_string(path.join(env_file)).unwrap_or_default();
+        for variable in endpoint_variables(&content) {
+            if !variables.contains(&variable) {
+                variables.push(variable);
+            }
+        }
+    }
+    // The template reads `PROVIDER_SOCKET` from `src/config/<env>.json`, overridden by `REACT_APP_` variables
+    let provider_socket = fs::read_to_string(path.join("src/config/development.json"))
+        .is_ok_and(|content| content.contains("\"PROVIDER_SOCKET\""));
+    if provider_socket && !variables.iter().any(|variable| variable == "REACT_APP_PROVIDER_SOCKET") {
+        variables.push("REACT_APP_PROVIDER_SOCKET".to_string());
+    }
+    if variables.is_empty() {
+        return Ok(variables);
+    }
+
+    let env_path = path.join(".env.local");
+    let content = fs::read_to_string(&env_path).unwrap_or_default();
+    fs::write(&env_path, set_env_variables(&content, &variables, url))?;
+    Ok(variables)
+}
+
+/// Starts a dev node listening on the local endpoint `url` and waits until its RPC port is open.
+///
+/// Returns `None` if Ctrl-C was pressed while waiting.
+fn start_node(config: &Config, chain: &ChainInfo, url: &str) -> SubstrateResult<Option<ProcessGroup>> {
+    if network_name(url) != "local" {
+        anyhow::bail!("can only start a node for a local endpoint, not `{}`", url);
+    }
+    let rpc_port = rpc_port(url);
+    let binary = ensure_node_binary(config, chain)?;
+
+    let mut command = Command::new(&binary);
+    command
+        .current_dir(config.cwd())
+        .arg("--dev")
+        .args(["--rpc-port", &rpc_port.to_string()])
+        .args(["--rpc-cors", "all"]);
+    let mut group = ProcessGroup::new("node".len());
+    println!("Starting `{}` in dev mode on {}\n", binary.display(), url);
+    group.spawn_quiet("node", &mut command)?;
+
+    let started = Instant::now();
+    while !rpc_ready(rpc_port) {
+        group.ensure_running()?;
+        if is_interrupted() {
+            return Ok(None);
+        }
+        if started.elapsed() > NODE_STARTUP_TIMEOUT {
+            anyhow::bail!("the node didn't open its RPC port {} in time", rpc_port);
+        }
+        thread::sleep(Duration::from_millis(200));
+    }
+
+    Ok(Some(group))
+}
+
+/// The script starting the frontend's dev server: `start`, or `dev` for Vite projects.
+fn start_script(path: &Path) -> &'static str {
+    let has_start = fs::read_to_string(path.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .is_some_and(|package| package["scripts"]["start"].is_string());
+    if has_start {
+        "start"
+    } else {
+        "dev"
+    }
+}
+
+/// Runs the frontend's dev server, connected to the node at `opts.rpc`.
+///
+/// The node started with `opts.start_node` is stopped once the dev server exits.
+pub fn frontend(opts: &FrontendOptions, config: &Config, chain: &ChainInfo) -> SubstrateResult<()> {
+    let mut shell = Shell::new();
+    let path = config.cwd().join(&chain.frontend_path);
+    if !path.join("package.json").exists() {
+        anyhow::bail!("no frontend found at `{}`", path.display());
+    }
+
+    let variables = configure_endpoint(&path, &opts.rpc)?;
+    if variables.is_empty() {
+        shell.warn(format!(
+            "couldn't find where the frontend reads its node endpoint from, make sure it connects to {}",
+            opts.rpc
+        ))?;
+    } else {
+        shell.status(
+            "Configured",
+            format!("{} to {} in `{}`", variables.join(", "), opts.rpc, path.join(".env.local").display()),
+        )?;
+    }
+
+    let _node = if opts.start_node {
+        match start_node(config, chain, &opts.rpc)? {
+            Some(node) => Some(node),
+            None => return Ok(()),
+        }
+    } else {
+        None
+    };
+
+    let mut command = Command::new(PackageManager::detect(&path).binary()?);
+    command.current_dir(&path).args(["run", start_script(&path)]);
+    // Also passed through the environment, which takes precedence over the env files
+    for variable in &variables {
+        command.env(variable, &opts.rpc);
+    }
+    let status = command.status()?;
+    // Stopped with Ctrl-C along with the node
+    if !status.success() && !is_interrupted() {
+        anyhow::bail!("the frontend exited with {}", status);
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_detect_package_manager() {
+        let path = std::env::temp_dir().join("substrate-manager-test-package-manager");
+        fs::remove_dir_all(&path).ok();
+        fs::create_dir_all(&path).unwrap();
+
+        assert_eq!(PackageManager::detect(&path), PackageManager::Npm);
+        fs::write(path.join("package.json"), r#"{ "packageManager": "pnpm@8.6.0" }"#).unwrap();
+        assert_eq!(PackageManager::detect(&path), PackageManager::Pnpm);
+        fs::write(path.join("yarn.lock"), "").unwrap();
+        assert_eq!(PackageManager::detect(&path), PackageManager::Yarn);
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_set_endpoint_variables() {
+        let content = "# Node to connect to\n\
+            # REACT_APP_PROVIDER_SOCKET=wss://rpc.polkadot.io\n\
+            VITE_WS_PROVIDER=\"wss://rpc.polkadot.io\"\n\
+            export VITE_RELAY_PROVIDER='ws://127.0.0.1:9944'\n\
+            VITE-INVALID=wss://rpc.polkadot.io\n\
+            VITE_APP_NAME=dapp\n";
+        let variables = endpoint_variables(content);
+        assert_eq!(variables, ["VITE_WS_PROVIDER", "VITE_RELAY_PROVIDER"]);
+
+        let variables = [
+            variables[0].clone(),
+            variables[1].clone(),
+            "REACT_APP_PROVIDER_SOCKET".to_string(),
+        ];
+        assert_eq!(
+            set_env_variables(content, &variables, "ws://127.0.0.1:9945"),
+            "# Node to connect to\n\
+            # REACT_APP_PROVIDER_SOCKET=wss://rpc.polkadot.io\n\
+            VITE_WS_PROVIDER=ws://127.0.0.1:9945\n\
+            VITE_RELAY_PROVIDER=ws://127.0.0.1:9945\n\
+            VITE-INVALID=wss://rpc.polkadot.io\n\
+            VITE_APP_NAME=dapp\n\
+            REACT_APP_PROVIDER_SOCKET=ws://127.0.0.1:9945\n"
+        );
+    }
+}
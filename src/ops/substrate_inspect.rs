@@ -0,0 +1,232 @@
+use std::fs;
+
+use anyhow::Context as _;
+use serde_derive::Serialize;
+
+use crate::{
+    core::{
+        manifest::Manifest,
+        runtime_source::{construct_runtime_pallets, runtime_configs},
+        Shell,
+    },
+    ops::substrate_bench::local_pallets,
+    util::{config::ChainInfo, Config, SubstrateResult},
+};
+
+pub struct InspectOptions {
+    /// Print the report as JSON instead of a table
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigType {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InspectedPallet {
+    pub name: String,
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    pub index: u8,
+    pub instance: Option<String>,
+    /// Empty when the pallet includes all its parts
+    pub parts: Vec<String>,
+    /// Associated types of its `Config` implementation, `None` when the runtime doesn't implement it
+    pub config: Option<Vec<ConfigType>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuntimeReport {
+    pub pallets: Vec<InspectedPallet>,
+    /// Pallet dependencies of the runtime's Cargo.toml missing from `construct_runtime!`
+    pub not_in_runtime: Vec<String>,
+    /// Pallets of `construct_runtime!` whose crate isn't a dependency of the runtime
+    pub not_in_manifest: Vec<String>,
+}
+
+/// The runtime's dependencies, with their names as used in its code, e.g. `pallet-balances` and `pallet_balances`.
+fn runtime_dependencies(config: &Config, chain: &ChainInfo) -> SubstrateResult<Vec<(String, String)>> {
+    let mut manifest = Manifest::new(config.cwd().join(&chain.runtime_path).join("Cargo.toml"));
+    let document = manifest.read_document()?;
+
+    Ok(document
+        .get("dependencies")
+        .and_then(|deps| deps.as_table_like())
+        .map(|deps| {
+            deps.iter()
+                .map(|(name, _)| (name.to_string(), name.replace('-', "_")))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// The pallets of the runtime with their `Config` types, and the pallets missing from either its
+/// Cargo.toml or its `construct_runtime!`.
+pub fn runtime_report(config: &Config, chain: &ChainInfo) -> SubstrateResult<RuntimeReport> {
+    let lib_path = config.cwd().join(&chain.runtime_path).join("src/lib.rs");
+    let content = fs::read_to_string(&lib_path)
+        .with_context(|| format!("couldn't read `{}`", lib_path.display()))?;
+    let mut configs = runtime_configs(&content)?;
+
+    let mut pallets = Vec::new();
+    for pallet in construct_runtime_pallets(&content)? {
+        // Instantiable pallets are matched on their instance when the crate is used several times
+        let candidates = configs
+            .iter()
+            .enumerate()
+            .filter(|(_, config)| config.crate_name == pallet.crate_name)
+            .map(|(position, _)| position)
+            .collect::<Vec<_>>();
+        let position = match candidates.as_slice() {
+            [position] => Some(*position),
+            _ => candidates
+                .iter()
+                .find(|position| configs[**position].instance == pallet.instance)
+                .or(candidates.first())
+                .copied(),
+        };
+        let config = position.map(|position| {
+            configs
+                .remove(position)
+                .types
+                .into_iter()
+                .map(|(name, value)| ConfigType { name, value })
+                .collect()
+        });
+
+        pallets.push(InspectedPallet {
+            name: pallet.name,
+            crate_name: pallet.crate_name,
+            index: pallet.index,
+            instance: pallet.instance,
+            parts: pallet.parts,
+            config,
+        });
+    }
+
+    // The dependencies that are pallets: `pallet-*` crates, other than runtime APIs, and the local pallets
+    let dependencies = runtime_dependencies(config, chain)?;
+    let local_pallets = local_pallets(config, chain)?;
+    let not_in_runtime = dependencies
+        .iter()
+        .filter(|(_, crate_name)| {
+            let is_frame_pallet =
+                crate_name.starts_with("pallet_") && !crate_name.ends_with("_api") && !crate_name.ends_with("_rpc");
+            is_frame_pallet || local_pallets.iter().any(|(pallet, _)| pallet == crate_name)
+        })
+        .filter(|(_, crate_name)| !pallets.iter().any(|pallet| &pallet.crate_name == crate_name))
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    // Pallets of the runtime crate itself, e.g. `mod my_pallet;`, aren't dependencies
+    let file = syn::parse_file(&content).with_context(|| "couldn't parse the runtime's lib.rs")?;
+    let is_local_module = |name: &str| {
+        ["crate", "self", "super"].contains(&name)
+            || file
+                .items
+                .iter()
+                .any(|item| matches!(item, syn::Item::Mod(module) if module.ident == name))
+    };
+    let not_in_manifest = pallets
+        .iter()
+        .filter(|pallet| {
+            !dependencies.iter().any(|(_, crate_name)| crate_name == &pallet.crate_name)
+                && !is_local_module(&pallet.crate_name)
+        })
+        .map(|pallet| pallet.name.clone())
+        .collect();
+
+    Ok(RuntimeReport {
+        pallets,
+        not_in_runtime,
+        not_in_manifest,
+    })
+}
+
+impl RuntimeReport {
+    /// Prints a table of the pallets, each followed by its `Config` types, then the mismatches.
+    pub fn print(&self, shell: &mut Shell) -> SubstrateResult<()> {
+        let name_width = self
+            .pallets
+            .iter()
+            .map(|pallet| pallet.name.len())
+            .chain(["pallet".len()])
+            .max()
+            .unwrap_or_default();
+        let crate_width = self
+            .pallets
+            .iter()
+            .map(|pallet| pallet.crate_name.len() + pallet.instance.as_ref().map_or(0, |i| i.len() + 3))
+            .chain(["crate".len()])
+            .max()
+            .unwrap_or_default();
+
+        shell.status("Runtime", format!("{} pallets", self.pallets.len()))?;
+        let out = shell.out();
+        writeln!(
+            out,
+            "{:>5}  {:<name_width$}  {:<crate_width$}  parts",
+            "index",
+            "pallet",
+            "crate",
+            name_width = name_width,
+            crate_width = crate_width
+        )?;
+        for pallet in &self.pallets {
+            let crate_name = match &pallet.instance {
+                Some(instance) => format!("{}::<{}>", pallet.crate_name, instance),
+                None => pallet.crate_name.clone(),
+            };
+            let parts = if pallet.parts.is_empty() {
+                "all".to_string()
+            } else {
+                pallet.parts.join(", ")
+            };
+            writeln!(
+                out,
+                "{:>5}  {:<name_width$}  {:<crate_width$}  {}",
+                pallet.index,
+                pallet.name,
+                crate_name,
+                parts,
+                name_width = name_width,
+                crate_width = crate_width
+            )?;
+            for ty in pallet.config.iter().flatten() {
+                writeln!(out, "{:>7}type {} = {}", "", ty.name, ty.value)?;
+            }
+        }
+
+        for pallet in self.pallets.iter().filter(|pallet| pallet.config.is_none()) {
+            shell.warn(format!("no `Config` implementation found for `{}`", pallet.name))?;
+        }
+        for dependency in &self.not_in_runtime {
+            shell.warn(format!(
+                "`{}` is a dependency of the runtime but isn't in its `construct_runtime!`",
+                dependency
+            ))?;
+        }
+        for pallet in &self.not_in_manifest {
+            shell.warn(format!(
+                "`{}` is in `construct_runtime!` but its crate isn't a dependency of the runtime",
+                pallet
+            ))?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints what the runtime contains: its pallets, their indices, parts and `Config` types.
+pub fn inspect(opts: &InspectOptions, config: &Config, chain: &ChainInfo) -> SubstrateResult<RuntimeReport> {
+    let report = runtime_report(config, chain)?;
+    let mut shell = Shell::new();
+    if opts.json {
+        shell.print_json(&report)?;
+    } else {
+        report.print(&mut shell)?;
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,305 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::Context as _;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    ops::{
+        substrate_network::{load_network_config, write_default_network_config, SessionKeyConfig},
+        substrate_run::ensure_node_binary,
+        substrate_testnet::is_parachain_node,
+    },
+    util::{config::ChainInfo, secret_file::SecretFile, Config, SubstrateResult},
+};
+
+const SCHEMES: [&str; 3] = ["sr25519", "ed25519", "ecdsa"];
+
+pub struct KeysOptions {
+    /// Print the secret phrase of each validator
+    pub show_secrets: bool,
+}
+
+/// A session key of a validator, as inserted in its keystore.
+pub struct SessionKey {
+    pub name: String,
+    pub key_type: String,
+    pub scheme: String,
+    pub public_key: String,
+    pub ss58_address: String,
+}
+
+pub struct ValidatorKeys {
+    pub node: String,
+    /// SS58 address of the validator's sr25519 account
+    pub account: String,
+    pub session_keys: Vec<SessionKey>,
+    secret_phrase: String,
+}
+
+impl ValidatorKeys {
+    /// The session keys concatenated, as returned by `author_rotateKeys` and expected by `session.setKeys`.
+    pub fn session_keys_bundle(&self) -> String {
+        let bundle = self
+            .session_keys
+            .iter()
+            .map(|key| key.public_key.trim_start_matches("0x"))
+            .collect::<String>();
+        format!("0x{}", bundle)
+    }
+
+    /// The `(account, validator, keys)` entry of the session pallet genesis config.
+    pub fn genesis_entry(&self) -> Value {
+        let keys = self
+            .session_keys
+            .iter()
+            .map(|key| (key.name.clone(), Value::String(key.ss58_address.clone())))
+            .collect::<Map<_, _>>();
+        json!([self.account, self.account, keys])
+    }
+}
+
+/// Session keys of the node templates: aura for parachains, aura and grandpa for solochains.
+fn default_session_keys(parachain: bool) -> Vec<SessionKeyConfig> {
+    let mut keys = vec![SessionKeyConfig {
+        name: "aura".to_string(),
+        key_type: "aura".to_string(),
+        scheme: "sr25519".to_string(),
+    }];
+    if !parachain {
+        keys.push(SessionKeyConfig {
+            name: "grandpa".to_string(),
+            key_type: "gran".to_string(),
+            scheme: "ed25519".to_string(),
+        });
+    }
+    keys
+}
+
+/// Runs one of the node's `key` subcommands that supports `--output-type json`.
+fn key_command_json(binary: &Path, args: &[&str]) -> SubstrateResult<Value> {
+    let output = Command::new(binary)
+        .arg("key")
+        .args(args)
+        .args(["--output-type", "json"])
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "`key {}` failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("unexpected output from `key {}`", args[0]))
+}
+
+fn json_field(value: &Value, field: &str) -> SubstrateResult<String> {
+    value[field]
+        .as_str()
+        .map(|field| field.to_string())
+        .with_context(|| format!("`{}` missing from the `key` command output", field))
+}
+
+/// Keystore files are named after the hex encoded key type followed by the public key.
+fn key_type_hex(key_type: &str) -> String {
+    key_type.bytes().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Finds the keystore files holding keys of `key_type` under a node's base path.
+fn keystore_files(node_path: &Path, key_type: &str) -> SubstrateResult<Vec<PathBuf>> {
+    let pattern = format!(
+        "{}/chains/*/keystore/{}*",
+        glob::Pattern::escape(&node_path.to_string_lossy()),
+        key_type_hex(key_type)
+    );
+
+    Ok(glob::glob(&pattern)?.flatten().collect())
+}
+
+/// Reads back the secret phrase of a key already inserted in the keystore.
+fn existing_secret_phrase(node_path: &Path, key_types: &[SessionKeyConfig]) -> SubstrateResult<Option<String>> {
+    for key in key_types {
+        if let Some(file) = keystore_files(node_path, &key.key_type)?.first() {
+            // The keystore stores the suri as a JSON string
+            let content = fs::read_to_string(file)?;
+            let phrase = serde_json::from_str::<String>(&content)
+                .with_context(|| format!("unexpected keystore file `{}`", file.display()))?;
+            return Ok(Some(phrase));
+        }
+    }
+
+    Ok(None)
+}
+
+fn validator_keys(
+    binary: &Path,
+    node: &str,
+    node_path: &Path,
+    chain: &str,
+    key_types: &[SessionKeyConfig],
+) -> SubstrateResult<ValidatorKeys> {
+    let secret_phrase = match existing_secret_phrase(node_path, key_types)? {
+        Some(phrase) => phrase,
+        None => {
+            let generated = key_command_json(binary, &["generate", "--scheme", "sr25519"])?;
+            json_field(&generated, "secretPhrase")?
+        }
+    };
+
+    let secret_file = SecretFile::new(node_path.join(".suri"), &secret_phrase)?;
+    let account = key_command_json(binary, &["inspect", "--scheme", "sr25519", secret_file.uri()])?;
+    let account = json_field(&account, "ss58Address")?;
+
+    let mut session_keys = Vec::new();
+    for key in key_types {
+        let inspected = key_command_json(binary, &["inspect", "--scheme", &key.scheme, secret_file.uri()])?;
+
+        if keystore_files(node_path, &key.key_type)?.is_empty() {
+            let output = Command::new(binary)
+                .args(["key", "insert", "--base-path"])
+                .arg(node_path)
+                .args(["--chain", chain])
+                .args(["--scheme", &key.scheme])
+                .args(["--key-type", &key.key_type])
+                .args(["--suri", secret_file.uri()])
+                .output()?;
+            if !output.status.success() {
+                anyhow::bail!(
+                    "failed to insert the `{}` key of {}: {}",
+                    key.key_type,
+                    node,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+        }
+
+        session_keys.push(SessionKey {
+            name: key.name.clone(),
+            key_type: key.key_type.clone(),
+            scheme: key.scheme.clone(),
+            public_key: json_field(&inspected, "publicKey")?,
+            ss58_address: json_field(&inspected, "ss58Address")?,
+        });
+    }
+
+    Ok(ValidatorKeys {
+        node: node.to_string(),
+        account,
+        session_keys,
+        secret_phrase,
+    })
+}
+
+pub fn keys(opts: &KeysOptions, config: &Config, chain: &ChainInfo) -> SubstrateResult<Vec<ValidatorKeys>> {
+    let network = match load_network_config(config.cwd())? {
+        Some(network) => network,
+        None => {
+            println!("No network topology found, declaring a default one (alice, bob) in Substrate.toml");
+            write_default_network_config(config.cwd())?
+        }
+    };
+
+    let key_types = match network.session_keys {
+        Some(key_types) => key_types,
+        None => default_session_keys(is_parachain_node(config, chain)?),
+    };
+    for key in &key_types {
+        if key.key_type.len() != 4 {
+            anyhow::bail!("key type `{}` should be 4 characters long", key.key_type);
+        }
+        if !SCHEMES.contains(&key.scheme.as_str()) {
+            anyhow::bail!(
+                "unknown scheme `{}` for key `{}`, expected one of {}",
+                key.scheme,
+                key.name,
+                SCHEMES.join(", ")
+            );
+        }
+    }
+
+    let binary = match &network.binary {
+        Some(binary) => config.cwd().join(binary),
+        None => ensure_node_binary(config, chain)?,
+    };
+
+    let base_path = config.cwd().join(&network.base_path);
+    let mut validators = Vec::new();
+    for node in network.nodes.iter().filter(|node| node.validator) {
+        let node_path = base_path.join(&node.name);
+        fs::create_dir_all(&node_path)?;
+        validators.push(validator_keys(
+            &binary,
+            &node.name,
+            &node_path,
+            &network.chain,
+            &key_types,
+        )?);
+    }
+    if validators.is_empty() {
+        anyhow::bail!("no validators declared under `[[network.nodes]]` in Substrate.toml");
+    }
+
+    for validator in &validators {
+        println!("{}", validator.node);
+        println!("  account        {}", validator.account);
+        for key in &validator.session_keys {
+            println!(
+                "  {:<14} {} ({}, {})",
+                key.name, key.ss58_address, key.scheme, key.public_key
+            );
+        }
+        println!("  session keys   {}", validator.session_keys_bundle());
+        if opts.show_secrets {
+            println!("  secret phrase  {}", validator.secret_phrase);
+        }
+        println!();
+    }
+
+    let genesis = validators
+        .iter()
+        .map(ValidatorKeys::genesis_entry)
+        .collect::<Vec<_>>();
+    println!(
+        "Keys inserted in each validator's keystore under `{}`.",
+        base_path.display()
+    );
+    println!(
+        "Session pallet genesis (see `spec set`):\n  session_keys='{}'",
+        Value::Array(genesis)
+    );
+
+    Ok(validators)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_existing_secret_phrase() {
+        let node_path = std::env::temp_dir().join("substrate-manager-test-keystore");
+        let keystore = node_path.join("chains/local_testnet/keystore");
+        fs::create_dir_all(&keystore).unwrap();
+        fs::write(
+            keystore.join(format!("{}d43593c7", key_type_hex("gran"))),
+            "\"bottom drive obey lake curtain smoke basket hold race lonely fit walk\"",
+        )
+        .unwrap();
+
+        let phrase = existing_secret_phrase(&node_path, &default_session_keys(false)).unwrap();
+        let missing = existing_secret_phrase(&node_path, &default_session_keys(true)).unwrap();
+        fs::remove_dir_all(&node_path).unwrap();
+
+        assert_eq!(key_type_hex("gran"), "6772616e");
+        assert_eq!(
+            phrase.as_deref(),
+            Some("bottom drive obey lake curtain smoke basket hold race lonely fit walk")
+        );
+        assert_eq!(missing, None);
+    }
+}
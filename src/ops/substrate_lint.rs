@@ -0,0 +1,191 @@
+use std::fs;
+
+use anyhow::Context as _;
+use toml_edit::{value, Item, Table};
+
+use crate::{
+    core::{
+        manifest::Manifest,
+        runtime_source::{construct_runtime_pallets, RuntimePallet},
+        Shell,
+    },
+    util::{config::ChainInfo, Config, SubstrateResult},
+};
+
+pub struct LintOptions {
+    /// Record the current pallet indices as the baseline instead of checking them against it
+    pub record: bool,
+}
+
+/// Reads the pallet indices recorded under `[runtime.pallet_indices]` in `Substrate.toml`.
+fn load_baseline(config: &Config) -> SubstrateResult<Option<Vec<(String, u8)>>> {
+    let manifest_path = config.cwd().join("Substrate.toml");
+    if !manifest_path.exists() {
+        return Ok(None);
+    }
+    let document = Manifest::new(manifest_path).read_document()?;
+    let Some(indices) = document
+        .get("runtime")
+        .and_then(|runtime| runtime.get("pallet_indices"))
+        .and_then(|indices| indices.as_table_like())
+    else {
+        return Ok(None);
+    };
+
+    indices
+        .iter()
+        .map(|(name, index)| {
+            let index = index
+                .as_integer()
+                .and_then(|index| u8::try_from(index).ok())
+                .with_context(|| format!("invalid index for `{}` in `[runtime.pallet_indices]`", name))?;
+            Ok((name.to_string(), index))
+        })
+        .collect::<SubstrateResult<_>>()
+        .map(Some)
+}
+
+/// Records the pallet indices under `[runtime.pallet_indices]` in `Substrate.toml`.
+fn record_baseline(config: &Config, pallets: &[RuntimePallet]) -> SubstrateResult<()> {
+    let mut manifest = Manifest::new(config.cwd().join("Substrate.toml"));
+    let mut document = manifest
+        .read_or_new_document()
+        .with_context(|| "couldn't record the pallet indices in `Substrate.toml`")?;
+
+    let mut indices = Table::new();
+    for pallet in pallets {
+        indices.insert(&pallet.name, value(i64::from(pallet.index)));
+    }
+    if !document.contains_key("runtime") {
+        let mut runtime = Table::new();
+        runtime.set_implicit(true);
+        document.insert("runtime", Item::Table(runtime));
+    }
+    document["runtime"]
+        .as_table_like_mut()
+        .with_context(|| "`runtime` in `Substrate.toml` isn't a table")?
+        .insert("pallet_indices", Item::Table(indices));
+
+    manifest.write_document(document)
+}
+
+/// Checks that every pallet has an explicit index and that none changed since the baseline.
+///
+/// Returns the problems found, each as a message.
+pub fn check_pallet_indices(pallets: &[RuntimePallet], baseline: Option<&[(String, u8)]>) -> Vec<String> {
+    let mut problems = pallets
+        .iter()
+        .filter(|pallet| !pallet.explicit_index)
+        .map(|pallet| {
+            format!(
+                "`{}` has no explicit index, declare it as `{}: {} = {}`",
+                pallet.name, pallet.name, pallet.crate_name, pallet.index
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for (name, index) in baseline.unwrap_or_default() {
+        match pallets.iter().find(|pallet| &pallet.name == name) {
+            Some(pallet) if pallet.index != *index => problems.push(format!(
+                "`{}` moved from index {} to {}, breaking the encoding of its calls, events and storage",
+                name, index, pallet.index
+            )),
+            Some(_) => {}
+            None => {
+                if let Some(pallet) = pallets.iter().find(|pallet| pallet.index == *index) {
+                    problems.push(format!(
+                        "`{}` reuses index {} of the removed `{}`, whose calls and storage may still be on-chain",
+                        pallet.name, index, name
+                    ));
+                }
+            }
+        }
+    }
+
+    problems
+}
+
+/// Lints the runtime's pallet indices, failing when any is implicit or changed since the recorded baseline.
+pub fn lint(opts: &LintOptions, config: &Config, chain: &ChainInfo) -> SubstrateResult<()> {
+    let mut shell = Shell::new();
+    let lib_path = config.cwd().join(&chain.runtime_path).join("src/lib.rs");
+    let content = fs::read_to_string(&lib_path)
+        .with_context(|| format!("couldn't read `{}`", lib_path.display()))?;
+    let pallets = construct_runtime_pallets(&content)?;
+
+    let baseline = if opts.record {
+        record_baseline(config, &pallets)?;
+        shell.status(
+            "Recorded",
+            format!("the indices of {} pallets in Substrate.toml", pallets.len()),
+        )?;
+        None
+    } else {
+        let baseline = load_baseline(config)?;
+        if baseline.is_none() {
+            shell.note("no pallet indices recorded yet, record them with `lint --record`")?;
+        }
+        baseline
+    };
+    if let Some(baseline) = &baseline {
+        for pallet in pallets
+            .iter()
+            .filter(|pallet| !baseline.iter().any(|(name, _)| name == &pallet.name))
+        {
+            shell.note(format!(
+                "`{}` isn't in the recorded indices, record them again once it's released",
+                pallet.name
+            ))?;
+        }
+    }
+
+    let problems = check_pallet_indices(&pallets, baseline.as_deref());
+    for problem in &problems {
+        shell.error(problem)?;
+    }
+    if !problems.is_empty() {
+        anyhow::bail!("the runtime's pallet indices have {} problem(s)", problems.len());
+    }
+    shell.status("Checked", format!("the indices of {} pallets", pallets.len()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check_pallet_indices() {
+        let pallet = |name: &str, index, explicit_index| RuntimePallet {
+            name: name.into(),
+            index,
+            explicit_index,
+            crate_name: format!("pallet_{}", name.to_lowercase()),
+            instance: None,
+            parts: Vec::new(),
+        };
+        let pallets = [
+            pallet("System", 0, true),
+            pallet("Balances", 1, false),
+            pallet("Sudo", 3, true),
+            pallet("Kitties", 4, true),
+        ];
+        let baseline = [
+            ("System".to_string(), 0),
+            ("Balances".to_string(), 1),
+            ("Sudo".to_string(), 2),
+            ("Template".to_string(), 4),
+        ];
+
+        assert_eq!(
+            check_pallet_indices(&pallets, Some(&baseline)),
+            [
+                "`Balances` has no explicit index, declare it as `Balances: pallet_balances = 1`",
+                "`Sudo` moved from index 2 to 3, breaking the encoding of its calls, events and storage",
+                "`Kitties` reuses index 4 of the removed `Template`, whose calls and storage may still be on-chain",
+            ]
+        );
+        assert_eq!(check_pallet_indices(&pallets[2..], None), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,377 @@
+use std::{
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::Context as _;
+use serde_derive::Deserialize;
+use toml_edit::{value, ArrayOfTables, Table};
+
+use crate::{
+    core::manifest::Manifest,
+    ops::{
+        substrate_run::ensure_node_binary,
+        substrate_testnet::{testnet, zombienet_config_path},
+    },
+    util::{config::ChainInfo, process::ProcessGroup, Config, SubstrateResult},
+};
+
+/// Dev accounts that the node CLI knows how to set up with a shortcut flag (`--alice`, ...).
+const WELL_KNOWN_NODES: [&str; 8] = [
+    "alice", "bob", "charlie", "dave", "eve", "ferdie", "one", "two",
+];
+
+pub(crate) const DEFAULT_P2P_PORT: u16 = 30333;
+pub(crate) const DEFAULT_RPC_PORT: u16 = 9944;
+
+/// Topology of the local network, declared under `[network]` in `Substrate.toml`.
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct NetworkConfig {
+    /// The chain-specification the nodes are started with
+    #[serde(default = "default_chain")]
+    pub chain: String,
+    /// Path to the node binary, defaults to the release build of the node
+    pub binary: Option<PathBuf>,
+    /// Directory under which each node gets its own base path
+    #[serde(default = "default_base_path")]
+    pub base_path: PathBuf,
+    #[serde(default)]
+    pub nodes: Vec<NodeConfig>,
+    /// Zombienet network file describing a relay chain and its parachains
+    pub zombienet: Option<PathBuf>,
+    /// Session keys of the runtime, generated for each validator by the `keys` command
+    pub session_keys: Option<Vec<SessionKeyConfig>>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct SessionKeyConfig {
+    /// Name of the key in the runtime's `SessionKeys`, e.g. `grandpa`
+    pub name: String,
+    /// Four character key type id, e.g. `gran`
+    pub key_type: String,
+    /// Signature scheme of the key: `sr25519`, `ed25519` or `ecdsa`
+    pub scheme: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct NodeConfig {
+    pub name: String,
+    pub p2p_port: Option<u16>,
+    pub rpc_port: Option<u16>,
+    #[serde(default = "default_validator")]
+    pub validator: bool,
+    /// Extra arguments passed verbatim to the node
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NetworkManifest {
+    network: Option<NetworkConfig>,
+}
+
+fn default_chain() -> String {
+    "local".to_string()
+}
+
+fn default_base_path() -> PathBuf {
+    PathBuf::from(".network")
+}
+
+fn default_validator() -> bool {
+    true
+}
+
+pub struct NetworkOptions {
+    /// Wipe each node's base path before starting it
+    pub purge: bool,
+}
+
+/// Reads the network topology from `Substrate.toml`, if one is declared.
+pub fn load_network_config(cwd: &Path) -> SubstrateResult<Option<NetworkConfig>> {
+    let manifest_path = cwd.join("Substrate.toml");
+    if !manifest_path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&manifest_path)?;
+    let manifest = toml_edit::de::from_str::<NetworkManifest>(&content)
+        .with_context(|| "invalid `[network]` section in Substrate.toml")?;
+
+    Ok(manifest.network)
+}
+
+/// Declares a two validator (alice and bob) topology in `Substrate.toml`.
+pub fn write_default_network_config(cwd: &Path) -> SubstrateResult<NetworkConfig> {
+    let mut manifest = Manifest::new(cwd.join("Substrate.toml"));
+    let mut document = manifest.read_or_new_document()?;
+
+    let mut nodes = ArrayOfTables::new();
+    for name in ["alice", "bob"] {
+        let mut node = Table::new();
+        node.insert("name", value(name));
+        nodes.push(node);
+    }
+
+    let mut network = Table::new();
+    network.insert("chain", value(default_chain()));
+    network.insert("nodes", toml_edit::Item::ArrayOfTables(nodes));
+    document.insert("network", toml_edit::Item::Table(network));
+
+    manifest.write_document(document)?;
+
+    Ok(load_network_config(cwd)?.expect("network config was just written"))
+}
+
+/// Generates the bootnode's network key (if missing) and returns its peer id.
+pub(crate) fn bootnode_peer_id(binary: &Path, node_key_file: &Path) -> SubstrateResult<String> {
+    if !node_key_file.exists() {
+        let output = Command::new(binary)
+            .args(["key", "generate-node-key", "--file"])
+            .arg(node_key_file)
+            .output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "failed to generate a node key: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+    }
+
+    let output = Command::new(binary)
+        .args(["key", "inspect-node-key", "--file"])
+        .arg(node_key_file)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "failed to inspect the node key: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Builds the command line shared by every node of a local network.
+///
+/// The first node of a network is the bootnode: it gets a persistent network key
+/// (`node-key` in its base path) and the other nodes are pointed at it through `bootnode`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn base_node_command(
+    binary: &Path,
+    name: &str,
+    chain: &OsStr,
+    base_path: &Path,
+    p2p_port: u16,
+    rpc_port: u16,
+    validator: bool,
+    bootnode: Option<&str>,
+) -> Command {
+    let mut command = Command::new(binary);
+    command
+        .arg("--chain")
+        .arg(chain)
+        .arg("--base-path")
+        .arg(base_path)
+        .arg("--port")
+        .arg(p2p_port.to_string())
+        .arg("--rpc-port")
+        .arg(rpc_port.to_string());
+
+    let lowercase_name = name.to_lowercase();
+    if WELL_KNOWN_NODES.contains(&lowercase_name.as_str()) {
+        // Sets the node name and inserts the dev account's session keys
+        command.arg(format!("--{}", lowercase_name));
+    } else {
+        command.args(["--name", name]);
+    }
+    if validator {
+        command.arg("--validator");
+    }
+    match bootnode {
+        Some(bootnode) => {
+            command.args(["--bootnodes", bootnode]);
+        }
+        None => {
+            command.arg("--node-key-file").arg(base_path.join("node-key"));
+        }
+    }
+
+    command
+}
+
+/// Formats the multiaddress of a node listening locally on `p2p_port`.
+pub(crate) fn local_multiaddr(p2p_port: u16, peer_id: &str) -> String {
+    format!("/ip4/127.0.0.1/tcp/{}/p2p/{}", p2p_port, peer_id)
+}
+
+pub fn network(opts: &NetworkOptions, config: &Config, chain: &ChainInfo) -> SubstrateResult<()> {
+    let network = load_network_config(config.cwd())?;
+
+    let zombienet_path = zombienet_config_path(config.cwd(), network.as_ref());
+    if zombienet_path.exists() {
+        let base_path = network
+            .map(|network| network.base_path)
+            .unwrap_or_else(default_base_path);
+        return testnet(opts, config, chain, &zombienet_path, &base_path);
+    }
+
+    let network = match network {
+        Some(network) => network,
+        None => {
+            println!("No network topology found, declaring a default one (alice, bob) in Substrate.toml");
+            write_default_network_config(config.cwd())?
+        }
+    };
+    if network.nodes.is_empty() {
+        anyhow::bail!("no nodes declared under `[[network.nodes]]` in Substrate.toml");
+    }
+
+    let binary = match &network.binary {
+        Some(binary) => config.cwd().join(binary),
+        None => ensure_node_binary(config, chain)?,
+    };
+    if !binary.exists() {
+        anyhow::bail!("node binary `{}` does not exist", binary.display());
+    }
+
+    let base_path = config.cwd().join(&network.base_path);
+    let node_paths = network
+        .nodes
+        .iter()
+        .map(|node| base_path.join(&node.name))
+        .collect::<Vec<_>>();
+    for node_path in &node_paths {
+        if opts.purge && node_path.exists() {
+            fs::remove_dir_all(node_path)?;
+        }
+        fs::create_dir_all(node_path)?;
+    }
+
+    let bootnode_config = &network.nodes[0];
+    let peer_id = bootnode_peer_id(&binary, &node_paths[0].join("node-key"))?;
+    let bootnode = local_multiaddr(
+        bootnode_config.p2p_port.unwrap_or(DEFAULT_P2P_PORT),
+        &peer_id,
+    );
+
+    let prefix_width = network.nodes.iter().map(|n| n.name.len()).max().unwrap_or(0);
+    let mut group = ProcessGroup::new(prefix_width);
+
+    println!(
+        "Starting {} nodes on chain `{}` (bootnode: {})\n",
+        network.nodes.len(),
+        network.chain,
+        bootnode
+    );
+    for (index, (node, node_path)) in network.nodes.iter().zip(&node_paths).enumerate() {
+        let index = index as u16;
+        let mut command = base_node_command(
+            &binary,
+            &node.name,
+            OsStr::new(&network.chain),
+            node_path,
+            node.p2p_port.unwrap_or(DEFAULT_P2P_PORT + index),
+            node.rpc_port.unwrap_or(DEFAULT_RPC_PORT + index),
+            node.validator,
+            (index > 0).then_some(bootnode.as_str()),
+        );
+        command.args(&node.args);
+        group.spawn(&node.name, &mut command)?;
+    }
+    println!("Press Ctrl-C to stop the network.\n");
+
+    group.wait()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(command: &Command) -> Vec<String> {
+        command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_network_config() {
+        let cwd = std::env::temp_dir().join("substrate-manager-test-network-config");
+        fs::remove_dir_all(&cwd).ok();
+        fs::create_dir_all(&cwd).unwrap();
+        assert_eq!(load_network_config(&cwd).unwrap(), None);
+
+        fs::write(cwd.join("Substrate.toml"), "type = \"chain\"\n").unwrap();
+        let network = write_default_network_config(&cwd).unwrap();
+        let names = network.nodes.iter().map(|node| node.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["alice", "bob"]);
+        assert_eq!(network.chain, "local");
+        assert_eq!(network.base_path, PathBuf::from(".network"));
+        assert!(network.nodes.iter().all(|node| node.validator && node.rpc_port.is_none()));
+        let content = fs::read_to_string(cwd.join("Substrate.toml")).unwrap();
+        assert!(content.starts_with("type = \"chain\"\n"));
+
+        fs::write(
+            cwd.join("Substrate.toml"),
+            "[network]\nchain = \"dev\"\n\n[[network.nodes]]\nname = \"alice\"\nrpc_port = 9945\nvalidator = false\nargs = [\"--pruning\", \"archive\"]\n",
+        )
+        .unwrap();
+        let network = load_network_config(&cwd).unwrap().unwrap();
+        assert_eq!(network.chain, "dev");
+        assert_eq!(network.nodes[0].rpc_port, Some(9945));
+        assert!(!network.nodes[0].validator);
+        assert_eq!(network.nodes[0].args, ["--pruning", "archive"]);
+
+        // An unparseable manifest is reported rather than overwritten
+        fs::write(cwd.join("Substrate.toml"), "type = ").unwrap();
+        assert!(write_default_network_config(&cwd).is_err());
+        assert_eq!(fs::read_to_string(cwd.join("Substrate.toml")).unwrap(), "type = ");
+
+        fs::remove_dir_all(&cwd).unwrap();
+    }
+
+    #[test]
+    fn test_base_node_command() {
+        let bootnode = local_multiaddr(30333, "12D3KooW");
+        assert_eq!(bootnode, "/ip4/127.0.0.1/tcp/30333/p2p/12D3KooW");
+
+        let command = base_node_command(
+            Path::new("node"),
+            "alice",
+            OsStr::new("local"),
+            Path::new(".network/alice"),
+            30333,
+            9944,
+            true,
+            None,
+        );
+        assert_eq!(
+            args(&command),
+            [
+                "--chain", "local", "--base-path", ".network/alice", "--port", "30333", "--rpc-port", "9944",
+                "--alice", "--validator", "--node-key-file", ".network/alice/node-key",
+            ]
+        );
+
+        let command = base_node_command(
+            Path::new("node"),
+            "Observer",
+            OsStr::new("local"),
+            Path::new(".network/Observer"),
+            30334,
+            9945,
+            false,
+            Some(&bootnode),
+        );
+        assert_eq!(
+            args(&command),
+            [
+                "--chain", "local", "--base-path", ".network/Observer", "--port", "30334", "--rpc-port", "9945",
+                "--name", "Observer", "--bootnodes", &bootnode,
+            ]
+        );
+    }
+}
@@ -1,4 +1,3 @@
-use anyhow::Context as _;
 use core::slice;
 use std::ffi::OsStr;
 use std::fs;
@@ -8,17 +7,32 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
 use strum::Display;
+use anyhow::Context as _;
+use regex::Regex;
 use toml_edit::value;
 use toml_edit::Array;
 use toml_edit::Document;
+use toml_edit::InlineTable;
 use toml_edit::Item;
 use toml_edit::Table;
 
 use crate::core::manifest::Manifest;
+use crate::ops::substrate_add::add_pallet;
+use crate::ops::substrate_add::add_pallet_feature_to_manifest;
+use crate::ops::substrate_add::add_pallet_to_benchmarks;
+use crate::ops::substrate_add::AddOptions;
+use crate::ops::substrate_add::CrateSource;
 use crate::templates::load_template_config;
+use crate::templates::render;
+use crate::templates::TemplateConfig;
+use crate::templates::PALLET_TEMPLATE;
 use crate::util::config::get_package_name;
+use crate::util::config::ChainInfo;
+use crate::util::config::ChainMemberKind;
 use crate::util::config::Config;
+use crate::util::config::ProjectType;
 use crate::util::restricted_names;
+use crate::util::to_pascal_case;
 use crate::util::to_snake_case;
 use crate::util::SubstrateResult;
 
@@ -31,7 +45,13 @@ pub enum Template {
     Canvas,
     // Contract templates
     CargoContract,
-    // Path to custom template
+    Erc20,
+    Psp22,
+    Erc721,
+    Psp34,
+    Multisig,
+    Proxy,
+    // Path to custom template: a template config, a local directory or a git remote for contracts
     Custom(String),
 }
 
@@ -43,6 +63,26 @@ pub struct NewOptions {
     pub name: Option<String>,
 }
 
+#[derive(Debug)]
+pub struct NewPalletOptions {
+    /// Name of the pallet, with or without its `pallet-` prefix, e.g. `kitties`
+    pub name: String,
+}
+
+/// Directory of the chain's local pallets.
+const PALLETS_DIR: &str = "pallets";
+
+/// Dependencies of a new pallet, by name and package, resolved from the chain's own manifests.
+const PALLET_DEPENDENCIES: [(&str, &str); 5] = [
+    ("codec", "parity-scale-codec"),
+    ("scale-info", "scale-info"),
+    ("frame-benchmarking", "frame-benchmarking"),
+    ("frame-support", "frame-support"),
+    ("frame-system", "frame-system"),
+];
+
+const PALLET_DEV_DEPENDENCIES: [&str; 3] = ["sp-core", "sp-io", "sp-runtime"];
+
 // TODO: Add digestible error message on failure
 pub fn validate_rust_installation() -> SubstrateResult<()> {
     let info = String::from_utf8_lossy(
@@ -100,23 +140,6 @@ fn get_name(opts: &NewOptions) -> SubstrateResult<&str> {
     })
 }
 
-fn get_parent(opts: &NewOptions) -> SubstrateResult<&str> {
-    let path = &opts.path;
-    let parent = path.parent().ok_or_else(|| {
-        anyhow::format_err!(
-            "cannot auto-detect package parent directory from path {:?}",
-            path.as_os_str()
-        )
-    })?;
-
-    parent.to_str().ok_or_else(|| {
-        anyhow::format_err!(
-            "cannot create package with a non-unicode name: {:?}",
-            parent
-        )
-    })
-}
-
 /// Validates that the path contains valid PATH env characters.
 fn validate_path(path: &Path) -> SubstrateResult<()> {
     if cargo_util::paths::join_paths(slice::from_ref(&OsStr::new(path)), "").is_err() {
@@ -229,7 +252,7 @@ pub fn new_chain(opts: &NewOptions, config: &Config) -> SubstrateResult<()> {
 
     println!("Creating new chain...\n");
 
-    generate_node_template(&opts.template, &path)?;
+    generate_node_template(&opts.template, path)?;
 
     mk_chain(opts, name)?;
 
@@ -254,18 +277,336 @@ pub fn new_contract(opts: &NewOptions, config: &Config) -> SubstrateResult<()> {
     let name = get_name(opts)?;
     validate_name(name, opts.name.is_none())?;
 
+    // Inside a contract project, the new contract becomes a member of its workspace
+    let workspace_root = match &config.project_type {
+        Some(ProjectType::Contract(info)) if info.workspace => Some(config.cwd().join(&info.path)),
+        Some(ProjectType::Contract(_)) => anyhow::bail!(
+            "the current contract project isn't a cargo workspace\n\n\
+             Declare a `[workspace]` in its Cargo.toml to add more contracts"
+        ),
+        _ => None,
+    };
+    let member_path = match &workspace_root {
+        Some(root) => Some(
+            path.strip_prefix(root)
+                .with_context(|| format!("`{}` is outside of the workspace", path.display()))?
+                .to_path_buf(),
+        ),
+        None => None,
+    };
+
     validate_rust_installation()?;
     validate_cargo_contract_installation()?;
 
     println!("Creating new contract...");
-    create_smart_contract(name, &opts.path)?;
-    mk_contract(opts, name)?;
+    generate_contract_template(&opts.template, name, path, config.cwd())?;
+    if workspace_root.is_some() {
+        // Templates may be standalone workspaces, which cargo rejects as workspace members
+        remove_workspace_table(&path.join("Cargo.toml"))?;
+    }
 
-    print_start_hacking_message(config.cwd(), path);
+    match (&workspace_root, &member_path) {
+        (Some(root), Some(member_path)) => {
+            if add_workspace_member(&root.join("Cargo.toml"), member_path)? {
+                println!("Added `{}` to the workspace members", member_path.display());
+            }
+        }
+        _ => {
+            mk_contract(opts, name)?;
+            print_start_hacking_message(config.cwd(), path);
+        }
+    }
 
     Ok(())
 }
 
+/// Adds `member_path` to the workspace members, unless one of the member globs already covers it.
+///
+/// Returns whether the manifest was changed.
+fn add_workspace_member(manifest_path: &Path, member_path: &Path) -> SubstrateResult<bool> {
+    let mut manifest = Manifest::new(manifest_path.to_path_buf());
+    let mut document = manifest.read_document()?;
+    let members = document["workspace"]
+        .as_table_like_mut()
+        .with_context(|| "unknown `workspace` type in Cargo.toml")?
+        .entry("members")
+        .or_insert(value(Array::new()))
+        .as_array_mut()
+        .with_context(|| "unknown `workspace.members` type in Cargo.toml")?;
+
+    let member = member_path.to_string_lossy().replace('\\', "/");
+    let covered = members.iter().filter_map(|pattern| pattern.as_str()).any(|pattern| {
+        glob::Pattern::new(pattern).is_ok_and(|pattern| pattern.matches(&member))
+    });
+    if covered {
+        return Ok(false);
+    }
+
+    members.push(member);
+    manifest.write_document(document)?;
+    Ok(true)
+}
+
+/// Names of a local pallet: its crate, its module, its name in the mock runtime and its directory.
+#[derive(Debug, PartialEq, Eq)]
+struct PalletNames {
+    crate_name: String,
+    mod_name: String,
+    pallet_name: String,
+    dir_name: String,
+}
+
+impl PalletNames {
+    fn new(name: &str) -> Self {
+        // `to_snake_case` drops underscores, they separate words like dashes here
+        let snake_name = to_snake_case(&name.replace('_', "-"));
+        let short_name = snake_name.strip_prefix("pallet_").unwrap_or(&snake_name);
+        Self {
+            crate_name: format!("pallet-{}", short_name.replace('_', "-")),
+            mod_name: format!("pallet_{}", short_name),
+            pallet_name: to_pascal_case(short_name),
+            dir_name: short_name.replace('_', "-"),
+        }
+    }
+}
+
+/// Manifests of the chain's crates and workspace, with their directory relative to the current directory.
+fn chain_manifests(config: &Config, chain: &ChainInfo) -> SubstrateResult<Vec<(PathBuf, Document)>> {
+    let mut manifests = Vec::new();
+    // Existing pallets depend on the same crates as the new one, and on compatible versions
+    let mut members = chain.members(config.cwd())?;
+    members.sort_by_key(|member| member.kind != ChainMemberKind::Pallet);
+    for member in members {
+        if let Ok(document) = Manifest::new(config.cwd().join(&member.path).join("Cargo.toml")).read_document() {
+            manifests.push((member.path, document));
+        }
+    }
+    if let Ok(document) = Manifest::new(config.cwd().join("Cargo.toml")).read_document() {
+        manifests.push((PathBuf::new(), document));
+    }
+    Ok(manifests)
+}
+
+fn to_inline_table(item: &Item) -> Option<InlineTable> {
+    if let Some(version) = item.as_str() {
+        let mut table = InlineTable::new();
+        table.insert("version", version.into());
+        return Some(table);
+    }
+    item.as_inline_table()
+        .cloned()
+        .or_else(|| item.as_table().map(|table| table.clone().into_inline_table()))
+}
+
+/// Finds how the chain depends on `name`, or on its `package` under another name.
+///
+/// Path dependencies are made relative to the new pallet's directory.
+fn find_dependency(manifests: &[(PathBuf, Document)], name: &str, package: &str) -> Option<InlineTable> {
+    for (dir, document) in manifests {
+        // The workspace's own dependencies are inherited rather than copied
+        if let Some(dependencies) = document
+            .get("workspace")
+            .and_then(|workspace| workspace.get("dependencies"))
+            .and_then(|dependencies| dependencies.as_table_like())
+        {
+            if dependencies.get(name).is_some() {
+                let mut table = InlineTable::new();
+                table.insert("workspace", true.into());
+                return Some(table);
+            }
+        }
+
+        for table in ["dependencies", "dev-dependencies"] {
+            let Some(dependencies) = document.get(table).and_then(|t| t.as_table_like()) else {
+                continue;
+            };
+            let Some((key, mut spec)) = dependencies.iter().find_map(|(key, item)| {
+                let spec = to_inline_table(item)?;
+                let matches = key == name
+                    || spec.get("package").and_then(|p| p.as_str()) == Some(package);
+                matches.then_some((key, spec))
+            }) else {
+                continue;
+            };
+
+            if key != name && spec.get("package").is_none() {
+                spec.insert("package", package.into());
+            }
+            if let Some(path) = spec.get("path").and_then(|p| p.as_str()).map(PathBuf::from) {
+                if path.is_relative() {
+                    let path = Path::new("../..").join(cargo_util::paths::normalize_path(&dir.join(path)));
+                    spec.insert("path", path.to_string_lossy().replace('\\', "/").as_str().into());
+                }
+            }
+            return Some(spec);
+        }
+    }
+
+    None
+}
+
+/// The spec of a new pallet's dependency on `name`: the chain's own, or one from the same git
+/// repository as its `frame-support`.
+fn pallet_dependency(manifests: &[(PathBuf, Document)], name: &str, package: &str) -> SubstrateResult<InlineTable> {
+    if let Some(spec) = find_dependency(manifests, name, package) {
+        return Ok(spec);
+    }
+
+    let git_spec = find_dependency(manifests, "frame-support", "frame-support")
+        .filter(|spec| spec.contains_key("git"))
+        .map(|frame_support| {
+            let mut spec = InlineTable::new();
+            for key in ["git", "branch", "tag", "rev"] {
+                if let Some(value) = frame_support.get(key) {
+                    spec.insert(key, value.clone());
+                }
+            }
+            spec
+        });
+    git_spec.with_context(|| {
+        format!(
+            "couldn't find which version of `{}` the chain uses, add it to the runtime's dependencies first",
+            package
+        )
+    })
+}
+
+/// The `[dependencies]` and `[dev-dependencies]` of a new pallet.
+fn pallet_dependencies(manifests: &[(PathBuf, Document)]) -> SubstrateResult<(Table, Table)> {
+    let mut dependencies = Table::new();
+    for (name, package) in PALLET_DEPENDENCIES {
+        let mut spec = pallet_dependency(manifests, name, package)?;
+        spec.remove("optional");
+        spec.remove("default_features");
+        spec.insert("default-features", false.into());
+        if name == "frame-benchmarking" {
+            spec.insert("optional", true.into());
+        }
+        if name == "codec" || name == "scale-info" {
+            let features = spec
+                .entry("features")
+                .or_insert(Array::new().into())
+                .as_array_mut()
+                .with_context(|| format!("unknown `features` type of `{}`", name))?;
+            if !features.iter().any(|feature| feature.as_str() == Some("derive")) {
+                features.push("derive");
+            }
+        }
+        spec.fmt();
+        dependencies.insert(name, value(spec));
+    }
+
+    let mut dev_dependencies = Table::new();
+    for name in PALLET_DEV_DEPENDENCIES {
+        let mut spec = pallet_dependency(manifests, name, name)?;
+        // Tests run with the standard library
+        for key in ["optional", "default-features", "default_features"] {
+            spec.remove(key);
+        }
+        spec.fmt();
+        dev_dependencies.insert(name, value(spec));
+    }
+
+    Ok((dependencies, dev_dependencies))
+}
+
+/// Creates a pallet in `pallets/` from the pallet template, and adds it to the workspace and the runtime.
+pub fn new_pallet(opts: &NewPalletOptions, config: &Config) -> SubstrateResult<PathBuf> {
+    let Some(ProjectType::Chain(chain)) = &config.project_type else {
+        anyhow::bail!("pallets can only be created in chain projects");
+    };
+    let names = PalletNames::new(&opts.name);
+    validate_name(&names.crate_name, false)?;
+
+    let relative_path = Path::new(PALLETS_DIR).join(&names.dir_name);
+    let path = config.cwd().join(&relative_path);
+    if path.exists() {
+        anyhow::bail!("destination `{}` already exists", path.display());
+    }
+    let runtime_name = chain
+        .runtime_name
+        .clone()
+        .with_context(|| "couldn't determine the runtime's package name")?;
+    // Resolved before writing anything, so that a missing dependency fails early
+    let (dependencies, dev_dependencies) = pallet_dependencies(&chain_manifests(config, chain)?)?;
+
+    // Files edited below, restored together with the removal of the pallet if a step fails
+    let runtime_path = config.cwd().join(&chain.runtime_path);
+    let edited_files = [
+        config.cwd().join("Cargo.toml"),
+        config.cwd().join("Cargo.lock"),
+        runtime_path.join("Cargo.toml"),
+        runtime_path.join("src/lib.rs"),
+    ];
+    let originals = edited_files
+        .iter()
+        .map(|file| fs::read(file).ok())
+        .collect::<Vec<_>>();
+
+    let result = (|| {
+        println!("Creating pallet `{}`...", names.crate_name);
+        let values = [
+            ("crate_name", names.crate_name.as_str()),
+            ("mod_name", names.mod_name.as_str()),
+            ("pallet_name", names.pallet_name.as_str()),
+        ];
+        for (file, template) in PALLET_TEMPLATE {
+            let file_path = path.join(file);
+            if let Some(parent) = file_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(file_path, render(template, &values))?;
+        }
+
+        let mut manifest = Manifest::new(path.join("Cargo.toml"));
+        let mut document = manifest.read_document()?;
+        document["dependencies"] = Item::Table(dependencies);
+        document["dev-dependencies"] = Item::Table(dev_dependencies);
+        manifest.write_document(document)?;
+
+        let workspace_manifest = config.cwd().join("Cargo.toml");
+        if Manifest::new(workspace_manifest.clone())
+            .read_document()
+            .is_ok_and(|document| document.get("workspace").is_some())
+            && add_workspace_member(&workspace_manifest, &relative_path)?
+        {
+            println!("Added `{}` to the workspace members", relative_path.display());
+        }
+
+        let opts = AddOptions {
+            package_name: runtime_name,
+            package_path: chain.runtime_path.clone(),
+            crate_spec: names.crate_name.clone(),
+            features: Vec::new(),
+            default_features: Some(false),
+            source: CrateSource::Path(relative_path.to_string_lossy().to_string()),
+            config_items: vec![
+                "type RuntimeEvent = RuntimeEvent;".to_string(),
+                format!("type WeightInfo = {}::weights::SubstrateWeight<Runtime>;", names.mod_name),
+            ],
+            index: None,
+        };
+        add_pallet(&opts, config)?;
+        for feature in ["runtime-benchmarks", "try-runtime"] {
+            add_pallet_feature_to_manifest(config.cwd(), &chain.runtime_path, &names.crate_name, feature)?;
+        }
+        add_pallet_to_benchmarks(config.cwd(), &chain.runtime_path, &names.crate_name)?;
+        Ok(())
+    })();
+    if let Err(err) = result {
+        for (file, original) in edited_files.iter().zip(originals) {
+            match original {
+                Some(content) => fs::write(file, content).ok(),
+                None => fs::remove_file(file).ok(),
+            };
+        }
+        fs::remove_dir_all(&path).ok();
+        return Err(err);
+    }
+
+    Ok(path)
+}
+
 /// Gets the latest commit id (SHA1) of the repository given by `path`.
 fn get_git_commit_id(path: &Path) -> String {
     let commit_id_output = Command::new("git")
@@ -413,6 +754,25 @@ fn update_top_level_cargo_toml(
         .insert("members", value(members));
 }
 
+/// Shallow clones the template's repository to `path`, only checking out the template path (and
+/// the top level files) when one is given.
+pub(crate) fn git_clone(template_config: &TemplateConfig, path: &Path) -> SubstrateResult<()> {
+    let mut command = Command::new("git");
+    command.args(["clone", "--filter=blob:none", "--depth", "1"]);
+    if !template_config.template_path.is_empty() {
+        command.arg("--sparse");
+    }
+    if !template_config.branch.is_empty() {
+        command.args(["--branch", &template_config.branch]);
+    }
+    let status = command.arg(&template_config.remote).arg(path).status()?;
+    if !status.success() {
+        anyhow::bail!("failed to clone template from `{}`", template_config.remote);
+    }
+
+    Ok(())
+}
+
 pub fn generate_node_template(template: &Template, path: &Path) -> SubstrateResult<()> {
     let template_config = if let Template::Custom(template_config_path) = template {
         load_template_config(template_config_path)?
@@ -420,21 +780,7 @@ pub fn generate_node_template(template: &Template, path: &Path) -> SubstrateResu
         load_template_config(&template.to_string())?
     };
 
-    Command::new("git")
-        .args([
-            "clone",
-            "--filter=blob:none",
-            "--depth",
-            "1",
-            "--sparse",
-            "--branch",
-            &template_config.branch,
-            &template_config.remote,
-            path.as_os_str()
-                .to_str()
-                .expect("invalid characters in path"),
-        ])
-        .status()?;
+    git_clone(&template_config, path)?;
 
     // Get commit id before we mutate the repository
     let commit_id = get_git_commit_id(path);
@@ -448,17 +794,15 @@ pub fn generate_node_template(template: &Template, path: &Path) -> SubstrateResu
     fs::remove_dir_all(path.join(".git"))?;
 
     if let Ok(entries) = fs::read_dir(path) {
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let entry_path = entry.path();
-
-                if let Some(file_name) = entry_path.file_name().and_then(|n| n.to_str()) {
-                    if entry_path.is_file()
-                        && !file_name.contains("rustfmt.toml")
-                        && !file_name.contains("Cargo")
-                    {
-                        fs::remove_file(entry_path)?;
-                    }
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+
+            if let Some(file_name) = entry_path.file_name().and_then(|n| n.to_str()) {
+                if entry_path.is_file()
+                    && !file_name.contains("rustfmt.toml")
+                    && !file_name.contains("Cargo")
+                {
+                    fs::remove_file(entry_path)?;
                 }
             }
         }
@@ -485,6 +829,7 @@ pub fn generate_node_template(template: &Template, path: &Path) -> SubstrateResu
         // create the top_level_cargo_toml
         OpenOptions::new()
             .create(true)
+            .truncate(false)
             .write(true)
             .open(&top_level_cargo_toml_path)
             .expect("Create root level `Cargo.toml` failed.");
@@ -545,7 +890,9 @@ pub fn create_smart_contract(name: &str, path: &Path) -> SubstrateResult<()> {
         .status()?;
 
     // We have to do this in case the package name is different then its root directory name
-    let dir_from_path = path.file_name().unwrap();
+    let dir_from_path = path
+        .file_name()
+        .with_context(|| format!("`{}` doesn't name a directory", path.display()))?;
     if name != dir_from_path {
         fs::rename(parent.join(name), path)?;
     }
@@ -557,6 +904,194 @@ pub fn create_smart_contract(name: &str, path: &Path) -> SubstrateResult<()> {
     Ok(())
 }
 
+/// Generates the contract from `template` at `path`, renamed to `name`.
+///
+/// Custom templates are resolved relative to `cwd`, as a template config file, a local directory
+/// or else a git remote.
+pub fn generate_contract_template(
+    template: &Template,
+    name: &str,
+    path: &Path,
+    cwd: &Path,
+) -> SubstrateResult<()> {
+    let template_config = match template {
+        Template::CargoContract => return create_smart_contract(name, path),
+        Template::Custom(source) if cwd.join(source).is_dir() => {
+            copy_template_dir(&cwd.join(source), path)?;
+            resolve_path_dependencies(&cwd.join(source), path)?;
+            return rename_contract(path, name);
+        }
+        Template::Custom(source) if cwd.join(source).is_file() => {
+            load_template_config(&cwd.join(source).to_string_lossy())?
+        }
+        Template::Custom(source) => TemplateConfig {
+            remote: source.clone(),
+            branch: String::new(),
+            template_path: String::new(),
+        },
+        template => load_template_config(&template.to_string())?,
+    };
+
+    let parent = path.parent().unwrap_or(Path::new(""));
+    fs::create_dir_all(parent)?;
+    let dir_name = path
+        .file_name()
+        .with_context(|| format!("`{}` doesn't name a directory", path.display()))?;
+    let clone_path = parent.join(format!(".{}-template", dir_name.to_string_lossy()));
+    if clone_path.exists() {
+        fs::remove_dir_all(&clone_path)?;
+    }
+
+    let result = (|| {
+        git_clone(&template_config, &clone_path)?;
+        let commit_id = get_git_commit_id(&clone_path);
+        if !template_config.template_path.is_empty() {
+            let status = Command::new("git")
+                .current_dir(&clone_path)
+                .args(["sparse-checkout", "set", &template_config.template_path])
+                .status()?;
+            if !status.success() {
+                anyhow::bail!("failed to check out `{}`", template_config.template_path);
+            }
+        }
+
+        let template_path = clone_path.join(&template_config.template_path);
+        if !template_path.join("Cargo.toml").exists() {
+            anyhow::bail!(
+                "no contract found at `{}` in `{}`",
+                template_config.template_path,
+                template_config.remote
+            );
+        }
+        copy_template_dir(&template_path, path)?;
+
+        // Path dependencies on other crates of the template's repository are taken from git
+        let manifest_path = path.join("Cargo.toml");
+        let mut manifest = Manifest::new(manifest_path.clone());
+        let mut document = manifest.read_document()?;
+        replace_path_dependencies_with_git(
+            &manifest_path,
+            &template_config.remote,
+            &commit_id,
+            &mut document,
+        );
+        manifest.write_document(document)?;
+
+        rename_contract(path, name)
+    })();
+
+    fs::remove_dir_all(&clone_path).ok();
+    result
+}
+
+/// Recursively copies a template, without its git repository and build artifacts.
+pub(crate) fn copy_template_dir(from: &Path, to: &Path) -> SubstrateResult<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        if [".git", "target", "Substrate.toml"].contains(&file_name.to_string_lossy().as_ref()) {
+            continue;
+        }
+
+        if entry.file_type()?.is_dir() {
+            copy_template_dir(&entry.path(), &to.join(&file_name))?;
+        } else {
+            fs::copy(entry.path(), to.join(&file_name))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Points the relative path dependencies of a template copied to `path` back to the template.
+fn resolve_path_dependencies(template_path: &Path, path: &Path) -> SubstrateResult<()> {
+    let mut manifest = Manifest::new(path.join("Cargo.toml"));
+    let mut document = manifest.read_document()?;
+    for table in ["dependencies", "build-dependencies", "dev-dependencies"] {
+        let Some(dependencies) = document.get_mut(table).and_then(|t| t.as_table_like_mut()) else {
+            continue;
+        };
+        for (_, dependency) in dependencies.iter_mut() {
+            let Some(dependency) = dependency.as_table_like_mut() else {
+                continue;
+            };
+            let Some(dependency_path) = dependency.get("path").and_then(|p| p.as_str()) else {
+                continue;
+            };
+            if path.join(dependency_path).exists() {
+                continue;
+            }
+            if let Ok(resolved) = fs::canonicalize(template_path.join(dependency_path)) {
+                dependency.insert("path", value(resolved.to_string_lossy().as_ref()));
+            }
+        }
+    }
+    manifest.write_document(document)?;
+
+    Ok(())
+}
+
+/// Renames the template's package to `name`, along with its module and contract names in the sources.
+fn rename_contract(path: &Path, name: &str) -> SubstrateResult<()> {
+    let mut manifest = Manifest::new(path.join("Cargo.toml"));
+    let mut document = manifest.read_document()?;
+    let original = document
+        .get("package")
+        .and_then(|package| package.get("name"))
+        .and_then(|name| name.as_str())
+        .with_context(|| format!("no package name in `{}`", path.join("Cargo.toml").display()))?
+        .to_string();
+    let original_lib = document
+        .get("lib")
+        .and_then(|lib| lib.get("name"))
+        .and_then(|name| name.as_str())
+        .map(str::to_string);
+
+    document["package"]["name"] = value(name);
+    if original_lib.is_some() {
+        document["lib"]["name"] = value(to_snake_case(name));
+    }
+    let original_lib = original_lib.unwrap_or_else(|| to_snake_case(&original));
+    manifest.write_document(document)?;
+
+    let replacements = [
+        (
+            Regex::new(&format!(r"\b{}\b", regex::escape(&original_lib)))?,
+            to_snake_case(name),
+        ),
+        // Also renames the `<Contract>Ref` type generated by ink!
+        (
+            Regex::new(&format!(r"\b{}(Ref)?\b", regex::escape(&to_pascal_case(&original))))?,
+            format!("{}${{1}}", to_pascal_case(name)),
+        ),
+    ];
+
+    let pattern = format!("{}/**/*.rs", glob::Pattern::escape(&path.to_string_lossy()));
+    for file in glob::glob(&pattern)?.flatten() {
+        let source = fs::read_to_string(&file)?;
+        let mut renamed = source.clone();
+        for (regex, replacement) in &replacements {
+            renamed = regex.replace_all(&renamed, replacement.as_str()).into_owned();
+        }
+        if renamed != source {
+            fs::write(&file, renamed)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes the `[workspace]` table of a manifest, if any.
+fn remove_workspace_table(manifest_path: &Path) -> SubstrateResult<()> {
+    let mut manifest = Manifest::new(manifest_path.to_path_buf());
+    let mut document = manifest.read_document()?;
+    if document.remove("workspace").is_some() {
+        manifest.write_document(document)?;
+    }
+    Ok(())
+}
+
 fn replace_occurrence_in_file(file_path: &Path, original: &str, new: &str) -> SubstrateResult<()> {
     if file_path.exists() {
         let file = fs::read_to_string(file_path)?;
@@ -684,3 +1219,116 @@ pub fn mk_contract(opts: &NewOptions, _name: &str) -> SubstrateResult<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_pallet_names() {
+        let expected = PalletNames {
+            crate_name: "pallet-proof-of-existence".to_string(),
+            mod_name: "pallet_proof_of_existence".to_string(),
+            pallet_name: "ProofOfExistence".to_string(),
+            dir_name: "proof-of-existence".to_string(),
+        };
+        assert_eq!(PalletNames::new("proof-of-existence"), expected);
+        assert_eq!(PalletNames::new("pallet_proof_of_existence"), expected);
+        assert_eq!(PalletNames::new("ProofOfExistence"), expected);
+    }
+
+    #[test]
+    fn test_pallet_dependencies() {
+        let manifest = |dir: &str, content: &str| (PathBuf::from(dir), content.parse::<Document>().unwrap());
+        let manifests = [
+            manifest(
+                "pallets/template",
+                r#"
+                [dependencies]
+                parity-codec = { package = "parity-scale-codec", version = "3.6.1", default-features = false }
+                scale-info = { workspace = true }
+
+                [dev-dependencies]
+                sp-runtime = "24.0.0"
+                "#,
+            ),
+            manifest(
+                "runtime",
+                r#"
+                [dependencies]
+                frame-benchmarking = { version = "4.0.0-dev", optional = true, default_features = false }
+                frame-support = { git = "https://github.com/paritytech/substrate.git", branch = "polkadot-v1.0.0", default-features = false }
+                frame-system = { path = "../frame/system", default-features = false }
+                "#,
+            ),
+            manifest(
+                "",
+                r#"
+                [workspace.dependencies]
+                scale-info = "2.5.0"
+                sp-core = "21.0.0"
+                "#,
+            ),
+        ];
+
+        assert_eq!(
+            find_dependency(&manifests, "codec", "parity-scale-codec").unwrap().to_string().trim(),
+            r#"{ package = "parity-scale-codec", version = "3.6.1", default-features = false }"#
+        );
+        assert_eq!(
+            find_dependency(&manifests, "frame-system", "frame-system").unwrap().to_string().trim(),
+            r#"{ path = "../../frame/system", default-features = false }"#
+        );
+        assert!(find_dependency(&manifests, "sp-io", "sp-io").is_none());
+
+        let (dependencies, dev_dependencies) = pallet_dependencies(&manifests).unwrap();
+        assert_eq!(
+            dependencies.to_string(),
+            [
+                r#"codec = { package = "parity-scale-codec", version = "3.6.1", default-features = false, features = ["derive"] }"#,
+                r#"scale-info = { workspace = true, default-features = false, features = ["derive"] }"#,
+                r#"frame-benchmarking = { version = "4.0.0-dev", default-features = false, optional = true }"#,
+                r#"frame-support = { git = "https://github.com/paritytech/substrate.git", branch = "polkadot-v1.0.0", default-features = false }"#,
+                r#"frame-system = { path = "../../frame/system", default-features = false }"#,
+                "",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            dev_dependencies.to_string(),
+            [
+                r#"sp-core = { workspace = true }"#,
+                r#"sp-io = { git = "https://github.com/paritytech/substrate.git", branch = "polkadot-v1.0.0" }"#,
+                r#"sp-runtime = { version = "24.0.0" }"#,
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_rename_contract() {
+        // Glob metacharacters in the path are matched literally
+        let path = env::temp_dir().join("substrate-manager-test-rename-contract[1]");
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(path.join("src")).unwrap();
+        fs::write(path.join("Cargo.toml"), "[package]\nname = \"erc20\"\nversion = \"0.1.0\"\n").unwrap();
+        fs::write(
+            path.join("src/lib.rs"),
+            "mod erc20 {\n    pub struct Erc20;\n    type Other = erc20_ext::Erc20Ref;\n    pub struct Erc20Token;\n}\n",
+        )
+        .unwrap();
+
+        rename_contract(&path, "my-token").unwrap();
+
+        let manifest = fs::read_to_string(path.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("name = \"my-token\""));
+        assert_eq!(
+            fs::read_to_string(path.join("src/lib.rs")).unwrap(),
+            "mod my_token {\n    pub struct MyToken;\n    type Other = erc20_ext::MyTokenRef;\n    pub struct Erc20Token;\n}\n"
+        );
+        fs::remove_dir_all(&path).unwrap();
+    }
+}
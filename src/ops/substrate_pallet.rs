@@ -0,0 +1,157 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use crate::{
+    core::{
+        pallet_source::{self, CallItem, StorageItem, Variant},
+        Shell,
+    },
+    ops::substrate_bench::local_pallets,
+    util::{config::ChainInfo, Config, SubstrateResult},
+};
+
+/// Weight of new calls in pallets without a `WeightInfo`, a transfer's order of magnitude.
+const DEFAULT_CALL_WEIGHT: &str = "Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1)";
+
+pub enum PalletComponent {
+    Storage(StorageItem),
+    Call {
+        name: String,
+        params: Vec<(String, String)>,
+    },
+    Event(Variant),
+    Error(String),
+}
+
+pub struct PalletComponentOptions {
+    /// Local pallet to edit, e.g. `pallet_template`
+    pub pallet: String,
+    pub component: PalletComponent,
+}
+
+/// The `src/lib.rs` of the local pallet named `name`.
+fn pallet_lib(config: &Config, chain: &ChainInfo, name: &str) -> SubstrateResult<PathBuf> {
+    let pallets = local_pallets(config, chain)?;
+    let (_, path) = pallets
+        .iter()
+        .find(|(pallet, _)| pallet == name)
+        .ok_or_else(|| {
+            let names = pallets.iter().map(|(pallet, _)| pallet.as_str()).collect::<Vec<_>>();
+            anyhow::anyhow!("`{}` isn't a pallet of the workspace, expected one of: {}", name, names.join(", "))
+        })?;
+
+    Ok(path.join("src").join("lib.rs"))
+}
+
+/// Adds the call's weight function to the pallet's `WeightInfo`, in `weights.rs` or in `lib.rs`.
+///
+/// Returns the updated `lib.rs` and, if the function went there, `weights.rs`; or `None` if no
+/// `WeightInfo` trait was found.
+fn add_weight_function(lib: &Path, content: &str, name: &str) -> SubstrateResult<Option<(String, Option<String>)>> {
+    let weights = lib.with_file_name("weights.rs");
+    if weights.exists() {
+        if let Some(updated) = pallet_source::add_weight_function(&fs::read_to_string(&weights)?, name)? {
+            return Ok(Some((content.to_string(), Some(updated))));
+        }
+    }
+    Ok(pallet_source::add_weight_function(content, name)?.map(|updated| (updated, None)))
+}
+
+/// Formats `content` with `rustfmt`, run in `dir` to pick up the project's `rustfmt.toml`.
+///
+/// Read from stdin, so that the modules the file declares are left alone. Returns `None` if
+/// `rustfmt` isn't installed or fails.
+fn rustfmt(content: &str, dir: &Path) -> Option<String> {
+    let mut child = Command::new("rustfmt")
+        .current_dir(dir)
+        .args(["--edition", "2021", "--emit", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    child.stdin.take()?.write_all(content.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+
+    output.status.success().then(|| String::from_utf8(output.stdout).ok())?
+}
+
+/// Adds a storage item, call, event or error to a local pallet, keeping the rest of its source as is.
+pub fn add_component(opts: &PalletComponentOptions, config: &Config, chain: &ChainInfo) -> SubstrateResult<()> {
+    let mut shell = Shell::new();
+    let lib = pallet_lib(config, chain, &opts.pallet)?;
+    let content = fs::read_to_string(&lib)?;
+    let mut weights = None;
+
+    let (content, added) = match &opts.component {
+        PalletComponent::Storage(storage) => (
+            pallet_source::add_storage(&content, storage)?,
+            format!("storage `{}`", storage.name),
+        ),
+        PalletComponent::Call { name, params } => {
+            let has_weight_info = pallet_source::has_weight_info(&content)?;
+            let weight = if has_weight_info {
+                format!("T::WeightInfo::{}()", name)
+            } else {
+                DEFAULT_CALL_WEIGHT.to_string()
+            };
+            let call = CallItem {
+                name: name.clone(),
+                params: params.clone(),
+                weight,
+            };
+            let (content, index) = pallet_source::add_call(&content, &call)?;
+            let content = if has_weight_info {
+                match add_weight_function(&lib, &content, name)? {
+                    Some((content, updated_weights)) => {
+                        weights = updated_weights;
+                        content
+                    }
+                    None => {
+                        shell.warn(format!(
+                            "couldn't find the pallet's `WeightInfo` trait, add `fn {}() -> Weight;` to it",
+                            name
+                        ))?;
+                        content
+                    }
+                }
+            } else {
+                content
+            };
+            shell.note(format!(
+                "its weight is a placeholder, add a `{}` benchmark to measure it",
+                name
+            ))?;
+            (content, format!("call `{}` with call index {}", name, index))
+        }
+        PalletComponent::Event(variant) => (
+            pallet_source::add_event(&content, variant)?,
+            format!("event `{}`", variant.name),
+        ),
+        PalletComponent::Error(name) => (pallet_source::add_error(&content, name)?, format!("error `{}`", name)),
+    };
+
+    // Written only once both edits succeeded, `lib.rs` first
+    let dir = lib.parent().unwrap_or(Path::new("."));
+    let mut formatted = true;
+    for (path, content) in [(lib.clone(), Some(content)), (lib.with_file_name("weights.rs"), weights)] {
+        let Some(content) = content else {
+            continue;
+        };
+        let content = rustfmt(&content, dir).unwrap_or_else(|| {
+            formatted = false;
+            content
+        });
+        fs::write(path, content)?;
+    }
+    if !formatted {
+        shell.note("couldn't run `rustfmt`, the added code is left unformatted")?;
+    }
+    shell.status("Added", format!("{} to `{}`", added, lib.display()))?;
+
+    Ok(())
+}
@@ -1,13 +1,189 @@
-use std::process::Command;
+use std::{path::PathBuf, process::Command};
 
-use anyhow::Ok;
+use anyhow::{Context as _, Ok};
+use syn::visit::{self, Visit};
 
-use crate::util::{Config, SubstrateResult};
+use crate::{
+    core::manifest::Manifest,
+    util::{config::ChainInfo, Config, SubstrateResult},
+};
 
 pub struct RunOptions {
     pub chain: String,
 }
 
+/// The chain-specifications a node knows how to load, as declared by its `load_spec` function.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ChainSpecs {
+    /// Built-in chain-specification ids, such as `dev` or `local`
+    pub ids: Vec<String>,
+    /// Whether `load_spec` falls back to loading any other id as a chain spec file path
+    pub accepts_path: bool,
+}
+
+impl ChainSpecs {
+    fn push_id(&mut self, id: String) {
+        if !id.is_empty() && !self.ids.contains(&id) {
+            self.ids.push(id);
+        }
+    }
+
+    /// Collects the ids matched by a `match id { ... }` arm pattern.
+    ///
+    /// Patterns that can't be turned into an id (e.g. `_`) are skipped.
+    fn collect_pattern(&mut self, pat: &syn::Pat) {
+        match pat {
+            // "dev" => ...
+            syn::Pat::Lit(syn::PatLit {
+                lit: syn::Lit::Str(lit_str),
+                ..
+            }) => self.push_id(lit_str.value()),
+            // some::module::CONSTANT => "constant"
+            syn::Pat::Path(syn::PatPath { path, .. }) => {
+                if let Some(segment) = path.segments.last() {
+                    self.push_id(segment.ident.to_string().to_lowercase());
+                }
+            }
+            // "dev" | "test" | some::module::CONSTANT => ...
+            syn::Pat::Or(syn::PatOr { cases, .. }) => {
+                cases.iter().for_each(|case| self.collect_pattern(case));
+            }
+            syn::Pat::Paren(syn::PatParen { pat, .. }) => self.collect_pattern(pat),
+            // path => ChainSpec::from_json_file(path.into())
+            syn::Pat::Ident(syn::PatIdent { subpat: None, .. }) => self.accepts_path = true,
+            _ => {}
+        }
+    }
+}
+
+/// Finds every `load_spec` function (free or inside an `impl` block) and the
+/// `match` on its id argument, wherever it is nested in the body.
+#[derive(Default)]
+struct LoadSpecVisitor {
+    specs: ChainSpecs,
+    /// Whether we're inside the body of a `load_spec` function
+    in_load_spec: bool,
+    /// Name of the id argument of the `load_spec` currently being visited
+    id_arg: Option<String>,
+}
+
+impl LoadSpecVisitor {
+    fn visit_load_spec(&mut self, sig: &syn::Signature, block: &syn::Block) {
+        let id_arg = sig.inputs.iter().find_map(|arg| match arg {
+            syn::FnArg::Typed(syn::PatType { pat, .. }) => match pat.as_ref() {
+                syn::Pat::Ident(ident) => Some(ident.ident.to_string()),
+                _ => None,
+            },
+            syn::FnArg::Receiver(_) => None,
+        });
+
+        self.in_load_spec = true;
+        self.id_arg = id_arg;
+        self.visit_block(block);
+        self.in_load_spec = false;
+        self.id_arg = None;
+    }
+
+    /// Whether `expr` is the id argument, e.g. `id`, `&id` or `id.as_str()`.
+    fn is_id_expr(&self, expr: &syn::Expr) -> bool {
+        if !self.in_load_spec {
+            return false;
+        }
+        let Some(id_arg) = &self.id_arg else {
+            // Unnamed argument, any match inside `load_spec` may be the one
+            return true;
+        };
+
+        match expr {
+            syn::Expr::Path(path) => path.path.is_ident(id_arg),
+            syn::Expr::Reference(reference) => self.is_id_expr(&reference.expr),
+            syn::Expr::Paren(paren) => self.is_id_expr(&paren.expr),
+            syn::Expr::MethodCall(call) => self.is_id_expr(&call.receiver),
+            _ => false,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for LoadSpecVisitor {
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        if item.sig.ident == "load_spec" {
+            self.visit_load_spec(&item.sig, &item.block);
+        } else {
+            visit::visit_item_fn(self, item);
+        }
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast syn::ImplItemFn) {
+        if item.sig.ident == "load_spec" {
+            self.visit_load_spec(&item.sig, &item.block);
+        } else {
+            visit::visit_impl_item_fn(self, item);
+        }
+    }
+
+    fn visit_expr_match(&mut self, expr: &'ast syn::ExprMatch) {
+        if self.is_id_expr(&expr.expr) {
+            for arm in &expr.arms {
+                self.specs.collect_pattern(&arm.pat);
+            }
+        }
+        visit::visit_expr_match(self, expr);
+    }
+}
+
+/// Extracts the chain-specifications supported by a node from the source of its `command.rs`.
+pub fn extract_chain_specs(content: &str) -> SubstrateResult<ChainSpecs> {
+    let file = syn::parse_file(content).with_context(|| "couldn't parse the node's command.rs")?;
+
+    let mut visitor = LoadSpecVisitor::default();
+    visitor.visit_file(&file);
+
+    Ok(visitor.specs)
+}
+
+/// Path of the node binary produced by a release build of the chain.
+pub fn node_binary_path(config: &Config, chain: &ChainInfo) -> SubstrateResult<PathBuf> {
+    Ok(config.cwd().join("target/release").join(node_binary_name(config, chain)?))
+}
+
+/// Name of the node's binary target.
+pub(crate) fn node_binary_name(config: &Config, chain: &ChainInfo) -> SubstrateResult<String> {
+    let node_document = Manifest::new(config.cwd().join(&chain.node_path).join("Cargo.toml"))
+        .read_document()
+        .with_context(|| "couldn't read the node's Cargo.toml")?;
+
+    // The binary name defaults to the package name unless a `[[bin]]` target renames it
+    node_document
+        .get("bin")
+        .and_then(|bin| bin.get(0))
+        .and_then(|bin| bin.get("name"))
+        .and_then(|name| name.as_str())
+        .map(|name| name.to_string())
+        .or_else(|| chain.node_name.clone())
+        .with_context(|| "couldn't determine the node's binary name")
+}
+
+/// Returns the path of the node binary, building the node first if it hasn't been built yet.
+pub fn ensure_node_binary(config: &Config, chain: &ChainInfo) -> SubstrateResult<PathBuf> {
+    let binary = node_binary_path(config, chain)?;
+    if binary.exists() {
+        return Ok(binary);
+    }
+
+    println!("Node binary not found, building the node...\n");
+    let mut command = Command::new("cargo");
+    command.current_dir(config.cwd()).args(["+nightly", "build", "--release"]);
+    if let Some(node_name) = &chain.node_name {
+        command.args(["-p", node_name]);
+    }
+    let status = command.status()?;
+    if !status.success() {
+        anyhow::bail!("failed to build the node");
+    }
+
+    Ok(binary)
+}
+
 pub fn run(opts: &RunOptions) -> SubstrateResult<()> {
     let mut args = vec!["+nightly", "run", "--release", "--"];
     if opts.chain == "dev" {
@@ -22,3 +198,133 @@ pub fn run(opts: &RunOptions) -> SubstrateResult<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ids(specs: &ChainSpecs) -> Vec<&str> {
+        specs.ids.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn test_extract_chain_specs_substrate() {
+        let content = r#"
+            impl SubstrateCli for Cli {
+                fn impl_name() -> String {
+                    "Substrate Node".into()
+                }
+
+                fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+                    Ok(match id {
+                        "dev" => Box::new(chain_spec::development_config()?),
+                        "" | "local" => Box::new(chain_spec::local_testnet_config()?),
+                        path =>
+                            Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(path))?),
+                    })
+                }
+            }
+        "#;
+
+        let specs = extract_chain_specs(content).unwrap();
+        assert_eq!(ids(&specs), ["dev", "local"]);
+        assert!(specs.accepts_path);
+    }
+
+    #[test]
+    fn test_extract_chain_specs_cumulus() {
+        let content = r#"
+            fn load_spec(id: &str) -> std::result::Result<Box<dyn ChainSpec>, String> {
+                Ok(match id {
+                    "dev" => Box::new(chain_spec::development_config()),
+                    "template-rococo" => Box::new(chain_spec::local_testnet_config()),
+                    "" | "local" => Box::new(chain_spec::local_testnet_config()),
+                    path => Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(path))?),
+                })
+            }
+
+            impl SubstrateCli for Cli {
+                fn load_spec(&self, id: &str) -> std::result::Result<Box<dyn sc_service::ChainSpec>, String> {
+                    load_spec(id)
+                }
+            }
+
+            impl SubstrateCli for RelayChainCli {
+                fn load_spec(&self, id: &str) -> std::result::Result<Box<dyn sc_service::ChainSpec>, String> {
+                    polkadot_cli::Cli::from_iter([RelayChainCli::executable_name()].iter()).load_spec(id)
+                }
+            }
+        "#;
+
+        let specs = extract_chain_specs(content).unwrap();
+        assert_eq!(ids(&specs), ["dev", "template-rococo", "local"]);
+        assert!(specs.accepts_path);
+    }
+
+    #[test]
+    fn test_extract_chain_specs_frontier() {
+        let content = r#"
+            impl SubstrateCli for Cli {
+                fn load_spec(&self, id: &str) -> Result<Box<dyn ChainSpec>, String> {
+                    Ok(match id {
+                        "dev" => {
+                            let enable_manual_seal = self.sealing.map(|_| true);
+                            Box::new(chain_spec::development_config(enable_manual_seal))
+                        }
+                        "" | "local" => Box::new(chain_spec::local_testnet_config()),
+                        path => Box::new(chain_spec::ChainSpec::from_json_file(
+                            std::path::PathBuf::from(path),
+                        )?),
+                    })
+                }
+            }
+        "#;
+
+        let specs = extract_chain_specs(content).unwrap();
+        assert_eq!(ids(&specs), ["dev", "local"]);
+        assert!(specs.accepts_path);
+    }
+
+    #[test]
+    fn test_extract_chain_specs_canvas() {
+        let content = r#"
+            fn load_spec(id: &str) -> std::result::Result<Box<dyn sc_service::ChainSpec>, String> {
+                Ok(match id {
+                    "dev" => Box::new(chain_spec::development_config()),
+                    "rococo" => Box::new(chain_spec::rococo_config()),
+                    "" | "local" => Box::new(chain_spec::local_testnet_config()),
+                    path => Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(path))?),
+                })
+            }
+        "#;
+
+        let specs = extract_chain_specs(content).unwrap();
+        assert_eq!(ids(&specs), ["dev", "rococo", "local"]);
+        assert!(specs.accepts_path);
+    }
+
+    #[test]
+    fn test_extract_chain_specs_boxed_let_binding_and_wildcard() {
+        let content = r#"
+            impl SubstrateCli for Cli {
+                fn load_spec(&self, id: &str) -> Result<Box<dyn ChainSpec>, String> {
+                    let spec = Box::new(match id.as_str() {
+                        "dev" => chain_spec::development_config(),
+                        consts::STAGING => chain_spec::staging_config(),
+                        Chain::Kusama(_) => chain_spec::kusama_config(),
+                        _ => return Err(format!("Unknown chain: {}", id)),
+                    });
+                    let unrelated = match self.sealing {
+                        Some(sealing) => sealing,
+                        None => Sealing::Instant,
+                    };
+                    Ok(spec)
+                }
+            }
+        "#;
+
+        let specs = extract_chain_specs(content).unwrap();
+        assert_eq!(ids(&specs), ["dev", "staging"]);
+        assert!(!specs.accepts_path);
+    }
+}
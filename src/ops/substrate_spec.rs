@@ -0,0 +1,255 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::Context as _;
+use serde_json::Value;
+
+use crate::{
+    core::chain_spec::{self, ChainSpec},
+    ops::substrate_run::ensure_node_binary,
+    util::{config::ChainInfo, Config, SubstrateResult},
+};
+
+/// Directory, relative to the project root, where chain specs are stored.
+pub const SPECS_DIR: &str = "specs";
+
+pub struct BuildSpecOptions {
+    /// Chain-specification id (e.g. `local`) or path to a chain spec file
+    pub chain: String,
+    /// Also produce the raw version of the spec
+    pub raw: bool,
+}
+
+pub struct RawSpecOptions {
+    pub spec: PathBuf,
+}
+
+pub struct PatchSpecOptions {
+    pub spec: PathBuf,
+    /// `target=value` assignments, see [`apply_assignment`]
+    pub assignments: Vec<String>,
+}
+
+pub struct DiffSpecOptions {
+    pub old: PathBuf,
+    pub new: PathBuf,
+}
+
+/// Runs `command`, writing its standard output to `output`.
+pub(crate) fn capture_to_file(command: &mut Command, output: &Path, what: &str) -> SubstrateResult<()> {
+    let result = command.output()?;
+    if !result.status.success() {
+        anyhow::bail!(
+            "failed to {}: {}",
+            what,
+            String::from_utf8_lossy(&result.stderr).trim()
+        );
+    }
+    fs::write(output, result.stdout)?;
+
+    Ok(())
+}
+
+/// Runs the node's `build-spec` command for `chain`, writing the spec to `output`.
+pub(crate) fn build_spec(binary: &Path, chain: &Path, output: &Path, raw: bool) -> SubstrateResult<()> {
+    let mut command = Command::new(binary);
+    command
+        .arg("build-spec")
+        .arg("--chain")
+        .arg(chain)
+        .arg("--disable-default-bootnode");
+    if raw {
+        command.arg("--raw");
+    }
+
+    capture_to_file(&mut command, output, "build the chain spec")
+}
+
+/// Chain specs stored in the project's `specs` directory.
+pub fn list_specs(config: &Config) -> SubstrateResult<Vec<PathBuf>> {
+    let specs_dir = config.cwd().join(SPECS_DIR);
+    if !specs_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut specs = fs::read_dir(specs_dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    specs.sort();
+
+    Ok(specs)
+}
+
+/// Path of the raw version of the plain spec at `path`, e.g. `local.json` -> `local-raw.json`.
+fn raw_spec_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}-raw.json", stem))
+}
+
+pub fn build(opts: &BuildSpecOptions, config: &Config, chain: &ChainInfo) -> SubstrateResult<PathBuf> {
+    let binary = ensure_node_binary(config, chain)?;
+
+    let specs_dir = config.cwd().join(SPECS_DIR);
+    fs::create_dir_all(&specs_dir)?;
+
+    // A spec file is named after its file stem, built-in specs after their id
+    let name = Path::new(&opts.chain)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or_else(|| "local".to_string());
+    let mut output = specs_dir.join(format!("{}.json", name));
+    // The node truncates its output before reading the spec, which mustn't be the same file
+    if let (Ok(input), Ok(existing)) = (fs::canonicalize(&opts.chain), fs::canonicalize(&output)) {
+        if input == existing {
+            output = specs_dir.join(format!("{}-rebuilt.json", name));
+        }
+    }
+
+    build_spec(&binary, Path::new(&opts.chain), &output, false)?;
+    println!("Chain spec written to `{}`", output.display());
+
+    if opts.raw {
+        let raw_output = raw_spec_path(&output);
+        build_spec(&binary, &output, &raw_output, true)?;
+        println!("Raw chain spec written to `{}`", raw_output.display());
+    }
+
+    Ok(output)
+}
+
+pub fn raw(opts: &RawSpecOptions, config: &Config, chain: &ChainInfo) -> SubstrateResult<PathBuf> {
+    let spec = ChainSpec::read(opts.spec.clone())?;
+    if spec.is_raw() {
+        anyhow::bail!("`{}` is already a raw chain spec", opts.spec.display());
+    }
+
+    let binary = ensure_node_binary(config, chain)?;
+    let output = raw_spec_path(&opts.spec);
+    build_spec(&binary, &opts.spec, &output, true)?;
+    println!("Raw chain spec written to `{}`", output.display());
+
+    Ok(output)
+}
+
+/// Applies a `target=value` assignment to a plain chain spec.
+///
+/// `target` is either a JSON pointer from the root of the spec
+/// (e.g. `/genesis/runtime/sudo/key`) or one of the shorthands `para_id`,
+/// `sudo`, `balances` and `session_keys`. `value` is parsed as JSON, falling
+/// back to a plain string (so that addresses don't need quoting). Numbers keep
+/// their exact text, so balances above `u64::MAX` don't lose precision.
+pub fn apply_assignment(spec: &mut ChainSpec, assignment: &str) -> SubstrateResult<()> {
+    let (target, raw_value) = assignment
+        .split_once('=')
+        .with_context(|| format!("`{}` should be of the form `target=value`", assignment))?;
+    let value = serde_json::from_str::<Value>(raw_value)
+        .unwrap_or_else(|_| Value::String(raw_value.to_string()));
+
+    match target {
+        pointer if pointer.starts_with('/') => spec.set(pointer, value),
+        "para_id" => {
+            let para_id = value
+                .as_u64()
+                .and_then(|id| u32::try_from(id).ok())
+                .with_context(|| format!("`{}` is not a valid para id", raw_value))?;
+            spec.set_para_id(para_id)
+        }
+        "sudo" => spec.set_genesis("/sudo/key", value),
+        "balances" => spec.set_genesis("/balances/balances", value),
+        "session_keys" => spec.set_genesis("/session/keys", value),
+        _ => anyhow::bail!(
+            "unknown target `{}`, expected a JSON pointer or one of `para_id`, `sudo`, `balances`, `session_keys`",
+            target
+        ),
+    }
+}
+
+pub fn patch(opts: &PatchSpecOptions) -> SubstrateResult<()> {
+    let mut spec = ChainSpec::read(opts.spec.clone())?;
+    if spec.is_raw() {
+        anyhow::bail!(
+            "`{}` is a raw chain spec, patch the plain spec and convert it again",
+            opts.spec.display()
+        );
+    }
+
+    for assignment in &opts.assignments {
+        apply_assignment(&mut spec, assignment)?;
+    }
+    spec.write()?;
+    println!(
+        "Applied {} change(s) to `{}`",
+        opts.assignments.len(),
+        opts.spec.display()
+    );
+
+    Ok(())
+}
+
+pub fn diff(opts: &DiffSpecOptions) -> SubstrateResult<()> {
+    let old = ChainSpec::read(opts.old.clone())?;
+    let new = ChainSpec::read(opts.new.clone())?;
+
+    let differences = chain_spec::diff(old.json(), new.json());
+    if differences.is_empty() {
+        println!("The chain specs are identical");
+    }
+    for difference in differences {
+        println!("{}", difference);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_apply_assignment() {
+        let path = std::env::temp_dir().join("substrate-manager-test-apply-assignment.json");
+        fs::write(
+            &path,
+            json!({ "para_id": 1000, "genesis": { "runtime": { "sudo": { "key": null } } } })
+                .to_string(),
+        )
+        .unwrap();
+        let mut spec = ChainSpec::read(path.clone()).unwrap();
+        fs::remove_file(path).unwrap();
+
+        apply_assignment(&mut spec, "sudo=5GrwvaEF").unwrap();
+        apply_assignment(&mut spec, "para_id=2000").unwrap();
+        apply_assignment(&mut spec, r#"balances=[["5GrwvaEF", 1000]]"#).unwrap();
+        apply_assignment(&mut spec, "/name=Local Testnet").unwrap();
+
+        assert_eq!(
+            spec.json(),
+            &json!({
+                "name": "Local Testnet",
+                "para_id": 2000,
+                "genesis": { "runtime": {
+                    "sudo": { "key": "5GrwvaEF" },
+                    "parachainInfo": { "parachainId": 2000 },
+                    "balances": { "balances": [["5GrwvaEF", 1000]] },
+                } },
+            })
+        );
+
+        apply_assignment(&mut spec, r#"/genesis/runtime/balances/balances=[["5GrwvaEF",1000000000000000000000]]"#).unwrap();
+        assert_eq!(
+            spec.json()["genesis"]["runtime"]["balances"].to_string(),
+            r#"{"balances":[["5GrwvaEF",1000000000000000000000]]}"#
+        );
+
+        assert!(apply_assignment(&mut spec, "sudo").is_err());
+        assert!(apply_assignment(&mut spec, "aura=[]").is_err());
+    }
+}
@@ -1,15 +1,236 @@
-use std::process::Command;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
-use anyhow::Ok;
+use anyhow::Context as _;
 
-use crate::util::{Config, SubstrateResult};
+use strum::Display;
 
-pub struct DevOptions {
-    pub debug: bool,
+use crate::{
+    core::{
+        test_report::{TestOutcome, TestReport},
+        Shell,
+    },
+    ops::substrate_contracts_node::{
+        contracts_node_binary, load_contracts_node_config, running_node_url, LocalContractsNode,
+    },
+    util::{
+        config::{ContractMember, ProjectType},
+        process::run_with_output,
+        to_snake_case, Config, SubstrateResult,
+    },
+};
+
+/// Feature gating the ink! end-to-end tests, by convention of the ink! templates.
+const E2E_FEATURE: &str = "e2e-tests";
+
+/// Module holding the ink! end-to-end tests, by convention of the ink! templates.
+const E2E_MODULE: &str = "e2e_tests";
+
+/// Which tests of a contract to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum TestMode {
+    /// Off-chain unit tests only
+    Unit,
+    /// End-to-end tests only, against a local contracts node
+    E2e,
+    /// Unit and end-to-end tests
+    All,
+}
+
+pub struct TestOptions {
+    /// Packages to test, e.g. some contracts of a workspace, all of them if empty
+    pub packages: Vec<String>,
+    /// Only used by contract projects
+    pub mode: TestMode,
+    /// Only run the tests whose name contains this filter
+    pub filter: Option<String>,
+    pub release: bool,
+    pub features: Vec<String>,
+    /// Compile the tests without running them
+    pub no_run: bool,
+    /// Where to write a JUnit XML report of the tests, relative to the current directory
+    pub junit: Option<PathBuf>,
+}
+
+impl TestOptions {
+    /// Adds the cargo flags shared by every test run, up to the test binary's arguments.
+    fn apply(&self, command: &mut Command) {
+        // Run the tests of every crate even if some fail, for the summary
+        command.arg("--no-fail-fast");
+        if self.release {
+            command.arg("--release");
+        }
+        if self.no_run {
+            command.arg("--no-run");
+        }
+        if !self.features.is_empty() {
+            command.args(["--features", &self.features.join(",")]);
+        }
+    }
+}
+
+/// The crate of each target of the packages of `manifest_path`, from `cargo metadata`, so that the
+/// report groups integration tests with their crate. Empty if cargo can't tell.
+fn target_crates(config: &Config, manifest_path: &Path) -> Vec<(String, String)> {
+    let Ok(output) = Command::new("cargo")
+        .current_dir(config.cwd())
+        .args(["metadata", "--no-deps", "--format-version", "1", "--manifest-path"])
+        .arg(manifest_path)
+        .output()
+    else {
+        return Vec::new();
+    };
+    let Ok(metadata) = serde_json::from_slice::<serde_json::Value>(&output.stdout) else {
+        return Vec::new();
+    };
+
+    let mut target_crates = Vec::new();
+    for package in metadata["packages"].as_array().into_iter().flatten() {
+        let crate_name = package["name"].as_str().unwrap_or_default().replace('-', "_");
+        for target in package["targets"].as_array().into_iter().flatten() {
+            if let Some(target) = target["name"].as_str() {
+                target_crates.push((target.replace('-', "_"), crate_name.clone()));
+            }
+        }
+    }
+    target_crates
 }
 
-pub fn test() -> SubstrateResult<()> {
-    Command::new("cargo").args(["+nightly", "test"]).status()?;
+/// Prints the summary of the tests, writes the JUnit report if asked to, and fails if any test
+/// failed or cargo failed before running them.
+fn finish(opts: &TestOptions, report: &TestReport, errored: &[String], config: &Config) -> SubstrateResult<()> {
+    let mut shell = Shell::new();
+    println!();
+    report.print_summary(&mut shell)?;
+
+    if let Some(junit) = &opts.junit {
+        let path = config.cwd().join(junit);
+        fs::write(&path, report.junit())
+            .with_context(|| format!("failed to write the JUnit report to `{}`", path.display()))?;
+        shell.status("Wrote", format!("JUnit report to {}", path.display()))?;
+    }
 
+    if !errored.is_empty() {
+        anyhow::bail!("cargo test failed for {}", errored.join(", "));
+    }
+    let failed = report.count(TestOutcome::Failed);
+    if failed > 0 {
+        anyhow::bail!("{} test(s) failed", failed);
+    }
     Ok(())
 }
+
+/// Whether the contract declares the feature enabling its end-to-end tests.
+fn has_e2e_tests(config: &Config, contract: &ContractMember) -> bool {
+    let manifest_path = config.cwd().join(&contract.path).join("Cargo.toml");
+    fs::read_to_string(manifest_path)
+        .ok()
+        .and_then(|content| content.parse::<toml_edit::Document>().ok())
+        .is_some_and(|document| {
+            document
+                .get("features")
+                .and_then(|features| features.get(E2E_FEATURE))
+                .is_some()
+        })
+}
+
+fn test_contracts(
+    opts: &TestOptions,
+    contracts: Vec<ContractMember>,
+    config: &Config,
+) -> SubstrateResult<()> {
+    let mut contracts = contracts
+        .into_iter()
+        .map(|contract| {
+            let e2e = opts.mode != TestMode::Unit && has_e2e_tests(config, &contract);
+            (contract, e2e)
+        })
+        .collect::<Vec<_>>();
+    if opts.mode == TestMode::E2e {
+        for (contract, _) in contracts.iter().filter(|(_, e2e)| !e2e) {
+            println!("Skipping `{}`, it has no end-to-end tests", contract.name);
+        }
+        contracts.retain(|(_, e2e)| *e2e);
+        if contracts.is_empty() {
+            anyhow::bail!("no contract declares an `{}` feature", E2E_FEATURE);
+        }
+    }
+
+    // End-to-end tests need a node: the running one, or one started for the tests
+    let mut node = None;
+    let mut node_env = Vec::new();
+    if contracts.iter().any(|(_, e2e)| *e2e) {
+        let node_config = load_contracts_node_config(config)?;
+        let url = match running_node_url(config) {
+            Some(url) => url,
+            None => {
+                let Some(started) = LocalContractsNode::start(config, false)? else {
+                    return Ok(());
+                };
+                node.insert(started).url.clone()
+            }
+        };
+        if let Ok(binary) = contracts_node_binary(config, &node_config) {
+            node_env.push(("CONTRACTS_NODE", binary.to_string_lossy().to_string()));
+        }
+        println!("Running end-to-end tests against {}\n", url);
+        node_env.push(("CONTRACTS_NODE_URL", url));
+    }
+
+    let mut report = TestReport::default();
+    let mut errored = Vec::new();
+    for (contract, e2e) in &contracts {
+        let mut command = Command::new("cargo");
+        command
+            .current_dir(config.cwd())
+            .args(["+nightly", "test", "--manifest-path"])
+            .arg(contract.path.join("Cargo.toml"));
+        opts.apply(&mut command);
+        if *e2e {
+            command.args(["--features", E2E_FEATURE]).envs(node_env.clone());
+        }
+        match (&opts.filter, opts.mode) {
+            (Some(filter), _) => command.args(["--", filter]),
+            (None, TestMode::E2e) => command.args(["--", E2E_MODULE]),
+            _ => &mut command,
+        };
+        report.add_target_crates(target_crates(config, &contract.path.join("Cargo.toml")));
+        report.start_suite(&to_snake_case(&contract.name));
+        if !run_with_output(&mut command, |line| report.parse_line(line))? && report.count(TestOutcome::Failed) == 0 {
+            errored.push(format!("`{}`", contract.name));
+        }
+    }
+    drop(node);
+
+    finish(opts, &report, &errored, config)
+}
+
+pub fn test(opts: &TestOptions, config: &Config) -> SubstrateResult<()> {
+    if let Some(ProjectType::Contract(info)) = &config.project_type {
+        let contracts = info.select(&opts.packages.join(","))?;
+        return test_contracts(opts, contracts, config);
+    }
+
+    let mut command = Command::new("cargo");
+    command.current_dir(config.cwd()).args(["+nightly", "test"]);
+    for package in &opts.packages {
+        command.args(["-p", package]);
+    }
+    opts.apply(&mut command);
+    if let Some(filter) = &opts.filter {
+        command.args(["--", filter]);
+    }
+
+    let mut report = TestReport::default();
+    report.add_target_crates(target_crates(config, &config.cwd().join("Cargo.toml")));
+    let mut errored = Vec::new();
+    if !run_with_output(&mut command, |line| report.parse_line(line))? && report.count(TestOutcome::Failed) == 0 {
+        errored.push("the workspace".to_string());
+    }
+
+    finish(opts, &report, &errored, config)
+}
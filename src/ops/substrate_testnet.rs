@@ -0,0 +1,476 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::Context as _;
+use serde_derive::Deserialize;
+use serde_json::json;
+use toml_edit::{value, ArrayOfTables, Document, Item, Table};
+
+use crate::{
+    core::{chain_spec::ChainSpec, manifest::Manifest},
+    ops::{
+        substrate_network::{
+            base_node_command, bootnode_peer_id, local_multiaddr, NetworkConfig, NetworkOptions,
+            DEFAULT_P2P_PORT, DEFAULT_RPC_PORT,
+        },
+        substrate_run::{ensure_node_binary, node_binary_path},
+        substrate_spec::{build_spec, capture_to_file},
+    },
+    util::{config::ChainInfo, process::ProcessGroup, Config, SubstrateResult},
+};
+
+const DEFAULT_ZOMBIENET_CONFIG: &str = "zombienet.toml";
+
+/// Ports of the collators are offset from the relay chain ones to avoid clashes.
+const COLLATOR_P2P_PORT: u16 = 40333;
+const COLLATOR_RPC_PORT: u16 = 9988;
+const COLLATOR_RELAY_P2P_PORT: u16 = 30433;
+
+/// The subset of the zombienet network definition that is needed to launch a local testnet.
+///
+/// Unknown fields are ignored, so existing zombienet files can be reused as is.
+/// See: https://paritytech.github.io/zombienet/network-definition-spec.html
+#[derive(Debug, Deserialize)]
+pub struct ZombienetConfig {
+    pub relaychain: RelayChainConfig,
+    #[serde(default)]
+    pub parachains: Vec<ParachainConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RelayChainConfig {
+    pub default_command: Option<String>,
+    #[serde(default = "default_relay_chain")]
+    pub chain: String,
+    pub chain_spec_path: Option<PathBuf>,
+    #[serde(default)]
+    pub default_args: Vec<String>,
+    #[serde(default)]
+    pub nodes: Vec<ZombienetNode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ParachainConfig {
+    pub id: u32,
+    pub chain: Option<String>,
+    pub chain_spec_path: Option<PathBuf>,
+    #[serde(default = "default_true")]
+    pub cumulus_based: bool,
+    pub collator: Option<ZombienetNode>,
+    #[serde(default)]
+    pub collators: Vec<ZombienetNode>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ZombienetNode {
+    pub name: String,
+    pub command: Option<String>,
+    #[serde(default = "default_true")]
+    pub validator: bool,
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub rpc_port: Option<u16>,
+    pub ws_port: Option<u16>,
+    pub p2p_port: Option<u16>,
+}
+
+fn default_relay_chain() -> String {
+    "rococo-local".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl ParachainConfig {
+    /// Both the `collator` and `collators` forms of the zombienet spec.
+    fn all_collators(&self) -> Vec<ZombienetNode> {
+        self.collator
+            .iter()
+            .chain(self.collators.iter())
+            .cloned()
+            .collect()
+    }
+}
+
+/// Location of the zombienet network file: `network.zombienet` in `Substrate.toml`
+/// or `zombienet.toml` at the root of the project.
+pub fn zombienet_config_path(cwd: &Path, network: Option<&NetworkConfig>) -> PathBuf {
+    match network.and_then(|network| network.zombienet.as_ref()) {
+        Some(path) => cwd.join(path),
+        None => cwd.join(DEFAULT_ZOMBIENET_CONFIG),
+    }
+}
+
+pub fn load_zombienet_config(path: &Path) -> SubstrateResult<ZombienetConfig> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("couldn't read `{}`", path.display()))?;
+    toml_edit::de::from_str(&content)
+        .with_context(|| format!("`{}` is not a valid zombienet network file", path.display()))
+}
+
+/// Whether the node is a Cumulus-based collator, i.e. it depends on Cumulus client crates.
+pub fn is_parachain_node(config: &Config, chain: &ChainInfo) -> SubstrateResult<bool> {
+    let node_document = Manifest::new(config.cwd().join(&chain.node_path).join("Cargo.toml"))
+        .read_document()?;
+
+    Ok(node_document
+        .get("dependencies")
+        .and_then(|deps| deps.as_table_like())
+        .map(|deps| deps.iter().any(|(name, _)| name.starts_with("cumulus-client")))
+        .unwrap_or(false))
+}
+
+/// A zombienet network with two relay chain validators and a collator run by `collator_command`.
+fn default_zombienet_document(relay_command: &str, collator_command: &str) -> Document {
+    let mut relaychain = Table::new();
+    relaychain.insert("default_command", value(relay_command));
+    relaychain.insert("chain", value("rococo-local"));
+    let mut nodes = ArrayOfTables::new();
+    for name in ["alice", "bob"] {
+        let mut node = Table::new();
+        node.insert("name", value(name));
+        node.insert("validator", value(true));
+        nodes.push(node);
+    }
+    relaychain.insert("nodes", Item::ArrayOfTables(nodes));
+
+    let mut collator = Table::new();
+    collator.insert("name", value("alice"));
+    collator.insert("command", value(collator_command));
+    let mut parachain = Table::new();
+    parachain.insert("id", value(1000));
+    parachain.insert("cumulus_based", value(true));
+    parachain.insert("collator", Item::Table(collator));
+    let mut parachains = ArrayOfTables::new();
+    parachains.push(parachain);
+
+    let mut document = Document::new();
+    document.insert("relaychain", Item::Table(relaychain));
+    document.insert("parachains", Item::ArrayOfTables(parachains));
+    document
+}
+
+/// Writes a zombienet network file with two relay chain validators and the project's collator.
+pub fn write_default_zombienet_config(
+    config: &Config,
+    chain: &ChainInfo,
+    relay_command: &str,
+) -> SubstrateResult<PathBuf> {
+    let collator_binary = node_binary_path(config, chain)?;
+    let collator_command = collator_binary
+        .strip_prefix(config.cwd())
+        .unwrap_or(&collator_binary);
+    let collator_command = Path::new(".").join(collator_command);
+    let collator_command = collator_command
+        .to_str()
+        .with_context(|| format!("`{}` isn't valid UTF-8", collator_command.display()))?;
+
+    let path = config.cwd().join(DEFAULT_ZOMBIENET_CONFIG);
+    fs::write(&path, default_zombienet_document(relay_command, collator_command).to_string())?;
+
+    Ok(path)
+}
+
+/// Resolves a zombienet `command`: paths are relative to the project, bare names are looked up in `PATH`.
+fn resolve_command(cwd: &Path, command: &str) -> SubstrateResult<PathBuf> {
+    if command.contains('/') {
+        let path = cwd.join(command);
+        if !path.exists() {
+            anyhow::bail!("`{}` does not exist", path.display());
+        }
+        Ok(path)
+    } else {
+        which::which(command).with_context(|| {
+            format!(
+                "couldn't find `{}` in PATH, set its location with `default_command` in the zombienet file",
+                command
+            )
+        })
+    }
+}
+
+/// Generates the raw chain spec, genesis state and genesis wasm of a parachain.
+fn prepare_parachain(
+    collator: &Path,
+    parachain: &ParachainConfig,
+    relay_chain: &str,
+    cwd: &Path,
+    work_dir: &Path,
+) -> SubstrateResult<(PathBuf, String, String)> {
+    let id = parachain.id;
+    let plain_spec_path = work_dir.join(format!("para-{}-plain.json", id));
+    let raw_spec_path = work_dir.join(format!("para-{}-raw.json", id));
+
+    match &parachain.chain_spec_path {
+        Some(path) => {
+            fs::copy(cwd.join(path), &plain_spec_path)?;
+        }
+        None => {
+            let chain = parachain.chain.as_deref().unwrap_or("local");
+            build_spec(collator, Path::new(chain), &plain_spec_path, false)?;
+        }
+    }
+
+    let mut spec = ChainSpec::read(plain_spec_path.clone())?;
+    if !spec.is_raw() {
+        spec.set_para_id(id)?;
+        spec.set_relay_chain(relay_chain);
+        spec.write()?;
+    }
+    build_spec(collator, &plain_spec_path, &raw_spec_path, true)?;
+
+    let genesis_state_path = work_dir.join(format!("para-{}-genesis-state", id));
+    capture_to_file(
+        Command::new(collator)
+            .args(["export-genesis-state", "--chain"])
+            .arg(&raw_spec_path),
+        &genesis_state_path,
+        "export the parachain genesis state",
+    )?;
+
+    let genesis_wasm_path = work_dir.join(format!("para-{}-genesis-wasm", id));
+    capture_to_file(
+        Command::new(collator)
+            .args(["export-genesis-wasm", "--chain"])
+            .arg(&raw_spec_path),
+        &genesis_wasm_path,
+        "export the parachain genesis wasm",
+    )?;
+
+    let genesis_state = fs::read_to_string(genesis_state_path)?.trim().to_string();
+    let genesis_wasm = fs::read_to_string(genesis_wasm_path)?.trim().to_string();
+
+    Ok((raw_spec_path, genesis_state, genesis_wasm))
+}
+
+/// Registers a parachain in the relay chain genesis so it is onboarded from block 0.
+fn register_parachain(
+    relay_spec: &mut ChainSpec,
+    id: u32,
+    genesis_state: &str,
+    genesis_wasm: &str,
+    cumulus_based: bool,
+) -> SubstrateResult<()> {
+    let genesis = relay_spec
+        .runtime_genesis_mut()
+        .with_context(|| "cannot register a parachain in a raw relay chain spec")?;
+    let paras = genesis
+        .pointer_mut("/paras/paras")
+        .and_then(|paras| paras.as_array_mut())
+        .with_context(|| "the relay chain spec has no `paras` genesis config")?;
+
+    paras.retain(|para| para[0] != json!(id));
+    paras.push(json!([
+        id,
+        {
+            "genesis_head": genesis_state,
+            "validation_code": genesis_wasm,
+            "para_kind": cumulus_based,
+        }
+    ]));
+
+    Ok(())
+}
+
+/// Launches a relay chain and the collators of its parachains, as described by a zombienet file.
+pub fn testnet(
+    opts: &NetworkOptions,
+    config: &Config,
+    chain: &ChainInfo,
+    zombienet_path: &Path,
+    base_path: &Path,
+) -> SubstrateResult<()> {
+    let cwd = config.cwd();
+    let zombienet = load_zombienet_config(zombienet_path)?;
+    let relay = &zombienet.relaychain;
+    if relay.nodes.is_empty() {
+        anyhow::bail!("no relay chain nodes declared in `{}`", zombienet_path.display());
+    }
+
+    let work_dir = cwd.join(base_path);
+    if opts.purge && work_dir.exists() {
+        fs::remove_dir_all(&work_dir)?;
+    }
+    fs::create_dir_all(&work_dir)?;
+
+    let relay_command = resolve_command(
+        cwd,
+        relay.default_command.as_deref().unwrap_or("polkadot"),
+    )?;
+
+    println!("Generating the relay chain spec...");
+    let relay_plain_spec_path = work_dir.join("relay-plain.json");
+    let relay_raw_spec_path = work_dir.join("relay-raw.json");
+    match &relay.chain_spec_path {
+        Some(path) => {
+            fs::copy(cwd.join(path), &relay_plain_spec_path)?;
+        }
+        None => build_spec(
+            &relay_command,
+            Path::new(&relay.chain),
+            &relay_plain_spec_path,
+            false,
+        )?,
+    }
+    let mut relay_spec = ChainSpec::read(relay_plain_spec_path.clone())?;
+
+    let mut parachains = Vec::new();
+    for parachain in &zombienet.parachains {
+        let collators = parachain.all_collators();
+        let collator_command = match collators.iter().find_map(|c| c.command.as_deref()) {
+            Some(command) => resolve_command(cwd, command)?,
+            None => ensure_node_binary(config, chain)?,
+        };
+
+        println!("Generating genesis state and wasm of parachain {}...", parachain.id);
+        let (raw_spec_path, genesis_state, genesis_wasm) = prepare_parachain(
+            &collator_command,
+            parachain,
+            &relay.chain,
+            cwd,
+            &work_dir,
+        )?;
+        register_parachain(
+            &mut relay_spec,
+            parachain.id,
+            &genesis_state,
+            &genesis_wasm,
+            parachain.cumulus_based,
+        )?;
+
+        parachains.push((parachain.id, collator_command, raw_spec_path, collators));
+    }
+
+    relay_spec.write()?;
+    build_spec(
+        &relay_command,
+        &relay_plain_spec_path,
+        &relay_raw_spec_path,
+        true,
+    )?;
+
+    let prefix_width = relay
+        .nodes
+        .iter()
+        .map(|n| n.name.len())
+        .chain(parachains.iter().flat_map(|(id, _, _, collators)| {
+            collators
+                .iter()
+                .map(move |c| format!("{}/{}", id, c.name).len())
+        }))
+        .max()
+        .unwrap_or(0);
+    let mut group = ProcessGroup::new(prefix_width);
+
+    let relay_bootnode_path = work_dir.join(&relay.nodes[0].name);
+    fs::create_dir_all(&relay_bootnode_path)?;
+    let relay_peer_id = bootnode_peer_id(&relay_command, &relay_bootnode_path.join("node-key"))?;
+    let relay_bootnode = local_multiaddr(
+        relay.nodes[0].p2p_port.unwrap_or(DEFAULT_P2P_PORT),
+        &relay_peer_id,
+    );
+
+    println!("\nStarting {} relay chain nodes...", relay.nodes.len());
+    for (index, node) in relay.nodes.iter().enumerate() {
+        let index = index as u16;
+        let node_command = match &node.command {
+            Some(command) => resolve_command(cwd, command)?,
+            None => relay_command.clone(),
+        };
+        let mut command = base_node_command(
+            &node_command,
+            &node.name,
+            relay_raw_spec_path.as_os_str(),
+            &work_dir.join(&node.name),
+            node.p2p_port.unwrap_or(DEFAULT_P2P_PORT + index),
+            node.rpc_port
+                .or(node.ws_port)
+                .unwrap_or(DEFAULT_RPC_PORT + index),
+            node.validator,
+            (index > 0).then_some(relay_bootnode.as_str()),
+        );
+        command.args(&relay.default_args).args(&node.args);
+        group.spawn(&node.name, &mut command)?;
+    }
+
+    let mut collator_index = 0;
+    for (id, collator_command, raw_spec_path, collators) in &parachains {
+        let para_dir = work_dir.join(id.to_string());
+        let mut para_bootnode = None;
+
+        println!("Starting {} collators of parachain {}...", collators.len(), id);
+        for collator in collators {
+            let collator_path = para_dir.join(&collator.name);
+            fs::create_dir_all(&collator_path)?;
+
+            let p2p_port = collator
+                .p2p_port
+                .unwrap_or(COLLATOR_P2P_PORT + collator_index);
+            let mut command = base_node_command(
+                collator_command,
+                &collator.name,
+                raw_spec_path.as_os_str(),
+                &collator_path,
+                p2p_port,
+                collator
+                    .rpc_port
+                    .or(collator.ws_port)
+                    .unwrap_or(COLLATOR_RPC_PORT + collator_index),
+                false,
+                para_bootnode.as_deref(),
+            );
+            command
+                .args(["--collator", "--force-authoring"])
+                .args(&collator.args)
+                // Arguments of the embedded relay chain node
+                .arg("--")
+                .arg("--chain")
+                .arg(&relay_raw_spec_path)
+                .arg("--port")
+                .arg((COLLATOR_RELAY_P2P_PORT + collator_index).to_string())
+                .args(["--bootnodes", &relay_bootnode]);
+
+            if para_bootnode.is_none() {
+                let peer_id = bootnode_peer_id(collator_command, &collator_path.join("node-key"))?;
+                para_bootnode = Some(local_multiaddr(p2p_port, &peer_id));
+            }
+
+            group.spawn(&format!("{}/{}", id, collator.name), &mut command)?;
+            collator_index += 1;
+        }
+    }
+    println!("Press Ctrl-C to stop the network.\n");
+
+    group.wait()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_zombienet_config() {
+        let relay_command = r#"C:\polkadot "nightly"\polkadot"#;
+        let collator_command = "./target/release/demo-node";
+        let document = default_zombienet_document(relay_command, collator_command);
+
+        let path = std::env::temp_dir().join("substrate-manager-test-zombienet.toml");
+        fs::write(&path, document.to_string()).unwrap();
+        let config = load_zombienet_config(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(config.relaychain.default_command.as_deref(), Some(relay_command));
+        assert_eq!(config.relaychain.chain, "rococo-local");
+        let validators = config.relaychain.nodes.iter().map(|node| node.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(validators, ["alice", "bob"]);
+        assert_eq!(config.parachains.len(), 1);
+        assert_eq!(config.parachains[0].id, 1000);
+        let collators = config.parachains[0].all_collators();
+        assert_eq!(collators.len(), 1);
+        assert_eq!(collators[0].command.as_deref(), Some(collator_command));
+    }
+}
@@ -0,0 +1,328 @@
+use std::{
+    fmt, fs,
+    path::PathBuf,
+    process::Command,
+};
+
+use anyhow::Context as _;
+use termcolor::Color::{Green, Red};
+
+use crate::{
+    core::Shell,
+    ops::substrate_build::find_runtime_wasm,
+    util::{config::ChainInfo, process::run_with_output, Config, SubstrateResult},
+};
+
+pub const SNAPSHOTS_DIR: &str = "snapshots";
+
+const TRY_RUNTIME_BINARY: &str = "try-runtime";
+
+/// Target directory of the try-runtime builds, so that they don't replace the runtime of release builds.
+const TRY_RUNTIME_TARGET_DIR: &str = "target/try-runtime";
+
+/// Block time of the chain in milliseconds, as in the node templates.
+const BLOCK_TIME: &str = "6000";
+
+pub struct SnapshotOptions {
+    /// RPC endpoint of the running node
+    pub uri: String,
+    /// Name of the snapshot file in `snapshots/`, without extension
+    pub name: String,
+}
+
+pub struct OnRuntimeUpgradeOptions {
+    pub snapshot: PathBuf,
+}
+
+/// The weight consumed by a migration, or by the whole upgrade.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Weight {
+    pub ref_time: u64,
+    pub proof_size: u64,
+}
+
+impl fmt::Display for Weight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ref_time {}, proof_size {}", self.ref_time, self.proof_size)
+    }
+}
+
+/// A pallet whose on-chain storage version differs from the one of the new runtime.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Migration {
+    pub pallet: String,
+    pub on_chain_version: String,
+    pub new_version: String,
+    /// Only known when the migration logs it
+    pub weight: Option<Weight>,
+    /// Errors of its `pre_upgrade` and `post_upgrade` checks
+    pub errors: Vec<String>,
+}
+
+/// Results of an `on-runtime-upgrade` run, built from its output.
+#[derive(Debug, Default)]
+pub struct UpgradeReport {
+    pub migrations: Vec<Migration>,
+    pub weight: Option<Weight>,
+    /// Errors that don't name a migrating pallet
+    pub errors: Vec<String>,
+}
+
+/// Parses the number following `key` in `line`, e.g. `ref_time: 1000`.
+fn parse_field(line: &str, key: &str) -> Option<u64> {
+    let rest = &line[line.find(key)? + key.len()..];
+    let digits = rest
+        .trim_start_matches([':', '=', ' '])
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '_' || *c == ',')
+        .filter(char::is_ascii_digit)
+        .collect::<String>();
+    digits.parse().ok()
+}
+
+/// Parses a weight printed by the runtime or try-runtime, e.g. `Weight { ref_time: 1000, proof_size: 0 }`.
+fn parse_weight(line: &str) -> Option<Weight> {
+    Some(Weight {
+        ref_time: parse_field(line, "ref_time")?,
+        proof_size: parse_field(line, "proof_size").unwrap_or_default(),
+    })
+}
+
+/// Parses the pallet's `declares internal migrations` log, e.g.
+/// ``⚠️ TemplateModule declares internal migrations (which *might* execute). On-chain `StorageVersion(0)` vs in-code storage version `StorageVersion(1)` ``.
+fn parse_migration(line: &str) -> Option<Migration> {
+    let (before, after) = line.split_once(" declares internal migrations")?;
+    let pallet = before.split_whitespace().last()?.to_string();
+    let mut versions = after.split("StorageVersion(").skip(1).filter_map(|rest| {
+        rest.split_once(')').map(|(version, _)| version.to_string())
+    });
+
+    Some(Migration {
+        pallet,
+        on_chain_version: versions.next()?,
+        new_version: versions.next()?,
+        ..Default::default()
+    })
+}
+
+fn is_error(line: &str) -> bool {
+    line.contains("ERROR") || line.contains("Error:") || line.contains("panicked at")
+}
+
+impl UpgradeReport {
+    pub fn parse_line(&mut self, line: &str) {
+        if let Some(migration) = parse_migration(line) {
+            if !self.migrations.iter().any(|m| m.pallet == migration.pallet) {
+                self.migrations.push(migration);
+            }
+            return;
+        }
+
+        let migration = self
+            .migrations
+            .iter_mut()
+            .find(|migration| line.contains(&migration.pallet));
+        if is_error(line) {
+            let error = line.trim().to_string();
+            match migration {
+                Some(migration) => migration.errors.push(error),
+                None => self.errors.push(error),
+            }
+        } else if let Some(weight) = parse_weight(line) {
+            match migration {
+                Some(migration) => migration.weight = Some(weight),
+                // try-runtime reports the weight of the whole upgrade, the first time without the
+                // checks' own reads and writes
+                None if self.weight.is_none() => self.weight = Some(weight),
+                None => {}
+            }
+        }
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.errors.len() + self.migrations.iter().map(|m| m.errors.len()).sum::<usize>()
+    }
+
+    /// Prints each migration with its weight and check results, then the upgrade's weight.
+    pub fn print(&self, shell: &mut Shell) -> SubstrateResult<()> {
+        if self.migrations.is_empty() {
+            shell.note("no pallet declares a storage migration")?;
+        }
+        for migration in &self.migrations {
+            let weight = migration
+                .weight
+                .map_or_else(|| "weight not reported".to_string(), |weight| weight.to_string());
+            let message = format!(
+                "{} storage version {} -> {}, {}",
+                migration.pallet, migration.on_chain_version, migration.new_version, weight
+            );
+            if migration.errors.is_empty() {
+                shell.status_with_color("Passed", message, Green)?;
+            } else {
+                shell.status_with_color("Failed", message, Red)?;
+                for error in &migration.errors {
+                    println!("    {}", error);
+                }
+            }
+        }
+        for error in &self.errors {
+            shell.error(error)?;
+        }
+        if let Some(weight) = self.weight {
+            shell.status("Weight", format!("of the upgrade: {}", weight))?;
+        }
+        Ok(())
+    }
+}
+
+fn try_runtime_binary() -> SubstrateResult<PathBuf> {
+    which::which(TRY_RUNTIME_BINARY).with_context(|| {
+        format!(
+            "`{}` not found, install it with `cargo install --git https://github.com/paritytech/try-runtime-cli --locked`",
+            TRY_RUNTIME_BINARY
+        )
+    })
+}
+
+/// The snapshots in `snapshots/`, in name order.
+pub fn list_snapshots(config: &Config) -> SubstrateResult<Vec<PathBuf>> {
+    let snapshots_dir = config.cwd().join(SNAPSHOTS_DIR);
+    if !snapshots_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots = fs::read_dir(snapshots_dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "snap"))
+        .collect::<Vec<_>>();
+    snapshots.sort();
+
+    Ok(snapshots)
+}
+
+/// Saves the state of a running node into `snapshots/<name>.snap`, to test upgrades against offline.
+pub fn create_snapshot(opts: &SnapshotOptions, config: &Config) -> SubstrateResult<PathBuf> {
+    let binary = try_runtime_binary()?;
+    let snapshots_dir = config.cwd().join(SNAPSHOTS_DIR);
+    fs::create_dir_all(&snapshots_dir)?;
+    let snapshot = snapshots_dir.join(format!("{}.snap", opts.name));
+
+    let status = Command::new(binary)
+        .current_dir(config.cwd())
+        .args(["--runtime", "existing", "create-snapshot", "--uri", &opts.uri])
+        .arg(&snapshot)
+        .status()?;
+    if !status.success() {
+        anyhow::bail!("failed to create a snapshot of the node at {}", opts.uri);
+    }
+    println!("\nSnapshot saved to `{}`", snapshot.display());
+
+    Ok(snapshot)
+}
+
+/// Builds the runtime with the `try-runtime` feature, returning the path of its wasm.
+fn build_try_runtime(config: &Config, chain: &ChainInfo) -> SubstrateResult<PathBuf> {
+    let mut command = Command::new("cargo");
+    command
+        .current_dir(config.cwd())
+        .args(["+nightly", "build", "--release", "--features", "try-runtime"])
+        .args(["--target-dir", TRY_RUNTIME_TARGET_DIR]);
+    if let Some(runtime_name) = &chain.runtime_name {
+        command.args(["-p", runtime_name]);
+    }
+    let status = command.status()?;
+    if !status.success() {
+        anyhow::bail!("failed to build the runtime with the `try-runtime` feature");
+    }
+
+    find_runtime_wasm(&config.cwd().join(TRY_RUNTIME_TARGET_DIR), chain, "release")
+}
+
+/// Runs the runtime upgrade and its migrations' checks against a snapshot, without any node.
+pub fn on_runtime_upgrade(opts: &OnRuntimeUpgradeOptions, config: &Config, chain: &ChainInfo) -> SubstrateResult<()> {
+    let binary = try_runtime_binary()?;
+    let snapshot = config.cwd().join(&opts.snapshot);
+    if !snapshot.is_file() {
+        anyhow::bail!(
+            "snapshot `{}` not found, create one from a running node first",
+            opts.snapshot.display()
+        );
+    }
+    let wasm = build_try_runtime(config, chain)?;
+
+    let mut command = Command::new(binary);
+    command
+        .current_dir(config.cwd())
+        .arg("--runtime")
+        .arg(&wasm)
+        .args(["on-runtime-upgrade", "--checks", "all", "--blocktime", BLOCK_TIME, "snap", "--path"])
+        .arg(&snapshot);
+    // The pallets only log their migrations at debug level
+    if std::env::var_os("RUST_LOG").is_none() {
+        command.env("RUST_LOG", "runtime=debug,try-runtime::cli=info");
+    }
+
+    let mut report = UpgradeReport::default();
+    let success = run_with_output(&mut command, |line| report.parse_line(line))?;
+
+    let mut shell = Shell::new();
+    println!();
+    report.print(&mut shell)?;
+
+    let errors = report.error_count();
+    if errors > 0 {
+        anyhow::bail!("the runtime upgrade failed with {} error(s)", errors);
+    }
+    if !success {
+        anyhow::bail!("the runtime upgrade failed");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const OUTPUT: &str = "2024-01-10 10:00:00 INFO try-runtime::cli: 🔨 building the externalities from the snapshot
+2024-01-10 10:00:01 INFO runtime::frame-support: ⚠️ TemplateModule declares internal migrations (which *might* execute). On-chain `StorageVersion(0)` vs in-code storage version `StorageVersion(1)`
+2024-01-10 10:00:01 INFO runtime::frame-support: ⚠️ Balances declares internal migrations (which *might* execute). On-chain `StorageVersion(0)` vs in-code storage version `StorageVersion(1)`
+2024-01-10 10:00:01 DEBUG runtime::frame-support: ✅ no migration for System
+2024-01-10 10:00:01 INFO runtime::template: TemplateModule migrated 3 entries, Weight { ref_time: 125000000, proof_size: 1_024 }
+2024-01-10 10:00:01 ERROR runtime::balances: Balances post_upgrade: total issuance changed
+2024-01-10 10:00:02 INFO try-runtime::cli: TryRuntime_on_runtime_upgrade executed without errors. Consumed weight = (ref_time: 250000000, proof_size: 2048), total weight = (ref_time: 2000000000000, proof_size: 5242880) (0.01 %, 0.04 %).
+";
+
+    #[test]
+    fn test_parse_upgrade_output() {
+        let mut report = UpgradeReport::default();
+        OUTPUT.lines().for_each(|line| report.parse_line(line));
+
+        assert_eq!(report.migrations.len(), 2);
+        let template = &report.migrations[0];
+        assert_eq!(template.pallet, "TemplateModule");
+        assert_eq!(template.on_chain_version, "0");
+        assert_eq!(template.new_version, "1");
+        assert_eq!(
+            template.weight,
+            Some(Weight {
+                ref_time: 125_000_000,
+                proof_size: 1024
+            })
+        );
+        assert!(template.errors.is_empty());
+
+        let balances = &report.migrations[1];
+        assert_eq!(balances.weight, None);
+        assert_eq!(balances.errors.len(), 1);
+
+        assert_eq!(
+            report.weight,
+            Some(Weight {
+                ref_time: 250_000_000,
+                proof_size: 2048
+            })
+        );
+        assert_eq!(report.error_count(), 1);
+    }
+}
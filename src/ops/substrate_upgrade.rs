@@ -0,0 +1,101 @@
+use std::fs;
+
+use anyhow::Context as _;
+
+use crate::{
+    core::{
+        runtime_source::{bump_runtime_version, construct_runtime_pallets},
+        runtime_wasm::{blake2_256, set_code_call, sudo_call, to_hex},
+        Shell,
+    },
+    ops::substrate_build::{build_chain, BuildTarget, ChainBuildOptions, ARTIFACTS_DIR},
+    util::{config::ChainInfo, to_snake_case, Config, SubstrateResult},
+};
+
+pub struct UpgradeOptions {
+    /// Also bump `transaction_version`, for upgrades that change existing extrinsics
+    pub transaction_version: bool,
+    /// Build the runtime in a pinned srtool container
+    pub deterministic: bool,
+}
+
+pub fn upgrade(opts: &UpgradeOptions, config: &Config, chain: &ChainInfo) -> SubstrateResult<()> {
+    let lib_path = config.cwd().join(&chain.runtime_path).join("src/lib.rs");
+    let content = fs::read_to_string(&lib_path)
+        .with_context(|| format!("couldn't read `{}`", lib_path.display()))?;
+
+    let mut fields = vec!["spec_version"];
+    if opts.transaction_version {
+        fields.push("transaction_version");
+    }
+    let (bumped, versions) = bump_runtime_version(&content, &fields)?;
+    fs::write(&lib_path, bumped)?;
+    for bump in &versions {
+        println!("Bumped {} from {} to {}", bump.field, bump.old, bump.new);
+    }
+    let spec_version = versions[0].new;
+    println!();
+
+    let build_opts = ChainBuildOptions {
+        target: BuildTarget::Runtime,
+        profile: "release".to_string(),
+        copy_artifacts: true,
+        deterministic: opts.deterministic,
+        verify: None,
+    };
+    let result = (|| {
+        let wasm = build_chain(&build_opts, config, chain).with_context(|| "the runtime didn't build")?;
+        let built_version = wasm.runtime_version()?.spec_version;
+        if built_version != spec_version {
+            anyhow::bail!(
+                "the built runtime has spec_version {}, expected {}",
+                built_version,
+                spec_version
+            );
+        }
+        Ok(wasm)
+    })();
+    // Restored on failure, so that retrying doesn't bump the version again
+    let wasm = match result {
+        Ok(wasm) => wasm,
+        Err(err) => {
+            fs::write(&lib_path, &content)
+                .with_context(|| format!("couldn't restore the runtime version in `{}`", lib_path.display()))?;
+            Shell::new().note(format!("restored the runtime version in `{}`", lib_path.display()))?;
+            return Err(err);
+        }
+    };
+
+    let pallets = construct_runtime_pallets(&fs::read_to_string(&lib_path)?)?;
+    let pallet_index = |name: &str| {
+        pallets
+            .iter()
+            .find(|pallet| pallet.name == name)
+            .map(|pallet| pallet.index)
+    };
+    let system_index = pallet_index("System")
+        .with_context(|| "no `System` pallet found in `construct_runtime!`")?;
+
+    let set_code = set_code_call(system_index, wasm.code());
+    let (call, description) = match pallet_index("Sudo") {
+        Some(sudo_index) => (sudo_call(sudo_index, &set_code), "sudo(system.setCode)"),
+        None => {
+            println!("\nNo `Sudo` pallet in the runtime, the call is left for governance to dispatch");
+            (set_code, "system.setCode")
+        }
+    };
+
+    let name = to_snake_case(chain.runtime_name.as_deref().unwrap_or("runtime"));
+    let call_path = config
+        .cwd()
+        .join(ARTIFACTS_DIR)
+        .join(format!("{}-v{}-upgrade.call", name, spec_version));
+    fs::write(&call_path, to_hex(&call))?;
+    let call_hash = to_hex(&blake2_256(&call));
+    fs::write(call_path.with_extension("call.hash"), &call_hash)?;
+
+    println!("\n{} call data written to `{}`", description, call_path.display());
+    println!("  call hash     {}", call_hash);
+
+    Ok(())
+}
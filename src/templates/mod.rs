@@ -1,29 +1,62 @@
-use std::path::PathBuf;
+use std::{fs, path::Path};
 
 use serde_derive::Deserialize;
 
-use crate::{core::manifest::Manifest, util::SubstrateResult};
+use crate::util::SubstrateResult;
+
+/// Substrate's weight template for pallets, used when benchmarking pallets of the chain.
+pub const FRAME_WEIGHT_TEMPLATE: &str = include_str!("bench/frame-weight-template.hbs");
+
+/// Files of a new local pallet, with their path inside the pallet's directory.
+pub const PALLET_TEMPLATE: [(&str, &str); 6] = [
+    ("Cargo.toml", include_str!("pallet/Cargo.toml.tpl")),
+    ("src/lib.rs", include_str!("pallet/lib.rs.tpl")),
+    ("src/mock.rs", include_str!("pallet/mock.rs.tpl")),
+    ("src/tests.rs", include_str!("pallet/tests.rs.tpl")),
+    ("src/benchmarking.rs", include_str!("pallet/benchmarking.rs.tpl")),
+    ("src/weights.rs", include_str!("pallet/weights.rs.tpl")),
+];
+
+/// Replaces the `{{name}}` placeholders of an embedded template.
+pub fn render(template: &str, values: &[(&str, &str)]) -> String {
+    values.iter().fold(template.to_string(), |rendered, (name, value)| {
+        rendered.replace(&format!("{{{{{}}}}}", name), value)
+    })
+}
 
 #[derive(Debug, Deserialize, PartialEq, Eq)]
 pub struct TemplateConfig {
     pub remote: String,
+    /// Branch to clone, defaults to the remote's default branch
+    #[serde(default)]
     pub branch: String,
+    /// Path of the template inside the repository, defaults to its root
+    #[serde(default)]
     pub template_path: String,
 }
 
-// TODO: Add support for custom templates
+/// Loads an embedded template config by name, or a custom one from the given `.toml` file.
 pub fn load_template_config(template_name: &str) -> SubstrateResult<TemplateConfig> {
      // Construct the path to the embedded config file
     let template_config_str = match template_name.to_lowercase().as_str() {
-        "substrate" => include_str!("chain/substrate.toml"),
-        "cumulus" => include_str!("chain/cumulus.toml"),
-        "frontier" => include_str!("chain/frontier.toml"),
-        "canvas" => include_str!("chain/canvas.toml"),
+        "substrate" => include_str!("chain/substrate.toml").to_string(),
+        "cumulus" => include_str!("chain/cumulus.toml").to_string(),
+        "frontier" => include_str!("chain/frontier.toml").to_string(),
+        "canvas" => include_str!("chain/canvas.toml").to_string(),
+        "erc20" => include_str!("contract/erc20.toml").to_string(),
+        "psp22" => include_str!("contract/psp22.toml").to_string(),
+        "erc721" => include_str!("contract/erc721.toml").to_string(),
+        "psp34" => include_str!("contract/psp34.toml").to_string(),
+        "multisig" => include_str!("contract/multisig.toml").to_string(),
+        "proxy" => include_str!("contract/proxy.toml").to_string(),
+        "substrate-front-end" => include_str!("frontend/substrate-front-end.toml").to_string(),
+        "papi" => include_str!("frontend/papi.toml").to_string(),
         // Add more cases for each config file
+        _ if Path::new(template_name).is_file() => fs::read_to_string(template_name)?,
         _ => anyhow::bail!("Invalid template name".to_string()),
     };
 
-    let template_config = toml_edit::de::from_str::<TemplateConfig>(template_config_str)?;
+    let template_config = toml_edit::de::from_str::<TemplateConfig>(&template_config_str)?;
 
     Ok(template_config)
 }
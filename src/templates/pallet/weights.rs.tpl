@@ -0,0 +1,51 @@
+//! Weights for {{mod_name}}
+//!
+//! Placeholder weights, regenerate them by benchmarking the pallet.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for {{mod_name}}.
+pub trait WeightInfo {
+	fn do_something() -> Weight;
+	fn cause_error() -> Weight;
+}
+
+/// Weights for {{mod_name}} using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: {{pallet_name}} Something (r:0 w:1)
+	/// Proof Skipped: {{pallet_name}} Something (max_values: Some(1), max_size: None, mode: Measured)
+	fn do_something() -> Weight {
+		Weight::from_parts(9_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: {{pallet_name}} Something (r:1 w:1)
+	/// Proof Skipped: {{pallet_name}} Something (max_values: Some(1), max_size: None, mode: Measured)
+	fn cause_error() -> Weight {
+		Weight::from_parts(6_000_000, 1489)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: {{pallet_name}} Something (r:0 w:1)
+	/// Proof Skipped: {{pallet_name}} Something (max_values: Some(1), max_size: None, mode: Measured)
+	fn do_something() -> Weight {
+		Weight::from_parts(9_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: {{pallet_name}} Something (r:1 w:1)
+	/// Proof Skipped: {{pallet_name}} Something (max_values: Some(1), max_size: None, mode: Measured)
+	fn cause_error() -> Weight {
+		Weight::from_parts(6_000_000, 1489)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
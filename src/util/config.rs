@@ -7,7 +7,7 @@ use std::{
     path::{Path, PathBuf},
     str::FromStr,
 };
-use toml_edit::{value, Document, Item, Table, Value};
+use toml_edit::{value, Document, Item, Table};
 
 use crate::core::manifest::Manifest;
 
@@ -17,14 +17,11 @@ fn find_field_recursive<'a>(table: &'a Table, field_path: &str) -> Option<&'a It
     let mut current = table;
 
     for field in field_path.split('.') {
-        if let Some(value) = current.get(field) {
-            if let Some(next_table) = value.as_table() {
-                current = next_table;
-            } else {
-                return Some(value);
-            }
+        let value = current.get(field)?;
+        if let Some(next_table) = value.as_table() {
+            current = next_table;
         } else {
-            return None;
+            return Some(value);
         }
     }
 
@@ -69,6 +66,105 @@ pub struct ChainInfo {
 #[derive(Debug, Clone)]
 pub struct ContractInfo {
     pub name: String,
+    /// Directory of the project's Cargo.toml, the workspace root in workspaces
+    pub path: PathBuf,
+    /// The contracts of the project, several when the project is a cargo workspace
+    pub contracts: Vec<ContractMember>,
+    pub workspace: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractMember {
+    pub name: String,
+    /// Directory of the contract, relative to the current directory
+    pub path: PathBuf,
+}
+
+/// The role of a crate in a chain's workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum ChainMemberKind {
+    Node,
+    Runtime,
+    Pallet,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainMember {
+    pub name: String,
+    /// Directory of the crate, relative to the current directory
+    pub path: PathBuf,
+    pub kind: ChainMemberKind,
+}
+
+impl ChainInfo {
+    /// The crates of the chain's workspace, node and runtime first, or only those two outside of a
+    /// workspace.
+    pub fn members(&self, cwd: &Path) -> SubstrateResult<Vec<ChainMember>> {
+        use cargo_util::paths::normalize_path;
+
+        let paths = match workspace_manifest(cwd) {
+            Some(manifest) => workspace_member_paths(cwd, &manifest)?,
+            None => vec![self.node_path.clone(), self.runtime_path.clone()],
+        };
+
+        let mut members = Vec::new();
+        for path in paths {
+            let Some(name) = get_package_name(&cwd.join(&path))? else {
+                continue;
+            };
+            let kind = if path == normalize_path(&self.node_path) {
+                ChainMemberKind::Node
+            } else if path == normalize_path(&self.runtime_path) {
+                ChainMemberKind::Runtime
+            } else if name.starts_with("pallet-") || path.starts_with("pallets") {
+                ChainMemberKind::Pallet
+            } else {
+                ChainMemberKind::Other
+            };
+            members.push(ChainMember { name, path, kind });
+        }
+        members.sort_by(|a, b| (a.kind, &a.name).cmp(&(b.kind, &b.name)));
+
+        Ok(members)
+    }
+}
+
+impl ContractInfo {
+    /// The contracts named in the comma separated `names`, all of them if empty.
+    pub fn select(&self, names: &str) -> SubstrateResult<Vec<ContractMember>> {
+        let names = names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect::<Vec<_>>();
+        if names.is_empty() {
+            return Ok(self.contracts.clone());
+        }
+
+        names
+            .into_iter()
+            .map(|name| self.contract(name).cloned())
+            .collect()
+    }
+
+    /// The contract called `name`, or the only contract of the project if `name` is empty.
+    pub fn contract(&self, name: &str) -> SubstrateResult<&ContractMember> {
+        if name.is_empty() && self.contracts.len() == 1 {
+            return Ok(&self.contracts[0]);
+        }
+
+        let names = self
+            .contracts
+            .iter()
+            .map(|contract| contract.name.as_str())
+            .collect::<Vec<_>>();
+        self.contracts
+            .iter()
+            .find(|contract| contract.name == name)
+            .with_context(|| format!("unknown contract `{}`, expected one of: {}", name, names.join(", ")))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -85,6 +181,14 @@ impl fmt::Display for ProjectType {
                 let runtime_name = info.runtime_name.as_deref().unwrap_or("unknown");
                 write!(f, "Chain: node - {}, runtime - {}", node_name, runtime_name)
             }
+            Self::Contract(info) if info.workspace => {
+                let names = info
+                    .contracts
+                    .iter()
+                    .map(|contract| contract.name.as_str())
+                    .collect::<Vec<_>>();
+                write!(f, "Contract workspace: {} ({})", info.name, names.join(", "))
+            }
             Self::Contract(info) => write!(f, "Contract: {}", info.name),
         }
     }
@@ -109,6 +213,125 @@ pub fn get_package_name(path: &Path) -> SubstrateResult<Option<String>> {
     Ok(name)
 }
 
+fn is_ink_contract(manifest: &Document) -> bool {
+    manifest
+        .get("dependencies")
+        .and_then(|dependencies| dependencies.as_table_like())
+        .is_some_and(|dependencies| {
+            dependencies
+                .iter()
+                .any(|(name, _)| name == "ink" || name == "ink_lang")
+        })
+}
+
+fn workspace_manifest(root: &Path) -> Option<Document> {
+    Manifest::new(root.join("Cargo.toml"))
+        .read_document()
+        .ok()
+        .filter(|manifest| manifest.get("workspace").is_some())
+}
+
+/// Expands the `members` globs of the workspace at `root`, without the `exclude`d paths.
+///
+/// The returned paths are relative to `root`.
+fn workspace_member_paths(root: &Path, manifest: &Document) -> SubstrateResult<Vec<PathBuf>> {
+    let patterns = |key: &str| {
+        manifest["workspace"]
+            .get(key)
+            .and_then(|patterns| patterns.as_array())
+            .map(|patterns| {
+                patterns
+                    .iter()
+                    .filter_map(|pattern| pattern.as_str())
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+    let excluded = patterns("exclude");
+
+    let mut paths = Vec::new();
+    for pattern in patterns("members") {
+        let pattern = format!("{}/{}", glob::Pattern::escape(&root.to_string_lossy()), pattern);
+        for member_path in glob::glob(&pattern)?.flatten() {
+            let Ok(relative_path) = member_path.strip_prefix(root) else {
+                continue;
+            };
+            if excluded.iter().any(|excluded| relative_path == Path::new(excluded)) {
+                continue;
+            }
+            if member_path.join("Cargo.toml").exists() {
+                paths.push(relative_path.to_path_buf());
+            }
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Reads the contract at `contract_path`, or the ink! contracts of the workspace there.
+fn get_contract_info(cwd: &Path, contract_path: &Path) -> SubstrateResult<ContractInfo> {
+    let root = cwd.join(contract_path);
+    let Some(manifest) = workspace_manifest(&root) else {
+        let name = get_package_name(&root)?.with_context(|| "no contract found in the current directory")?;
+        return Ok(ContractInfo {
+            name: name.clone(),
+            path: contract_path.to_path_buf(),
+            contracts: vec![ContractMember {
+                name,
+                path: contract_path.to_path_buf(),
+            }],
+            workspace: false,
+        });
+    };
+
+    let mut contracts = Vec::new();
+    if manifest.get("package").is_some() && is_ink_contract(&manifest) {
+        if let Some(name) = get_package_name(&root)? {
+            contracts.push(ContractMember {
+                name,
+                path: contract_path.to_path_buf(),
+            });
+        }
+    }
+
+    for relative_path in workspace_member_paths(&root, &manifest)? {
+        let member_path = root.join(&relative_path);
+        let is_contract = Manifest::new(member_path.join("Cargo.toml"))
+            .read_document()
+            .is_ok_and(|member| is_ink_contract(&member));
+        if let (true, Some(name)) = (is_contract, get_package_name(&member_path)?) {
+            contracts.push(ContractMember {
+                name,
+                path: contract_path.join(relative_path),
+            });
+        }
+    }
+    if contracts.is_empty() {
+        anyhow::bail!("no ink! contracts found in the workspace `{}`", root.display());
+    }
+    contracts.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let package_name = manifest
+        .get("package")
+        .and_then(|package| package.get("name"))
+        .and_then(|name| name.as_str());
+    let name = match package_name {
+        Some(name) => name.to_string(),
+        None => cargo_util::paths::normalize_path(&root)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "workspace".to_string()),
+    };
+
+    Ok(ContractInfo {
+        name,
+        path: contract_path.to_path_buf(),
+        contracts,
+        workspace: true,
+    })
+}
+
 // TODO: Move `inquire` related code to the bin module
 fn deduce_project_type_for_wd(
     cwd: &Path,
@@ -136,7 +359,9 @@ fn deduce_project_type_for_wd(
             runtime_name,
             frontend_path: PathBuf::from(frontend_path),
         })));
-    } else if cwd.join("lib.rs").exists() {
+    } else if cwd.join("lib.rs").exists()
+        || get_contract_info(cwd, Path::new("")).is_ok_and(|info| info.workspace)
+    {
         if Confirm::new("Found a potential smart contract project in the current directory. Do you want to continue? (y/n)").prompt()? {
                 let mut manifest = Manifest::new(cwd.join("Substrate.toml"));
                 let mut document = Document::new();
@@ -146,18 +371,10 @@ fn deduce_project_type_for_wd(
                 return Ok(None);
             }
 
-        // Get contract name from manifest file
-        let contract_manifest = Manifest::new(cwd.join("Cargo.toml")).read_document()?;
-        let contract_name = contract_manifest["package"]
-            .get("name")
-            .with_context(|| "no name found in contract manifest")?
-            .as_str()
-            .with_context(|| "unknown name type in contract manifest")?
-            .to_string();
-
-        return Ok(Some(ProjectType::Contract(ContractInfo {
-            name: contract_name,
-        })));
+        return Ok(Some(ProjectType::Contract(get_contract_info(
+            cwd,
+            Path::new(""),
+        )?)));
     }
 
     Ok(None)
@@ -208,12 +425,7 @@ impl Config {
                         frontend_path,
                     }))
                 }
-                Some("contract") => {
-                    let name = get_package_name(&cwd.join(contract_path))?;
-                    Some(ProjectType::Contract(ContractInfo {
-                        name: name.unwrap(),
-                    }))
-                }
+                Some("contract") => Some(ProjectType::Contract(get_contract_info(&cwd, &contract_path)?)),
                 _ => anyhow::bail!("Incorrect project type in Substrate.toml.\nSupported types: \"chain\" and \"contract\"."),
             },
             None => deduce_project_type_for_wd(&cwd, &node_path, &runtime_path, &frontend_path)?,
@@ -222,7 +434,7 @@ impl Config {
         Ok(Self::new(cwd, project_type))
     }
 
-    /// Gets a reference to the shell, e.g., for writing error messages.
+    // Gets a reference to the shell, e.g., for writing error messages.
     // pub fn shell(&self) -> RefMut<'_, Shell> {
     //     self.shell.borrow_mut()
     // }
@@ -237,3 +449,86 @@ impl Config {
         &self.project_type
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_contract_workspace_members() {
+        let root = env::temp_dir().join("substrate-manager-test-contract-workspace");
+        let _ = fs::remove_dir_all(&root);
+        for (dir, dependency) in [("erc20", "ink"), ("flipper", "ink"), ("shared", "scale")] {
+            fs::create_dir_all(root.join("contracts").join(dir)).unwrap();
+            fs::write(
+                root.join("contracts").join(dir).join("Cargo.toml"),
+                format!("[package]\nname = \"{}\"\n\n[dependencies]\n{} = \"4\"\n", dir, dependency),
+            )
+            .unwrap();
+        }
+        fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"contracts/*\"]\n").unwrap();
+
+        let info = get_contract_info(&root, Path::new("")).unwrap();
+        assert!(info.workspace);
+        assert_eq!(
+            info.contracts,
+            [
+                ContractMember { name: "erc20".into(), path: "contracts/erc20".into() },
+                ContractMember { name: "flipper".into(), path: "contracts/flipper".into() },
+            ]
+        );
+        assert_eq!(info.select("flipper").unwrap(), [info.contracts[1].clone()]);
+        assert_eq!(info.select("").unwrap().len(), 2);
+        assert!(info.contract("").is_err());
+        assert!(info.select("shared").is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_chain_members() {
+        let root = env::temp_dir().join("substrate-manager-test-chain-members");
+        let _ = fs::remove_dir_all(&root);
+        for (dir, name) in [
+            ("node", "demo-node"),
+            ("runtime", "demo-runtime"),
+            ("pallets/template", "pallet-template"),
+            ("primitives", "demo-primitives"),
+        ] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+            fs::write(root.join(dir).join("Cargo.toml"), format!("[package]\nname = \"{}\"\n", name))
+                .unwrap();
+        }
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"primitives\", \"pallets/*\", \"runtime\", \"node\"]\n",
+        )
+        .unwrap();
+
+        let info = ChainInfo {
+            node_path: "./node".into(),
+            node_name: None,
+            runtime_path: "runtime".into(),
+            runtime_name: None,
+            frontend_path: "frontend".into(),
+        };
+        let members = info
+            .members(&root)
+            .unwrap()
+            .into_iter()
+            .map(|member| (member.name, member.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            members,
+            [
+                ("demo-node".to_string(), ChainMemberKind::Node),
+                ("demo-runtime".to_string(), ChainMemberKind::Runtime),
+                ("pallet-template".to_string(), ChainMemberKind::Pallet),
+                ("demo-primitives".to_string(), ChainMemberKind::Other),
+            ]
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,6 +1,7 @@
 use core::fmt;
 
 use anyhow::{anyhow, Error};
+use cargo_util::ProcessError;
 use color_eyre::Report;
 
 pub type SubstrateResult<T> = anyhow::Result<T>;
@@ -143,9 +144,17 @@ impl CliError {
     }
 }
 
+/// The exit code of a failed subprocess (e.g. `cargo-contract`) in the error chain, if any.
+fn process_exit_code(err: &anyhow::Error) -> Option<i32> {
+    err.chain()
+        .find_map(|err| err.downcast_ref::<ProcessError>())
+        .and_then(|err| err.code)
+}
+
 impl From<anyhow::Error> for CliError {
     fn from(err: anyhow::Error) -> CliError {
-        CliError::new(err, 101)
+        let code = process_exit_code(&err).unwrap_or(101);
+        CliError::new(err, code)
     }
 }
 
@@ -164,9 +173,14 @@ impl From<std::io::Error> for CliError {
 
 impl From<Report> for CliError {
     fn from(err: color_eyre::Report) -> CliError {
+        // Errors from the lib side are wrapped with `eyre!(e)` by the commands
+        let exit_code = err
+            .downcast_ref::<anyhow::Error>()
+            .and_then(process_exit_code)
+            .unwrap_or(1);
         CliError {
             error: Some(anyhow!(err)),
-            exit_code: 1,
+            exit_code,
         }
     }
 }
@@ -6,7 +6,9 @@ pub use self::errors::{CliError, CliResult, SubstrateResult};
 pub mod command_prelude;
 pub mod config;
 pub mod errors;
+pub mod process;
 pub mod restricted_names;
+pub mod secret_file;
 
 pub fn normalize_paths(root_path: &Path, path: &Path) -> color_eyre::eyre::Result<PathBuf> {
     let canonical_parent = if let Some(parent) = path.parent() {
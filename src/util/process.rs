@@ -0,0 +1,221 @@
+//! Helpers for running and supervising several long-lived child processes,
+//! such as the nodes of a local network.
+
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Once,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+
+use crate::util::SubstrateResult;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static INSTALL_HANDLER: Once = Once::new();
+
+/// How long a child gets to exit after being asked to terminate, before it is killed.
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Installs a Ctrl-C handler (once per process) that flags the interruption
+/// instead of terminating immediately.
+fn install_interrupt_handler() {
+    INSTALL_HANDLER.call_once(|| {
+        if let Err(e) = ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst)) {
+            log::debug!("failed to install Ctrl-C handler: {}", e);
+        }
+    });
+}
+
+/// Returns `true` if Ctrl-C has been pressed since the handler was installed.
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Forwards every line of `stream` to stdout, prefixed with `prefix`.
+fn forward_lines<R: Read + Send + 'static>(prefix: String, stream: R) {
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            println!("{} {}", prefix, line);
+        }
+    });
+}
+
+/// Runs `command` with its stdout and stderr merged, so that their lines stay in order, streaming
+/// the output and passing each line to `on_line`.
+///
+/// Returns whether the command succeeded.
+pub fn run_with_output(command: &mut Command, mut on_line: impl FnMut(&str)) -> SubstrateResult<bool> {
+    let (reader, writer) = io::pipe()?;
+    let mut child = command
+        .stdout(writer.try_clone()?)
+        .stderr(writer)
+        .spawn()?;
+    // Drop the command's copies of the pipe's writer, or reading would never end
+    command.stdout(Stdio::null()).stderr(Stdio::null());
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        println!("{}", line);
+        on_line(&line);
+    }
+
+    Ok(child.wait()?.success())
+}
+
+/// A named child process.
+struct Member {
+    name: String,
+    child: Child,
+}
+
+/// A group of child processes that are started together and shut down together.
+///
+/// The output of each process is streamed to stdout, prefixed with its name.
+/// When the group is dropped, all processes that are still running are terminated.
+pub struct ProcessGroup {
+    members: Vec<Member>,
+    prefix_width: usize,
+}
+
+impl ProcessGroup {
+    /// Creates an empty group, aligning log prefixes to `prefix_width` characters.
+    pub fn new(prefix_width: usize) -> Self {
+        install_interrupt_handler();
+        Self {
+            members: Vec::new(),
+            prefix_width,
+        }
+    }
+
+    /// Spawns `command` as a member of the group under the given `name`.
+    pub fn spawn(&mut self, name: &str, command: &mut Command) -> SubstrateResult<()> {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+        // Put children in their own process group so that Ctrl-C only reaches us,
+        // and we decide in which order they are stopped.
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(command, 0);
+
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("failed to start `{}`", name))?;
+
+        let prefix = format!("{:>width$} |", name, width = self.prefix_width);
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(prefix.clone(), stdout);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(prefix, stderr);
+        }
+
+        self.members.push(Member {
+            name: name.to_string(),
+            child,
+        });
+
+        Ok(())
+    }
+
+    /// Spawns `command` as a member of the group, discarding its output.
+    pub fn spawn_quiet(&mut self, name: &str, command: &mut Command) -> SubstrateResult<()> {
+        command.stdout(Stdio::null()).stderr(Stdio::null());
+
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(command, 0);
+
+        let child = command
+            .spawn()
+            .with_context(|| format!("failed to start `{}`", name))?;
+        self.members.push(Member {
+            name: name.to_string(),
+            child,
+        });
+
+        Ok(())
+    }
+
+    /// Blocks until Ctrl-C is pressed or one of the members exits, then shuts
+    /// the whole group down.
+    ///
+    /// Returns an error if a member exited on its own with a failure status.
+    pub fn wait(&mut self) -> SubstrateResult<()> {
+        let mut failure = None;
+
+        'supervise: while !is_interrupted() {
+            for member in self.members.iter_mut() {
+                if let Some(status) = member.child.try_wait()? {
+                    if !status.success() {
+                        failure = Some(format!("`{}` exited with {}", member.name, status));
+                    } else {
+                        println!("\n`{}` exited, stopping the remaining processes...", member.name);
+                    }
+                    break 'supervise;
+                }
+            }
+            thread::sleep(Duration::from_millis(200));
+        }
+
+        if is_interrupted() {
+            println!("\nStopping {} processes...", self.members.len());
+        }
+        self.shutdown();
+
+        match failure {
+            Some(failure) => anyhow::bail!(failure),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns an error if one of the members already exited, e.g. while waiting for it to be ready.
+    pub fn ensure_running(&mut self) -> SubstrateResult<()> {
+        for member in self.members.iter_mut() {
+            if let Some(status) = member.child.try_wait()? {
+                anyhow::bail!("`{}` exited with {}", member.name, status);
+            }
+        }
+        Ok(())
+    }
+
+    /// Terminates every member that is still running, in reverse start order.
+    pub fn shutdown(&mut self) {
+        while let Some(mut member) = self.members.pop() {
+            if let Ok(Some(_)) = member.child.try_wait() {
+                continue;
+            }
+            terminate(&mut member.child);
+        }
+    }
+}
+
+impl Drop for ProcessGroup {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Asks `child` to terminate gracefully, killing it if it doesn't exit in time.
+fn terminate(child: &mut Child) -> Option<ExitStatus> {
+    #[cfg(unix)]
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, libc::SIGTERM);
+    }
+    #[cfg(not(unix))]
+    let _ = child.kill();
+
+    let started = Instant::now();
+    while started.elapsed() < SHUTDOWN_GRACE_PERIOD {
+        if let Ok(Some(status)) = child.try_wait() {
+            return Some(status);
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    let _ = child.kill();
+    child.wait().ok()
+}
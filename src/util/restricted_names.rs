@@ -46,7 +46,7 @@ pub fn is_conflicting_artifact_name(name: &str) -> bool {
 pub fn validate_package_name(name: &str, what: &str, help: &str) -> SubstrateResult<()> {
     let mut chars = name.chars();
     if let Some(ch) = chars.next() {
-        if ch.is_digit(10) {
+        if ch.is_ascii_digit() {
             // A specific error for a potentially common case.
             bail!(
                 "the name `{}` cannot be used as a {}, \
@@ -0,0 +1,51 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+use anyhow::Context as _;
+
+use crate::util::SubstrateResult;
+
+/// A secret URI written to a file only readable by the current user, removed when dropped.
+///
+/// The node's `key` commands and cargo-contract read the URI from the file that `--suri` names,
+/// which keeps the secret out of their command lines, readable by any local user.
+pub struct SecretFile {
+    path: PathBuf,
+    uri: String,
+}
+
+impl SecretFile {
+    pub fn new(path: PathBuf, secret: &str) -> SubstrateResult<Self> {
+        let uri = path
+            .to_str()
+            .with_context(|| format!("`{}` isn't valid UTF-8", path.display()))?
+            .to_string();
+        fs::remove_file(&path).ok();
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let secret_file = Self { path, uri };
+        options
+            .open(&secret_file.path)
+            .and_then(|mut file| file.write_all(secret.as_bytes()))
+            .with_context(|| format!("couldn't write `{}`", secret_file.path.display()))?;
+
+        Ok(secret_file)
+    }
+
+    /// The path of the file, to pass where the secret URI is expected.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+}
+
+impl Drop for SecretFile {
+    fn drop(&mut self) {
+        fs::remove_file(&self.path).ok();
+    }
+}